@@ -1,5 +1,5 @@
-use cgmath::Matrix4;
-use super::{Projection, View};
+use cgmath::{Matrix4, Point3, Vector3, Vector4};
+use super::{CameraPreset, Projection, View};
 
 /// This is a matrix used to convert a ViewProjection matrix in OpenGL format
 ///   to one that is in WGPU format.
@@ -29,15 +29,229 @@ impl Camera {
     /// Get a reference to the Projection object.
     pub fn get_projection(&self) -> &Projection { &self.projection }
 
+    /// Get a mutable reference to the Projection object.
+    pub fn get_projection_mut(&mut self) -> &mut Projection { &mut self.projection }
+
     /// Get a reference to the View object.
     pub fn get_view(&self) -> &View { &self.view }
 
     /// Set the View object.
     pub fn set_view(&mut self, view: View) { self.view = view; }
 
+    /// Create a new perspective Camera object from a CameraPreset.
+    ///
+    /// # Arguments
+    ///
+    /// * `preset` - The viewpoint of the Camera.
+    /// * `aspect` - The aspect ratio of the screen.
+    pub fn from_preset(preset: &CameraPreset, aspect: f32) -> Self {
+        let mut camera = Camera::new(View::default(), Projection::with_aspect(aspect));
+        camera.apply_preset(preset);
+        return camera
+    }
+
+    /// Get the current viewpoint of the Camera as a CameraPreset.
+    pub fn to_preset(&self) -> CameraPreset {
+        return CameraPreset {
+            eye: self.view.get_position().into(),
+            target: self.view.get_target().into(),
+            up: self.view.get_up().into(),
+            fov_y: self.projection.fov_y.0,
+        }
+    }
+
+    /// Move the Camera to the viewpoint of a CameraPreset. The rest of the Projection is preserved.
+    ///   A preset whose position coincides with its target is ignored.
+    pub fn apply_preset(&mut self, preset: &CameraPreset) {
+        use cgmath::Point3;
+        use super::view::orthogonal_up;
+
+        let eye = Point3::from(preset.eye);
+        let target = Point3::from(preset.target);
+        if eye == target {
+            return
+        }
+        self.view = View::new(eye, target, orthogonal_up(target - eye, preset.up.into()));
+        self.projection.set_fov(cgmath::Deg(preset.fov_y));
+    }
+
+    /// Move the Camera so that an axis-aligned box is entirely visible.
+    ///   The Camera keeps its viewing direction and looks at the center of the box.
+    ///   The far plane is pushed back if the box would reach past it.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - The corner of the box with the smallest coordinates.
+    /// * `max` - The corner of the box with the largest coordinates.
+    pub fn frame_bounds(&mut self, min: Vector3<f32>, max: Vector3<f32>) {
+        use cgmath::{EuclideanSpace, InnerSpace, Point3};
+        use super::{projection::DEFAULT_Z_NEAR, ProjectionMode};
+
+        // The sphere bounding the box. A degenerate (single point) box is given a small radius.
+        let center = Point3::from_vec((min + max) / 2.0);
+        let radius = ((max - min).magnitude() / 2.0).max(DEFAULT_Z_NEAR);
+
+        let distance = framing_distance(radius, self.projection.fov_y, self.projection.aspect);
+        if self.projection.get_mode() == ProjectionMode::Orthographic {
+            // The width of the viewing volume is the height times the aspect ratio.
+            self.projection.set_height(radius * 2.0 / self.projection.aspect.min(1.0));
+        }
+        if distance + radius > self.projection.z_far {
+            self.projection.set_z_planes(self.projection.z_near, distance + radius);
+        }
+
+        let forward = self.view.get_forward();
+        self.view = View::new(center - forward * distance, center, self.view.get_up());
+    }
+
+    /// Reset the View and Projection objects to their defaults, preserving the aspect ratio.
+    pub fn reset_view(&mut self) {
+        self.view = View::default();
+        self.projection = Projection::with_aspect(self.projection.aspect);
+    }
+
     /// Build the View-Projection matrix describing the current Camera.
     ///   Uses the WGPU format -- NOT the OpenGL format.
     pub fn build_view_projection_matrix(&self) -> Matrix4<f32> {
         return OPENGL_TO_WGPU_MATRIX * self.projection.as_matrix() * self.view.as_matrix();
     }
+
+    /// Compute the ray through a position on the screen, e.g. to find what is under the cursor.
+    ///
+    /// # Arguments
+    ///
+    /// * `x`      - The horizontal position on the screen, in pixels from the left edge.
+    /// * `y`      - The vertical position on the screen, in pixels from the top edge.
+    /// * `width`  - The width of the screen, in pixels.
+    /// * `height` - The height of the screen, in pixels.
+    ///
+    /// # Returns
+    ///
+    /// The (origin, direction) of the ray in world space, starting on the near plane with a unit direction.
+    ///   None if the View-Projection matrix cannot be inverted.
+    pub fn screen_ray(&self, x: f32, y: f32, width: f32, height: f32) -> Option<(Point3<f32>, Vector3<f32>)> {
+        use cgmath::{InnerSpace, SquareMatrix};
+
+        // Normalized device coordinates: Y points up, and WGPU depth ranges from 0 (near) to 1 (far).
+        let ndc_x = 2.0 * x / width - 1.0;
+        let ndc_y = 1.0 - 2.0 * y / height;
+        let inverse = self.build_view_projection_matrix().invert()?;
+        let unproject = |depth: f32| {
+            let point = inverse * Vector4::new(ndc_x, ndc_y, depth, 1.0);
+            Point3::from_homogeneous(point)
+        };
+        let near = unproject(0.0);
+        let far = unproject(1.0);
+        return Some((near, (far - near).normalize()))
+    }
+
+    /// Compute the planes (left, right, bottom, top, near, far) of the viewing frustum.
+    ///   Each plane is `(a, b, c, d)` with a unit normal `(a, b, c)` pointing into the frustum,
+    ///   so a point `p` is inside the plane when `a*p.x + b*p.y + c*p.z + d >= 0`.
+    pub fn frustum_planes(&self) -> [Vector4<f32>; 6] {
+        use cgmath::{InnerSpace, Matrix};
+
+        // WGPU clip space has a depth range of [0, w] (rather than OpenGL's [-w, w]).
+        let m = self.build_view_projection_matrix();
+        let (row_x, row_y, row_z, row_w) = (m.row(0), m.row(1), m.row(2), m.row(3));
+        let normalize = |plane: Vector4<f32>| plane / plane.truncate().magnitude();
+        return [
+            normalize(row_w + row_x),
+            normalize(row_w - row_x),
+            normalize(row_w + row_y),
+            normalize(row_w - row_y),
+            normalize(row_z),
+            normalize(row_w - row_z),
+        ]
+    }
+}
+
+/// The distance at which a sphere fits within both the vertical and the horizontal field of view.
+///
+/// # Arguments
+///
+/// * `radius` - The radius of the sphere.
+/// * `fov_y`  - The vertical Field of View.
+/// * `aspect` - The aspect ratio of the screen.
+fn framing_distance(radius: f32, fov_y: cgmath::Deg<f32>, aspect: f32) -> f32 {
+    let half_fov_y = cgmath::Rad::from(fov_y).0 / 2.0;
+    let half_fov_x = (half_fov_y.tan() * aspect).atan();
+    return radius / half_fov_y.min(half_fov_x).sin()
+}
+
+/// Whether a sphere is (at least partially) inside a viewing frustum.
+///
+/// # Arguments
+///
+/// * `planes` - The planes of the frustum (see `Camera::frustum_planes`).
+/// * `center` - The center of the sphere.
+/// * `radius` - The radius of the sphere.
+pub fn sphere_in_frustum(planes: &[Vector4<f32>; 6], center: Vector3<f32>, radius: f32) -> bool {
+    use cgmath::InnerSpace;
+    return planes.iter().all(|plane| plane.dot(center.extend(1.0)) >= -radius)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Deg;
+    use super::*;
+
+    /// Whether every corner of a box is inside the viewing volume of a Camera.
+    fn box_is_visible(camera: &Camera, min: Vector3<f32>, max: Vector3<f32>) -> bool {
+        let view_projection = camera.build_view_projection_matrix();
+        return (0..8).all(|corner| {
+            let x = if corner & 1 == 0 { min.x } else { max.x };
+            let y = if corner & 2 == 0 { min.y } else { max.y };
+            let z = if corner & 4 == 0 { min.z } else { max.z };
+            let clip = view_projection * Vector4::new(x, y, z, 1.0);
+            let ndc = clip.truncate() / clip.w;
+            clip.w > 0.0 && ndc.x.abs() <= 1.0 && ndc.y.abs() <= 1.0 && ndc.z >= 0.0 && ndc.z <= 1.0
+        })
+    }
+
+    #[test]
+    fn points_behind_the_camera_are_culled() {
+        use cgmath::EuclideanSpace;
+
+        let camera = Camera::new(View::default(), Projection::with_aspect(1.5));
+        let planes = camera.frustum_planes();
+        let eye = camera.get_view().get_position().to_vec();
+        let forward = camera.get_view().get_forward();
+        assert!(!sphere_in_frustum(&planes, eye - forward * 5.0, 0.0));
+        assert!(sphere_in_frustum(&planes, eye + forward * 5.0, 0.0));
+    }
+
+    #[test]
+    fn framing_distance_uses_the_narrower_field_of_view() {
+        let wide = framing_distance(1.0, Deg(90.0), 2.0);
+        let tall = framing_distance(1.0, Deg(90.0), 0.5);
+        assert!((wide - 2.0_f32.sqrt()).abs() < 1e-5, "{}", wide);
+        assert!(tall > wide, "{} <= {}", tall, wide);
+    }
+
+    #[test]
+    fn framed_box_is_visible_in_a_portrait_window() {
+        let (min, max) = (Vector3::new(-4.0, -1.0, -1.0), Vector3::new(4.0, 1.0, 1.0));
+        let mut camera = Camera::new(View::default(), Projection::with_aspect(0.5));
+        camera.frame_bounds(min, max);
+        assert!(box_is_visible(&camera, min, max));
+    }
+
+    #[test]
+    fn framed_box_is_visible_in_an_orthographic_portrait_window() {
+        let (min, max) = (Vector3::new(-4.0, -1.0, -1.0), Vector3::new(4.0, 1.0, 1.0));
+        let mut camera = Camera::new(View::default(), Projection::orthographic(0.5, 1.0, 0.1, 100.0));
+        camera.frame_bounds(min, max);
+        assert!(box_is_visible(&camera, min, max));
+    }
+
+    #[test]
+    fn framing_a_large_box_pushes_back_the_far_plane() {
+        let (min, max) = (Vector3::new(-500.0, -500.0, -500.0), Vector3::new(500.0, 500.0, 500.0));
+        let mut camera = Camera::new(View::default(), Projection::with_aspect(1.5));
+        camera.frame_bounds(min, max);
+        assert!(camera.get_projection().z_far > 100.0);
+        assert!(box_is_visible(&camera, min, max));
+    }
 }
@@ -1,9 +1,56 @@
-use cgmath::Deg;
-use winit::event::{ElementState, KeyboardInput, VirtualKeyCode, WindowEvent};
-use super::Camera;
+use cgmath::{Deg, InnerSpace};
+use winit::{
+    dpi::PhysicalPosition,
+    event::{ElementState, KeyboardInput, MouseButton, MouseScrollDelta, Touch, TouchPhase, WindowEvent},
+};
+use crate::input::{Action, KeyBindings};
+use super::{projection::DEFAULT_Z_NEAR, Camera, ProjectionMode, View};
 
+/// The default speed (distance per second) of the Camera while a movement key is held.
+pub const DEFAULT_MOVE_SPEED: f32 = 18.0;
 
-/// Holds information about whether Camera controlling keys are being pressed.
+/// The default angular speed (degrees per second) of the Camera while a rotation key is held.
+pub const DEFAULT_ROTATE_SPEED: f32 = 360.0;
+
+/// The default factor that scrolling is scaled by.
+pub const DEFAULT_ZOOM_SPEED: f32 = 1.0;
+
+/// The rotation of the Camera (in degrees) per pixel the cursor is dragged.
+const DRAG_SENSITIVITY: f32 = 0.25;
+
+/// The distance the Camera pans per pixel the cursor is dragged.
+const PAN_SENSITIVITY: f32 = 0.05;
+
+/// The framerate that the smoothing factor is defined at.
+const SMOOTHING_FPS: f32 = 60.0;
+
+/// The distance below which a smoothed Camera is considered to have reached its target View.
+const CONVERGENCE_EPSILON: f32 = 1e-4;
+
+/// The radial distance the Camera moves toward the target per line scrolled.
+const LINE_SCROLL_SPEED: f32 = 1.0;
+
+/// The radial distance the Camera moves toward the target per pixel scrolled (e.g. on touchpads).
+const PIXEL_SCROLL_SPEED: f32 = 0.05;
+
+/// The radial distance the Camera is moved per pixel that two fingers are pinched apart.
+const PINCH_SPEED: f32 = 0.05;
+
+
+/// The way in which the CameraController moves the Camera.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CameraMode {
+
+    // The Camera orbits about its target. W/S and A/D rotate the Camera and LShift/LControl zoom.
+    Orbit,
+
+    // The Camera flies freely through the scene. W/S move forward and backward, A/D strafe,
+    //   LShift/LControl move up and down, and dragging the cursor turns the Camera.
+    FreeFly,
+}
+
+/// Holds information about whether Camera controlling keys are being pressed,
+///   and whether the Camera is being dragged with the mouse.
 pub struct CameraController {
 
     is_up_pressed: bool,
@@ -17,6 +64,60 @@ pub struct CameraController {
     
     is_cw_pressed: bool,
     is_ccw_pressed: bool,
+
+    // The direction of the (yaw, pitch, radial) axes requested by an analog input such as a gamepad,
+    //   each in the range [-1, 1]. This is added to the direction requested by the pressed keys.
+    analog_directions: (f32, f32, f32),
+
+    // The time (in seconds) taken to ramp up to full speed while a key is held.
+    // If None, the Camera moves at a constant speed.
+    ramp_time: Option<f32>,
+
+    // The current signed velocity of each axis (yaw, pitch, roll, radial), in the range [-1, 1].
+    velocity: [f32; 4],
+
+    // Whether the left mouse button is held, i.e. whether the Camera is orbited by dragging.
+    is_dragging: bool,
+
+    // The last known position of the cursor within the window.
+    cursor_position: Option<PhysicalPosition<f64>>,
+
+    // The (x, y) distance in pixels the cursor was dragged since the last update.
+    drag_delta: (f32, f32),
+
+    // Whether the middle mouse button is held, i.e. whether the Camera is panned by dragging.
+    is_panning: bool,
+
+    // The (x, y) distance in pixels the cursor was dragged for panning since the last update.
+    pan_delta: (f32, f32),
+
+    // The radial distance to move toward the target, scrolled since the last update.
+    scroll_delta: f32,
+
+    // The ID and last known position of each finger touching the screen, in the order they touched it.
+    touches: Vec<(u64, PhysicalPosition<f64>)>,
+
+    // The fraction of the remaining distance to the target View that is kept every 1/60th of a second.
+    // If 0, the Camera jumps straight to the target View.
+    smoothing: f32,
+
+    // The View that the Camera is moving toward, if it has not yet been reached.
+    target_view: Option<View>,
+
+    // The way in which the Camera is moved.
+    mode: CameraMode,
+
+    // The speed (distance per second) of the Camera while a movement key is held.
+    move_speed: f32,
+
+    // The angular speed (degrees per second) of the Camera while a rotation key is held.
+    rotate_speed: f32,
+
+    // The factor that scrolling is scaled by.
+    zoom_speed: f32,
+
+    // The keys that trigger each Action.
+    key_bindings: KeyBindings,
 }
 
 impl CameraController {
@@ -32,9 +133,151 @@ impl CameraController {
             is_backward_pressed: false,
             is_cw_pressed: false,
             is_ccw_pressed: false,
+            analog_directions: (0.0, 0.0, 0.0),
+            ramp_time: None,
+            velocity: [0.0; 4],
+            is_dragging: false,
+            cursor_position: None,
+            drag_delta: (0.0, 0.0),
+            is_panning: false,
+            pan_delta: (0.0, 0.0),
+            scroll_delta: 0.0,
+            touches: Vec::new(),
+            smoothing: 0.0,
+            target_view: None,
+            mode: CameraMode::Orbit,
+            move_speed: DEFAULT_MOVE_SPEED,
+            rotate_speed: DEFAULT_ROTATE_SPEED,
+            zoom_speed: DEFAULT_ZOOM_SPEED,
+            key_bindings: KeyBindings::default(),
         }
     }
 
+    /// Set the speed of the Camera while a movement key is held. Defaults to `DEFAULT_MOVE_SPEED`.
+    ///   Returns the CameraController, so the speeds can be set in a chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed` - The distance per second.
+    pub fn set_move_speed(&mut self, speed: f32) -> &mut Self {
+        self.move_speed = speed.max(0.0);
+        return self
+    }
+
+    /// Set the angular speed of the Camera while a rotation key is held. Defaults to `DEFAULT_ROTATE_SPEED`.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed` - The rotation in degrees per second.
+    pub fn set_rotate_speed(&mut self, speed: f32) -> &mut Self {
+        self.rotate_speed = speed.max(0.0);
+        return self
+    }
+
+    /// Set the factor that scrolling is scaled by. Defaults to `DEFAULT_ZOOM_SPEED`.
+    ///
+    /// # Arguments
+    ///
+    /// * `speed` - The factor, where values below 1 zoom more precisely.
+    pub fn set_zoom_speed(&mut self, speed: f32) -> &mut Self {
+        self.zoom_speed = speed.max(0.0);
+        return self
+    }
+
+    /// Get the speed (distance per second) of the Camera while a movement key is held.
+    pub fn get_move_speed(&self) -> f32 { self.move_speed }
+
+    /// Get the angular speed (degrees per second) of the Camera while a rotation key is held.
+    pub fn get_rotate_speed(&self) -> f32 { self.rotate_speed }
+
+    /// Get the factor that scrolling is scaled by.
+    pub fn get_zoom_speed(&self) -> f32 { self.zoom_speed }
+
+    /// Get the keys that trigger each Action.
+    pub fn get_key_bindings(&self) -> &KeyBindings { &self.key_bindings }
+
+    /// Get the keys that trigger each Action, to override individual bindings.
+    pub fn get_key_bindings_mut(&mut self) -> &mut KeyBindings { &mut self.key_bindings }
+
+    /// Replace the keys that trigger each Action. Any held movement keys are released.
+    pub fn set_key_bindings(&mut self, key_bindings: KeyBindings) {
+        self.key_bindings = key_bindings;
+        self.release_keys();
+    }
+
+    /// Get the way in which the Camera is moved.
+    pub fn get_mode(&self) -> CameraMode { self.mode }
+
+    /// Set the way in which the Camera is moved.
+    ///   Both modes share the same View, so switching modes doesn't move the Camera.
+    pub fn set_mode(&mut self, mode: CameraMode) { self.mode = mode; }
+
+    /// Switch between the Orbit and FreeFly modes.
+    pub fn toggle_mode(&mut self) {
+        self.mode = match self.mode {
+            CameraMode::Orbit => CameraMode::FreeFly,
+            CameraMode::FreeFly => CameraMode::Orbit,
+        };
+    }
+
+    /// Set the smoothing of the Camera movement.
+    ///
+    /// # Arguments
+    ///
+    /// * `smoothing` - The fraction of the remaining distance to the target View that is kept
+    ///                   every 1/60th of a second, in the range [0, 1). If 0, the Camera moves instantly.
+    pub fn set_smoothing(&mut self, smoothing: f32) {
+        self.smoothing = smoothing.max(0.0).min(0.99);
+        if self.smoothing == 0.0 {
+            self.target_view = None;
+        }
+    }
+
+    /// Stop any Camera movement in progress, e.g. after the View of the Camera was set directly.
+    pub fn stop(&mut self) {
+        self.velocity = [0.0; 4];
+        self.drag_delta = (0.0, 0.0);
+        self.pan_delta = (0.0, 0.0);
+        self.scroll_delta = 0.0;
+        self.target_view = None;
+    }
+
+    /// Forget which movement keys are held, e.g. after they were rebound.
+    fn release_keys(&mut self) {
+        self.is_up_pressed = false;
+        self.is_down_pressed = false;
+        self.is_left_pressed = false;
+        self.is_right_pressed = false;
+        self.is_forward_pressed = false;
+        self.is_backward_pressed = false;
+        self.is_cw_pressed = false;
+        self.is_ccw_pressed = false;
+    }
+
+    /// Set the acceleration of the Camera.
+    ///
+    /// # Arguments
+    ///
+    /// * `ramp_time` - The time (in seconds) taken to ramp up to full speed while a key is held,
+    ///                   and to slow down to a stop once it is released.
+    ///                   If None, the Camera moves at a constant speed.
+    pub fn set_acceleration(&mut self, ramp_time: Option<f32>) {
+        self.ramp_time = ramp_time.filter(|ramp_time| *ramp_time > 0.0);
+    }
+
+    /// Set the Camera movement requested by an analog input such as a gamepad stick.
+    ///   The movement continues until it is set back to zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `yaw`    - The direction of the yaw axis (as with the MoveLeft/MoveRight keys), in the range [-1, 1].
+    /// * `pitch`  - The direction of the pitch axis (as with the MoveUp/MoveDown keys), in the range [-1, 1].
+    /// * `radial` - The direction of the radial axis (as with the MoveForward/MoveBackward keys), in the range [-1, 1].
+    pub fn set_analog_input(&mut self, yaw: f32, pitch: f32, radial: f32) {
+        let clamp = |value: f32| value.max(-1.0).min(1.0);
+        self.analog_directions = (clamp(yaw), clamp(pitch), clamp(radial));
+    }
+
     /// Process a WindowEvent.
     /// Returns whether any event was processed.
     pub fn process_events(&mut self, event: &WindowEvent) -> bool {
@@ -44,52 +287,252 @@ impl CameraController {
                 ..
             } => {
                 let is_pressed = *state == ElementState::Pressed;
-                match keycode {
-                    VirtualKeyCode::W | VirtualKeyCode::Up    => { self.is_up_pressed       = is_pressed; }
-                    VirtualKeyCode::S | VirtualKeyCode::Down  => { self.is_down_pressed     = is_pressed; }
+                match self.key_bindings.get_action(*keycode) {
+                    Some(Action::MoveUp)       => { self.is_up_pressed       = is_pressed; }
+                    Some(Action::MoveDown)     => { self.is_down_pressed     = is_pressed; }
 
-                    VirtualKeyCode::A | VirtualKeyCode::Left  => { self.is_left_pressed     = is_pressed; }
-                    VirtualKeyCode::D | VirtualKeyCode::Right => { self.is_right_pressed    = is_pressed; }
+                    Some(Action::MoveLeft)     => { self.is_left_pressed     = is_pressed; }
+                    Some(Action::MoveRight)    => { self.is_right_pressed    = is_pressed; }
 
-                    VirtualKeyCode::LShift                    => { self.is_forward_pressed  = is_pressed; }
-                    VirtualKeyCode::LControl                  => { self.is_backward_pressed = is_pressed; }
-                    
-                    VirtualKeyCode::E                         => { self.is_cw_pressed       = is_pressed; }
-                    VirtualKeyCode::Q                         => { self.is_ccw_pressed      = is_pressed; }
+                    Some(Action::MoveForward)  => { self.is_forward_pressed  = is_pressed; }
+                    Some(Action::MoveBackward) => { self.is_backward_pressed = is_pressed; }
 
-                    _ => return false, // If some other Key was pressed or released.
+                    Some(Action::RotateCW)     => { self.is_cw_pressed       = is_pressed; }
+                    Some(Action::RotateCCW)    => { self.is_ccw_pressed      = is_pressed; }
+
+                    _ => return false, // If some other Key was pressed or released, or it is not a Camera Action.
+                }
+            }
+            WindowEvent::MouseInput { state, button: MouseButton::Left, .. } => {
+                self.is_dragging = *state == ElementState::Pressed;
+            }
+            WindowEvent::MouseInput { state, button: MouseButton::Middle, .. } => {
+                self.is_panning = *state == ElementState::Pressed;
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                // The first movement after the cursor enters the window only records its position,
+                //   so that a drag started outside the window doesn't make the Camera jump.
+                let last_position = self.cursor_position.replace(*position);
+                match last_position {
+                    Some(last_position) if self.is_dragging || self.is_panning => {
+                        let delta_x = (position.x - last_position.x) as f32;
+                        let delta_y = (position.y - last_position.y) as f32;
+                        let delta = if self.is_dragging { &mut self.drag_delta } else { &mut self.pan_delta };
+                        delta.0 += delta_x;
+                        delta.1 += delta_y;
+                    }
+                    _ => return false, // If the Camera is not being dragged.
                 }
             }
-            _ => return false, // If the event was not a KeyboardInput event.
+            WindowEvent::MouseWheel { delta, .. } => {
+                self.scroll_delta += match delta {
+                    MouseScrollDelta::LineDelta(_, lines) => lines * LINE_SCROLL_SPEED,
+                    MouseScrollDelta::PixelDelta(position) => position.y as f32 * PIXEL_SCROLL_SPEED,
+                } * self.zoom_speed;
+            }
+            WindowEvent::Touch(touch) => self.process_touch(touch),
+            WindowEvent::CursorLeft { .. } => {
+                self.cursor_position = None;
+                return false
+            }
+            _ => return false, // If the event was not a Camera controlling event.
         }
-        // This statement is reached only if a Camera controlling KevboardInput event occurred.
+        // This statement is reached only if a Camera controlling event occurred.
         return true
     }
 
+    /// Process a Touch event. A single finger dragged across the screen orbits the Camera,
+    ///   while two fingers pinched together or apart move the Camera along the radial axis.
+    fn process_touch(&mut self, touch: &Touch) {
+        let index = self.touches.iter().position(|(id, _)| *id == touch.id);
+        match (touch.phase, index) {
+            (TouchPhase::Started, None) => self.touches.push((touch.id, touch.location)),
+            (TouchPhase::Moved, Some(index)) => {
+                let last_location = std::mem::replace(&mut self.touches[index].1, touch.location);
+                match self.touches.len() {
+                    1 => {
+                        self.drag_delta.0 += (touch.location.x - last_location.x) as f32;
+                        self.drag_delta.1 += (touch.location.y - last_location.y) as f32;
+                    }
+                    // Only the first two fingers pinch, any further fingers are ignored.
+                    _ if index < 2 => {
+                        let other = self.touches[1 - index].1;
+                        let distance = |location: PhysicalPosition<f64>| {
+                            ((location.x - other.x).powi(2) + (location.y - other.y).powi(2)).sqrt() as f32
+                        };
+                        let pinch = distance(touch.location) - distance(last_location);
+                        self.scroll_delta += pinch * PINCH_SPEED * self.zoom_speed;
+                    }
+                    _ => {}
+                }
+            }
+            // The fingers may be lifted in any order. The remaining fingers keep their last known
+            //   position, so the Camera doesn't jump when a pinch turns back into a drag.
+            (TouchPhase::Ended, Some(index)) | (TouchPhase::Cancelled, Some(index)) => {
+                self.touches.remove(index);
+            }
+            _ => {}
+        }
+    }
+
     /// Update the Camera position and rotation based upon the current state of the CamerController.
     /// Returns whether the Camera was updated.
-    pub fn update_camera(&self, camera: &mut Camera) -> bool {
-        const SPEED: f32 = 0.3;
-        const THETA: Deg<f32> = cgmath::Deg(6.0);
-        const ZERO: Deg<f32> = cgmath::Deg(0.0);
-
-        let yaw = 
-            if self.is_right_pressed   { THETA } else if self.is_left_pressed     { -THETA } else { ZERO };
-        let pitch = 
-            if self.is_up_pressed      { THETA } else if self.is_down_pressed     { -THETA } else { ZERO };
-        let roll = 
-            if self.is_ccw_pressed     { THETA } else if self.is_cw_pressed       { -THETA } else { ZERO };
-        let radial =
-            if self.is_forward_pressed { SPEED } else if self.is_backward_pressed { -SPEED } else { 0f32 };
-        
-        // If nothing changed, don't perform any calculations.
-        if (yaw == ZERO) && (pitch == ZERO) && (roll == ZERO) && (radial == 0f32) {
-            return false
+    ///
+    /// # Arguments
+    ///
+    /// * `camera` - The Camera object to update.
+    /// * `dt`     - The time (in seconds) since the last update.
+    pub fn update_camera(&mut self, camera: &mut Camera, dt: f32) -> bool {
+        // The radial speed (per second) and angular speed (degrees per second) of the Camera.
+        let speed = self.move_speed;
+        let theta = Deg(self.rotate_speed);
+
+        // The direction requested for each axis (yaw, pitch, roll, radial) by the pressed keys
+        //   and the analog input.
+        let (analog_yaw, analog_pitch, analog_radial) = self.analog_directions;
+        let combine = |key_direction: f32, analog_direction: f32| (key_direction + analog_direction).max(-1.0).min(1.0);
+        let directions = [
+            combine(direction(self.is_right_pressed, self.is_left_pressed), analog_yaw),
+            combine(direction(self.is_up_pressed, self.is_down_pressed), analog_pitch),
+            direction(self.is_ccw_pressed, self.is_cw_pressed),
+            combine(direction(self.is_forward_pressed, self.is_backward_pressed), analog_radial),
+        ];
+
+        // Ramp the velocity of each axis towards the requested direction.
+        match self.ramp_time {
+            Some(ramp_time) => {
+                for (velocity, direction) in self.velocity.iter_mut().zip(directions.iter()) {
+                    *velocity = approach(*velocity, *direction, dt / ramp_time);
+                }
+            }
+            None => self.velocity = directions,
+        }
+
+        // Dragging the cursor orbits the Camera so that the scene follows the cursor.
+        let (drag_x, drag_y) = std::mem::replace(&mut self.drag_delta, (0.0, 0.0));
+
+        // Dragging with the middle mouse button pans the Camera so that the scene follows the cursor.
+        let (pan_x, pan_y) = std::mem::replace(&mut self.pan_delta, (0.0, 0.0));
+
+        // Scrolling zooms the Camera. The zoom is clamped to the near plane by `spherical_adjust`.
+        let mut scroll = std::mem::replace(&mut self.scroll_delta, 0.0);
+
+        // Moving an orthographic Camera toward its target doesn't change the size of the scene,
+        //   so scrolling shrinks the viewing volume instead.
+        let is_zoomed = scroll != 0.0 && camera.get_projection().get_mode() == ProjectionMode::Orthographic;
+        if is_zoomed {
+            let view = self.target_view.unwrap_or(*camera.get_view());
+            let distance = (view.get_target() - view.get_position()).magnitude();
+            let projection = camera.get_projection_mut();
+            projection.set_height(zoom_height(projection.height, distance, scroll));
+            scroll = 0.0;
+        }
+
+        // If nothing changed and the Camera isn't still moving, don't perform any calculations.
+        let is_idle = self.velocity.iter().all(|velocity| *velocity == 0.0);
+        let is_dragged = drag_x != 0.0 || drag_y != 0.0 || pan_x != 0.0 || pan_y != 0.0;
+        let is_moved = !is_idle || is_dragged || scroll != 0.0;
+        if !is_moved && self.target_view.is_none() {
+            return is_zoomed
         }
 
-        camera.set_view(
-            camera.get_view().spherical_adjust(yaw, pitch, roll, radial)
-        );
+        // Apply the movement to the View that the Camera is moving toward.
+        let mut target = self.target_view.unwrap_or(*camera.get_view());
+        if is_moved {
+            // Key presses are scaled by the time step, so the Camera moves at the same rate
+            //   regardless of the framerate. Cursor movements are absolute.
+            let [yaw, pitch, roll, radial] = self.velocity;
+            let drag_yaw = Deg(-drag_x * DRAG_SENSITIVITY);
+            let drag_pitch = Deg(-drag_y * DRAG_SENSITIVITY);
+            let roll = theta * (roll * dt);
+            match self.mode {
+                CameraMode::Orbit => {
+                    let yaw = theta * (yaw * dt) + drag_yaw;
+                    let pitch = theta * (pitch * dt) + drag_pitch;
+                    let radial = speed * radial * dt + scroll;
+                    target = target.spherical_adjust(yaw, pitch, roll, radial);
+                }
+                CameraMode::FreeFly => {
+                    target = target.look_around(drag_yaw, drag_pitch, roll);
+                    target.fly(speed * pitch * dt + scroll, speed * yaw * dt, speed * radial * dt);
+                }
+            }
+            if pan_x != 0.0 || pan_y != 0.0 {
+                target.pan(-pan_x * PAN_SENSITIVITY, pan_y * PAN_SENSITIVITY);
+            }
+        }
+
+        // Without smoothing, the Camera jumps straight to the target View.
+        if self.smoothing == 0.0 {
+            camera.set_view(target);
+            return true
+        }
+
+        // Once the target View is reached, the Camera stops.
+        let view = *camera.get_view();
+        if view.is_near(&target, CONVERGENCE_EPSILON) {
+            self.target_view = None;
+            if is_moved {
+                camera.set_view(target);
+            }
+            return is_moved || is_zoomed
+        }
+
+        camera.set_view(view.lerp(target, 1.0 - self.smoothing.powf(dt * SMOOTHING_FPS)));
+        self.target_view = Some(target);
         return true
     }
-}
\ No newline at end of file
+}
+
+/// The direction of an axis controlled by a pair of keys: 1 if positive, -1 if negative, else 0.
+fn direction(is_positive_pressed: bool, is_negative_pressed: bool) -> f32 {
+    if is_positive_pressed { 1.0 } else if is_negative_pressed { -1.0 } else { 0.0 }
+}
+
+/// The height of an orthographic viewing volume after scrolling. The height shrinks by the same fraction
+///   as the distance to the target would, so zooming feels the same as in the Perspective mode.
+///
+/// # Arguments
+///
+/// * `height`   - The height of the viewing volume.
+/// * `distance` - The distance from the Camera to its target.
+/// * `scroll`   - The radial distance scrolled toward the target.
+fn zoom_height(height: f32, distance: f32, scroll: f32) -> f32 {
+    let distance = distance.max(DEFAULT_Z_NEAR);
+    return height * (distance - scroll).max(DEFAULT_Z_NEAR) / distance
+}
+
+/// Move a value towards a target by no more than `max_delta`.
+fn approach(value: f32, target: f32, max_delta: f32) -> f32 {
+    let difference = target - value;
+    if difference.abs() <= max_delta { target } else { value + max_delta * difference.signum() }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera::Projection;
+
+    #[test]
+    fn scrolling_toward_the_target_shrinks_the_height() {
+        assert!((zoom_height(40.0, 50.0, 1.0) - 39.2).abs() < 1e-4);
+        assert!((zoom_height(40.0, 50.0, -1.0) - 40.8).abs() < 1e-4);
+    }
+
+    #[test]
+    fn scrolling_past_the_target_keeps_a_positive_height() {
+        assert!(zoom_height(40.0, 50.0, 100.0) > 0.0);
+        assert!(zoom_height(40.0, 0.0, 1.0) > 0.0);
+    }
+
+    #[test]
+    fn scrolling_zooms_an_orthographic_camera_in_place() {
+        let mut camera = Camera::new(View::default(), Projection::orthographic(1.0, 40.0, 0.1, 100.0));
+        let mut controller = CameraController::new();
+        controller.scroll_delta = 5.0;
+        assert!(controller.update_camera(&mut camera, 1.0 / 60.0));
+        assert!(camera.get_projection().height < 40.0);
+        assert_eq!(camera.get_view().get_position(), View::default().get_position());
+    }
+}
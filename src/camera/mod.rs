@@ -1,9 +1,13 @@
 mod camera;
 mod controller;
+mod preset;
 mod projection;
 mod view;
 
-pub use camera::Camera;
-pub use controller::CameraController;
-pub use projection::Projection;
+pub use camera::{sphere_in_frustum, Camera};
+pub use controller::{
+    CameraController, CameraMode, DEFAULT_MOVE_SPEED, DEFAULT_ROTATE_SPEED, DEFAULT_ZOOM_SPEED,
+};
+pub use preset::CameraPreset;
+pub use projection::{Projection, ProjectionMode};
 pub use view::View;
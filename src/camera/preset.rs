@@ -0,0 +1,18 @@
+/// A saved viewpoint of the Camera, i.e. the parts of the View and Projection that describe what is seen.
+///   With the `presets` feature, CameraPresets can be (de)serialized, e.g. to store them as JSON on disk.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "presets", derive(serde::Serialize, serde::Deserialize))]
+pub struct CameraPreset {
+
+    // The position of the viewer.
+    pub eye: [f32; 3],
+
+    // The target that the viewer is looking at.
+    pub target: [f32; 3],
+
+    // The Vector pointing up. This is made orthogonal to the viewing direction when the preset is applied.
+    pub up: [f32; 3],
+
+    // The vertical Field of View (in degrees).
+    pub fov_y: f32,
+}
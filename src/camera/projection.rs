@@ -1,35 +1,71 @@
 use cgmath::{Deg, Matrix4};
 
+/// The kind of Projection onto the Screen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProjectionMode {
+
+    // Objects further away from the viewer appear smaller.
+    Perspective,
+
+    // Objects appear the same size regardless of their distance from the viewer.
+    Orthographic,
+}
+
 /// Representation of a 3D Projection onto a 2D Screen.
 #[derive(Clone, Copy)]
 pub struct Projection {
 
     // The aspect ratio of the screen.
+    // Changing the parameters directly does not rebuild the cached matrix -- use the setters.
     pub aspect: f32,
 
-    // The vertical Field of View (in degrees)
+    // The vertical Field of View (in degrees). Used by the Perspective mode.
     pub fov_y: Deg<f32>,
 
+    // The height of the viewing volume. Used by the Orthographic mode.
+    pub height: f32,
+
     // The minimum distance that is projected.
     pub z_near: f32,
 
     // The maximum distance that is projected.
     pub z_far: f32,
 
+    // The kind of Projection.
+    mode: ProjectionMode,
+
     // The projection matrix. This is cached.
     projection: Matrix4<f32>,
 }
 
 pub const DEFAULT_FOV_Y: cgmath::Deg<f32> = Deg(45.0);
+pub const DEFAULT_HEIGHT: f32 = 40.0;
 pub const DEFAULT_Z_NEAR: f32 = 0.1;
 pub const DEFAULT_Z_FAR: f32 = 100.0;
 
 impl Projection {
 
-    /// Create a new Projection object. Constructs the Projection matrix at creation.
+    /// Create a new perspective Projection object. Constructs the Projection matrix at creation.
     pub fn new(aspect: f32, fov_y: Deg<f32>, z_near: f32, z_far: f32) -> Self {
-        let projection = cgmath::perspective(fov_y, aspect, z_near, z_far);
-        return Projection { aspect, fov_y, z_near, z_far, projection }
+        let mode = ProjectionMode::Perspective;
+        let height = DEFAULT_HEIGHT;
+        let projection = build_matrix(mode, aspect, fov_y, height, z_near, z_far);
+        return Projection { aspect, fov_y, height, z_near, z_far, mode, projection }
+    }
+
+    /// Create a new orthographic Projection object. Constructs the Projection matrix at creation.
+    ///
+    /// # Arguments
+    ///
+    /// * `aspect` - The aspect ratio of the screen.
+    /// * `height` - The height of the viewing volume. The width is derived from the aspect ratio.
+    /// * `z_near` - The minimum distance that is projected.
+    /// * `z_far`  - The maximum distance that is projected.
+    pub fn orthographic(aspect: f32, height: f32, z_near: f32, z_far: f32) -> Self {
+        let mode = ProjectionMode::Orthographic;
+        let fov_y = DEFAULT_FOV_Y;
+        let projection = build_matrix(mode, aspect, fov_y, height, z_near, z_far);
+        return Projection { aspect, fov_y, height, z_near, z_far, mode, projection }
     }
 
     pub fn with_aspect(aspect: f32) -> Self {
@@ -41,6 +77,76 @@ impl Projection {
         )
     }
 
+    /// Getter for the kind of Projection.
+    pub fn get_mode(&self) -> ProjectionMode { self.mode }
+
+    /// Set the kind of Projection. Rebuilds the Projection matrix.
+    pub fn set_mode(&mut self, mode: ProjectionMode) {
+        self.mode = mode;
+        self.rebuild();
+    }
+
+    /// Switch between the Perspective and Orthographic modes. Rebuilds the Projection matrix.
+    pub fn toggle_mode(&mut self) {
+        self.set_mode(
+            match self.mode {
+                ProjectionMode::Perspective => ProjectionMode::Orthographic,
+                ProjectionMode::Orthographic => ProjectionMode::Perspective,
+            }
+        );
+    }
+
+    /// Set the vertical Field of View. Rebuilds the Projection matrix.
+    pub fn set_fov(&mut self, fov_y: Deg<f32>) {
+        self.fov_y = fov_y;
+        self.rebuild();
+    }
+
+    /// Set the height of the orthographic viewing volume. Rebuilds the Projection matrix.
+    pub fn set_height(&mut self, height: f32) {
+        self.height = height;
+        self.rebuild();
+    }
+
+    /// Set the aspect ratio of the screen. Rebuilds the Projection matrix.
+    pub fn set_aspect(&mut self, aspect: f32) {
+        self.aspect = aspect;
+        self.rebuild();
+    }
+
+    /// Set the minimum and maximum distances that are projected. Rebuilds the Projection matrix.
+    pub fn set_z_planes(&mut self, z_near: f32, z_far: f32) {
+        self.z_near = z_near;
+        self.z_far = z_far;
+        self.rebuild();
+    }
+
     /// Getter for the Projection Matrix.
     pub fn as_matrix(&self) -> Matrix4<f32> { self.projection }
+
+    /// Rebuild the cached Projection matrix from the current parameters.
+    fn rebuild(&mut self) {
+        self.projection = build_matrix(
+            self.mode, self.aspect, self.fov_y, self.height, self.z_near, self.z_far
+        );
+    }
+}
+
+/// Build a Projection matrix (in the OpenGL format).
+fn build_matrix(
+    mode: ProjectionMode,
+    aspect: f32,
+    fov_y: Deg<f32>,
+    height: f32,
+    z_near: f32,
+    z_far: f32,
+) -> Matrix4<f32> {
+    return match mode {
+        ProjectionMode::Perspective => cgmath::perspective(fov_y, aspect, z_near, z_far),
+        ProjectionMode::Orthographic => {
+            let half_height = height / 2.0;
+            let half_width = half_height * aspect;
+            cgmath::ortho(-half_width, half_width, -half_height, half_height, z_near, z_far)
+        }
+    }
 }
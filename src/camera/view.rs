@@ -38,13 +38,163 @@ impl View {
         return View { eye, target, up, view }
     }
 
+    /// Construct a new View looking from a position at a target, with the Up vector derived from the world Up
+    ///   (`DEFAULT_UP`). When looking straight up or down, the world Z axis is used as the Up vector instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `eye`    - The position of the viewer.
+    /// * `target` - The target that the viewer is looking at. If it coincides with the position,
+    ///                there is no direction to look in, so the viewer looks along the default forward
+    ///                direction (from `DEFAULT_EYE` to `DEFAULT_TARGET`) instead.
+    pub fn looking_at(eye: Point3<f32>, target: Point3<f32>) -> Self {
+        let target = if eye == target {
+            use cgmath::InnerSpace;
+            let default_forward = Point3::from(DEFAULT_TARGET) - Point3::from(DEFAULT_EYE);
+            eye + default_forward.normalize()
+        } else {
+            target
+        };
+        return View::new(eye, target, orthogonal_up(target - eye, DEFAULT_UP.into()))
+    }
+
+    /// Getter for the position of the viewer.
     pub fn get_position(&self) -> Point3<f32> { self.eye }
+
+    /// Getter for the target that the viewer is looking at.
+    pub fn get_target(&self) -> Point3<f32> { self.target }
+
+    /// Getter for the (normalized) Up vector.
+    pub fn get_up(&self) -> Vector3<f32> { self.up }
+
+    /// Getter for the normalized vector pointing from the viewer to the target.
+    pub fn get_forward(&self) -> Vector3<f32> {
+        use cgmath::InnerSpace;
+        (self.target - self.eye).normalize()
+    }
     
     /// Getter for the View Matrix.
     pub fn as_matrix(&self) -> Matrix4<f32> { self.view }
 
+    /// Move the viewer, keeping it looking at the same target.
+    ///   The Up vector is kept as close to its current direction as possible.
+    ///   The position is ignored if it coincides with the target.
+    ///
+    /// # Arguments
+    ///
+    /// * `eye` - The new position of the viewer.
+    pub fn set_position(&mut self, eye: Point3<f32>) {
+        if eye != self.target {
+            *self = View::new(eye, self.target, orthogonal_up(self.target - eye, self.up));
+        }
+    }
+
+    /// Look at a new target, keeping the viewer in place.
+    ///   The Up vector is kept as close to its current direction as possible.
+    ///   The target is ignored if it coincides with the position of the viewer.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The new target that the viewer is looking at.
+    pub fn set_target(&mut self, target: Point3<f32>) {
+        if target != self.eye {
+            *self = View::new(self.eye, target, orthogonal_up(target - self.eye, self.up));
+        }
+    }
+
+    /// Linearly interpolate between this View and another View.
+    ///   The interpolated Up vector is made orthogonal to the interpolated Forward vector.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The View to interpolate toward.
+    /// * `t`     - The interpolation factor. 0 returns this View, 1 returns the other View.
+    pub fn lerp(self, other: View, t: f32) -> Self {
+        use cgmath::{InnerSpace, VectorSpace};
+
+        let eye = self.eye + (other.eye - self.eye) * t;
+        let target = self.target + (other.target - self.target) * t;
+        let forward = (target - eye).normalize();
+        let up = self.up.lerp(other.up, t);
+        let up = (up - forward * forward.dot(up)).normalize();
+        return View::new(eye, target, up)
+    }
+
+    /// Whether the position, target and Up vector of this View are all within `epsilon` of another View.
+    pub fn is_near(&self, other: &View, epsilon: f32) -> bool {
+        use cgmath::InnerSpace;
+        return (self.eye - other.eye).magnitude() < epsilon
+            && (self.target - other.target).magnitude() < epsilon
+            && (self.up - other.up).magnitude() < epsilon
+    }
+
+    /// Translate the viewer and the target along the viewer's right and up vectors.
+    ///
+    /// # Arguments
+    ///
+    /// * `right_amount` - The distance to move along the right vector.
+    /// * `up_amount`    - The distance to move along the up vector.
+    pub fn pan(&mut self, right_amount: f32, up_amount: f32) {
+        self.fly(0.0, right_amount, up_amount);
+    }
+
+    /// Translate the viewer and the target along the viewer's forward, right and up vectors.
+    ///
+    /// # Arguments
+    ///
+    /// * `forward_amount` - The distance to move along the forward vector.
+    /// * `right_amount`   - The distance to move along the right vector.
+    /// * `up_amount`      - The distance to move along the up vector.
+    pub fn fly(&mut self, forward_amount: f32, right_amount: f32, up_amount: f32) {
+        use cgmath::InnerSpace;
+
+        let forward = (self.target - self.eye).normalize();
+        let right = forward.cross(self.up).normalize();
+        // Recompute the Up vector from the Right vector so that it stays orthonormal over repeated moves.
+        let up = right.cross(forward).normalize();
+
+        let offset = forward * forward_amount + right * right_amount + up * up_amount;
+        *self = View::new(self.eye + offset, self.target + offset, up);
+    }
+
+    /// Creates a new View object by rotating the viewer's orientation in place.
+    ///   The viewer's position is preserved and the target rotates about it.
+    ///
+    /// # Arguments
+    ///
+    /// * `yaw`   - The change in the Yaw angle, in degrees.
+    /// * `pitch` - The change in the Pitch angle, in degrees.
+    /// * `roll`  - The change in the Roll angle, in degrees.
+    pub fn look_around(self, yaw: Deg<f32>, pitch: Deg<f32>, roll: Deg<f32>) -> Self {
+        use cgmath::{InnerSpace, Rotation};
+
+        // Construct the Rotation Matrices.
+        let forward = self.target - self.eye;
+        let right = forward.normalize().cross(self.up);
+        let yaw_rot: Basis3<f32> = Rotation3::from_axis_angle(self.up, yaw);
+        let pitch_rot: Basis3<f32> = Rotation3::from_axis_angle(right, pitch);
+        let roll_rot: Basis3<f32> = Rotation3::from_axis_angle(forward.normalize(), roll);
+
+        // Apply Transformations.
+        let target = self.eye +
+            yaw_rot.rotate_vector(
+                pitch_rot.rotate_vector(
+                    roll_rot.rotate_vector(forward)
+                )
+            );
+        let up =
+            yaw_rot.rotate_vector(
+                pitch_rot.rotate_vector(
+                    roll_rot.rotate_vector(self.up)
+                )
+            ).normalize();
+
+        // Construct new View.
+        return View::new(self.eye, target, up)
+    }
+
     /// Creates a new View object based on spherical adjustments to the viewer's orientation and radial position.
-    ///   The target is preserved in the new View.
+    ///   The viewer orbits about the target, which is preserved in the new View.
     ///
     /// # Arguments
     /// 
@@ -53,7 +203,7 @@ impl View {
     /// * `roll` - The change in the Roll angle, in degrees.
     /// * `radial` - The change in radial distance from the target.
     pub fn spherical_adjust(self, yaw: Deg<f32>, pitch: Deg<f32>, roll: Deg<f32>, radial: f32) -> Self {
-        use cgmath::{EuclideanSpace, InnerSpace, Rotation};
+        use cgmath::{InnerSpace, Rotation};
 
         // Construct the Rotation Matrices.
         let forward = (self.target - self.eye).normalize();
@@ -63,7 +213,7 @@ impl View {
         let roll_rot: Basis3<f32> = Rotation3::from_axis_angle(forward, roll);
         
         // Apply Transformations.
-        let eye_vec = self.eye.to_vec();
+        let eye_vec = self.eye - self.target;
         let magnitude = {
             use super::projection::DEFAULT_Z_NEAR as z_near;
             match eye_vec.magnitude() - radial {
@@ -71,13 +221,12 @@ impl View {
                 _ => z_near
             }
         };
-        let eye = Point3::<f32>::from_vec(
+        let eye = self.target +
             yaw_rot.rotate_vector(
                 pitch_rot.rotate_vector(
                     roll_rot.rotate_vector(eye_vec)
                 )
-            ).normalize_to(magnitude)
-        );
+            ).normalize_to(magnitude);
         let up = 
             yaw_rot.rotate_vector(
                 pitch_rot.rotate_vector(
@@ -90,6 +239,21 @@ impl View {
     }
 }
 
+/// Make an Up vector orthogonal to a Forward vector, keeping it as close to its direction as possible.
+///   If the vectors are parallel, the axis that is least aligned with the Forward vector is used instead.
+pub(super) fn orthogonal_up(forward: Vector3<f32>, up: Vector3<f32>) -> Vector3<f32> {
+    use cgmath::InnerSpace;
+
+    let forward = forward.normalize();
+    let orthogonalize = |up: Vector3<f32>| up - forward * forward.dot(up);
+    let up = orthogonalize(up);
+    if up.magnitude() > 1e-4 {
+        return up.normalize()
+    }
+    let fallback = if forward.y.abs() < 0.9 { Vector3::unit_y() } else { Vector3::unit_z() };
+    return orthogonalize(fallback).normalize()
+}
+
 
 pub const DEFAULT_EYE: [f32; 3] = [0.0, 0.0, 50.0];
 pub const DEFAULT_TARGET: [f32; 3] = [0.0, 0.0, 0.0];
@@ -104,4 +268,42 @@ impl Default for View {
             DEFAULT_UP.into(),
         )
     }
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use cgmath::InnerSpace;
+    use super::*;
+
+    fn assert_valid(view: &View) {
+        let matrix: [[f32; 4]; 4] = view.as_matrix().into();
+        assert!(matrix.iter().flatten().all(|value| value.is_finite()), "{:?}", matrix);
+        assert!((view.get_up().magnitude() - 1.0).abs() < 1e-5);
+        assert!(view.get_up().dot(view.get_forward()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn looking_straight_down_has_a_valid_up() {
+        let view = View::looking_at(Point3::new(0.0, 10.0, 0.0), Point3::new(0.0, 0.0, 0.0));
+        assert_valid(&view);
+        assert!((view.get_forward() - Vector3::new(0.0, -1.0, 0.0)).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn looking_straight_up_has_a_valid_up() {
+        let view = View::looking_at(Point3::new(1.0, -5.0, 2.0), Point3::new(1.0, 5.0, 2.0));
+        assert_valid(&view);
+        assert!((view.get_forward() - Vector3::unit_y()).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn looking_at_the_eye_keeps_the_default_forward() {
+        let eye = Point3::new(3.0, 4.0, 5.0);
+        let view = View::looking_at(eye, eye);
+        assert_valid(&view);
+        assert_eq!(view.get_position(), eye);
+        assert!((view.get_forward() - Vector3::new(0.0, 0.0, -1.0)).magnitude() < 1e-5);
+        assert!((view.get_up() - Vector3::unit_y()).magnitude() < 1e-5);
+    }
+}
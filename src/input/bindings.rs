@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use winit::event::VirtualKeyCode;
+
+/// An action of the viewer that can be bound to keys.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Action {
+
+    // Held actions which move the Camera, see CameraMode for how each mode interprets them.
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    MoveForward,
+    MoveBackward,
+    RotateCW,
+    RotateCCW,
+
+    // Toggle whether the Light is drawn.
+    ToggleLight,
+
+    // Toggle between the perspective and orthographic projections.
+    ToggleProjection,
+
+    // Reset the Camera to its initial View.
+    ResetView,
+
+    // Toggle between the Orbit and FreeFly camera modes.
+    ToggleCameraMode,
+
+    // Move the Camera so that every Model is in view.
+    FrameAll,
+
+    // Toggle whether the Models are drawn as wireframes.
+    ToggleWireframe,
+
+    // Pause or resume the scene.
+    TogglePause,
+
+    // Select the Joint of the Robot with the index, outlining the Links that it moves.
+    SelectJoint(usize),
+
+    // Held actions which turn the selected Joint of the Robot forward or back about its axis.
+    JogJointUp,
+    JogJointDown,
+}
+
+impl Action {
+
+    /// Every Action, in the order they are listed in.
+    pub const ALL: [Action; 26] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::MoveForward,
+        Action::MoveBackward,
+        Action::RotateCW,
+        Action::RotateCCW,
+        Action::ToggleLight,
+        Action::ToggleProjection,
+        Action::ResetView,
+        Action::ToggleCameraMode,
+        Action::FrameAll,
+        Action::ToggleWireframe,
+        Action::TogglePause,
+        Action::SelectJoint(0),
+        Action::SelectJoint(1),
+        Action::SelectJoint(2),
+        Action::SelectJoint(3),
+        Action::SelectJoint(4),
+        Action::SelectJoint(5),
+        Action::SelectJoint(6),
+        Action::SelectJoint(7),
+        Action::SelectJoint(8),
+        Action::JogJointUp,
+        Action::JogJointDown,
+    ];
+
+    /// Get the keys that the Action is bound to by default.
+    pub fn default_keys(&self) -> &'static [VirtualKeyCode] {
+        return match self {
+            Action::MoveUp           => &[VirtualKeyCode::W, VirtualKeyCode::Up],
+            Action::MoveDown         => &[VirtualKeyCode::S, VirtualKeyCode::Down],
+            Action::MoveLeft         => &[VirtualKeyCode::A, VirtualKeyCode::Left],
+            Action::MoveRight        => &[VirtualKeyCode::D, VirtualKeyCode::Right],
+            Action::MoveForward      => &[VirtualKeyCode::LShift],
+            Action::MoveBackward     => &[VirtualKeyCode::LControl],
+            Action::RotateCW         => &[VirtualKeyCode::E],
+            Action::RotateCCW        => &[VirtualKeyCode::Q],
+            Action::ToggleLight      => &[VirtualKeyCode::L],
+            Action::ToggleProjection => &[VirtualKeyCode::O],
+            Action::ResetView        => &[VirtualKeyCode::R],
+            Action::ToggleCameraMode => &[VirtualKeyCode::C],
+            Action::FrameAll         => &[VirtualKeyCode::F],
+            Action::ToggleWireframe  => &[VirtualKeyCode::Z],
+            Action::TogglePause      => &[VirtualKeyCode::Space],
+            Action::SelectJoint(0)   => &[VirtualKeyCode::Key1],
+            Action::SelectJoint(1)   => &[VirtualKeyCode::Key2],
+            Action::SelectJoint(2)   => &[VirtualKeyCode::Key3],
+            Action::SelectJoint(3)   => &[VirtualKeyCode::Key4],
+            Action::SelectJoint(4)   => &[VirtualKeyCode::Key5],
+            Action::SelectJoint(5)   => &[VirtualKeyCode::Key6],
+            Action::SelectJoint(6)   => &[VirtualKeyCode::Key7],
+            Action::SelectJoint(7)   => &[VirtualKeyCode::Key8],
+            Action::SelectJoint(8)   => &[VirtualKeyCode::Key9],
+            Action::SelectJoint(_)   => &[],
+            Action::JogJointUp       => &[VirtualKeyCode::RBracket],
+            Action::JogJointDown     => &[VirtualKeyCode::LBracket],
+        }
+    }
+}
+
+/// Maps keys to the Actions they trigger. Each key triggers at most one Action,
+///   while an Action may be bound to any number of keys (or none, to disable it).
+#[derive(Clone, Debug)]
+pub struct KeyBindings {
+
+    // The Action triggered by each bound key.
+    actions: HashMap<VirtualKeyCode, Action>,
+}
+
+impl KeyBindings {
+
+    /// Create a KeyBindings object with no keys bound.
+    pub fn empty() -> Self {
+        return KeyBindings { actions: HashMap::new() }
+    }
+
+    /// Get the Action triggered by a key, if the key is bound.
+    pub fn get_action(&self, key: VirtualKeyCode) -> Option<Action> {
+        return self.actions.get(&key).copied()
+    }
+
+    /// Get the keys that an Action is bound to.
+    pub fn get_keys(&self, action: Action) -> Vec<VirtualKeyCode> {
+        return self.actions.iter()
+            .filter(|(_, bound)| **bound == action)
+            .map(|(key, _)| *key)
+            .collect()
+    }
+
+    /// Bind an Action to the given keys, replacing the keys it was previously bound to.
+    ///   Any other Action bound to one of the keys loses that key.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The Action to rebind.
+    /// * `keys`   - The keys that trigger the Action. If empty, the Action is disabled.
+    pub fn set_binding(&mut self, action: Action, keys: &[VirtualKeyCode]) {
+        self.actions.retain(|_, bound| *bound != action);
+        for key in keys {
+            self.actions.insert(*key, action);
+        }
+    }
+
+    /// Unbind a key, so that it no longer triggers any Action.
+    pub fn unbind_key(&mut self, key: VirtualKeyCode) {
+        self.actions.remove(&key);
+    }
+}
+
+impl Default for KeyBindings {
+
+    /// The default layout: WASD (or the arrow keys), LShift/LControl and Q/E move the Camera,
+    ///   while L, O, R, C, F, Z and Space trigger the toggles.
+    ///   The keys 1 to 9 select the first nine Joints of the Robot, and ] and [ jog the selected Joint.
+    fn default() -> Self {
+        let mut bindings = KeyBindings::empty();
+        for action in Action::ALL.iter() {
+            bindings.set_binding(*action, action.default_keys());
+        }
+        return bindings
+    }
+}
@@ -0,0 +1,61 @@
+use gilrs::{Axis, Button, GamepadId, Gilrs};
+
+/// Stick deflections below this magnitude are ignored, so a resting stick doesn't drift the Camera.
+pub const GAMEPAD_DEADZONE: f32 = 0.15;
+
+/// Reads the state of a gamepad, to navigate the scene with a controller.
+///   The left stick orbits the Camera, and the right and left triggers zoom in and out.
+pub struct GamepadInput {
+
+    // The context of the gamepad library.
+    gilrs: Gilrs,
+
+    // The gamepad that was used last, which is the one that controls the Camera.
+    active_gamepad: Option<GamepadId>,
+}
+
+impl GamepadInput {
+
+    /// Start reading the connected gamepads.
+    pub fn new() -> Result<Self, failure::Error> {
+        let gilrs = Gilrs::new()
+            .map_err(|error| failure::format_err!("Failed to initialize the gamepad input: {}", error))?;
+        // Until a gamepad is used, the first connected one controls the Camera.
+        let active_gamepad = gilrs.gamepads().next().map(|(id, _)| id);
+        return Ok(GamepadInput { gilrs, active_gamepad })
+    }
+
+    /// Process the pending gamepad events without blocking, and get the requested Camera movement.
+    ///
+    /// # Returns
+    ///
+    /// The direction of the (yaw, pitch, radial) axes, each in the range [-1, 1].
+    pub fn poll(&mut self) -> (f32, f32, f32) {
+        while let Some(event) = self.gilrs.next_event() {
+            self.active_gamepad = Some(event.id);
+        }
+
+        let gamepad = match self.active_gamepad {
+            Some(id) => self.gilrs.gamepad(id),
+            None => return (0.0, 0.0, 0.0),
+        };
+        if !gamepad.is_connected() {
+            self.active_gamepad = None;
+            return (0.0, 0.0, 0.0)
+        }
+
+        let trigger = |button| gamepad.button_data(button).map_or(0.0, |data| data.value());
+        let yaw = deadzone(gamepad.value(Axis::LeftStickX));
+        let pitch = deadzone(gamepad.value(Axis::LeftStickY));
+        let radial = deadzone(trigger(Button::RightTrigger2) - trigger(Button::LeftTrigger2));
+        return (yaw, pitch, radial)
+    }
+}
+
+/// Zero a value within the deadzone, and rescale the rest of the range to start from 0.
+fn deadzone(value: f32) -> f32 {
+    if value.abs() < GAMEPAD_DEADZONE {
+        return 0.0
+    }
+    return value.signum() * (value.abs() - GAMEPAD_DEADZONE) / (1.0 - GAMEPAD_DEADZONE)
+}
@@ -0,0 +1,145 @@
+use std::{
+    io::{BufRead, BufReader},
+    net::{TcpStream, ToSocketAddrs},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+use serde::Deserialize;
+
+/// A joint state message, in the layout of the ROS `sensor_msgs/JointState` message:
+///   `{"name": ["shoulder", "elbow"], "position": [0.5, -1.2]}`. Other fields (e.g. `header`) are ignored.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct JointState {
+
+    // The names of the joints.
+    pub name: Vec<String>,
+
+    // The angles (in radians) of the joints, in the same order as the names.
+    pub position: Vec<f32>,
+}
+
+impl JointState {
+
+    /// Parse a joint state message from a line of JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `line` - The JSON object of the message.
+    pub fn parse(line: &str) -> Result<Self, failure::Error> {
+        let state: JointState = serde_json::from_str(line)?;
+        if state.name.len() != state.position.len() {
+            return Err(failure::format_err!(
+                "The joint state has {} names but {} positions", state.name.len(), state.position.len(),
+            ))
+        }
+        if let Some(position) = state.position.iter().find(|position| !position.is_finite()) {
+            return Err(failure::format_err!("The joint state has a non-finite position {}", position))
+        }
+        return Ok(state)
+    }
+}
+
+/// Reads newline-delimited JSON joint state messages (see `JointState`) from stdin or a TCP socket,
+///   e.g. to pipe the state of a robot from a script. The messages are read on a background thread,
+///   and malformed lines are skipped with a warning.
+pub struct JointStateInput {
+
+    // Receives the messages read by the background thread.
+    receiver: Receiver<JointState>,
+}
+
+impl JointStateInput {
+
+    /// Start reading joint states from stdin.
+    pub fn from_stdin() -> Self {
+        info!("Reading joint states from stdin");
+        return Self::from_reader(BufReader::new(std::io::stdin()), "stdin")
+    }
+
+    /// Start reading joint states from a TCP connection.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address that sends the joint states, e.g. `"127.0.0.1:9090"`.
+    pub fn connect<A: ToSocketAddrs>(address: A) -> Result<Self, failure::Error> {
+        let stream = TcpStream::connect(address)?;
+        let source = format!("{}", stream.peer_addr()?);
+        info!("Reading joint states from {}", source);
+        return Ok(Self::from_reader(BufReader::new(stream), &source))
+    }
+
+    /// Start reading joint states from a reader, on a background thread.
+    fn from_reader<R: BufRead + Send + 'static>(reader: R, source: &str) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let source = String::from(source);
+        thread::spawn(move || read_joint_states(reader, &source, sender));
+        return JointStateInput { receiver }
+    }
+
+    /// Get the messages that were read since the last call, in order, without blocking.
+    pub fn poll(&self) -> Vec<JointState> {
+        return self.receiver.try_iter().collect()
+    }
+}
+
+/// Read joint state messages until the reader ends, or the JointStateInput is dropped.
+fn read_joint_states<R: BufRead>(reader: R, source: &str, sender: Sender<JointState>) {
+    for line in reader.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(error) => {
+                warn!("Stopped reading joint states from {}: {}", source, error);
+                return
+            }
+        };
+        if line.trim().is_empty() {
+            continue
+        }
+        match JointState::parse(&line) {
+            Ok(state) => {
+                if sender.send(state).is_err() {
+                    return
+                }
+            }
+            Err(error) => warn!("Skipping malformed joint state {:?}: {}", line, error),
+        }
+    }
+    info!("Reached the end of the joint states from {}", source);
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use super::*;
+
+    #[test]
+    fn ros_joint_states_are_parsed() {
+        let line = r#"{"header": {"seq": 1}, "name": ["shoulder", "elbow"], "position": [0.5, -1.25], "velocity": []}"#;
+        let state = JointState::parse(line).unwrap();
+        assert_eq!(state.name, ["shoulder", "elbow"]);
+        assert_eq!(state.position, [0.5, -1.25]);
+    }
+
+    #[test]
+    fn inconsistent_joint_states_are_rejected() {
+        assert!(JointState::parse(r#"{"name": ["shoulder"], "position": [0.5, 1.0]}"#).is_err());
+        assert!(JointState::parse(r#"{"name": ["shoulder"]}"#).is_err());
+        assert!(JointState::parse(r#"{"name": ["shoulder"], "position": [1e39]}"#).is_err());
+        assert!(JointState::parse("not json").is_err());
+    }
+
+    #[test]
+    fn malformed_lines_are_skipped() {
+        let lines = concat!(
+            "{\"name\": [\"shoulder\"], \"position\": [0.5]}\n",
+            "{\"name\": [\"shoulder\"\n",
+            "\n",
+            "{\"name\": [\"elbow\"], \"position\": [1.5]}\n",
+        );
+        let (sender, receiver) = mpsc::channel();
+        read_joint_states(Cursor::new(lines), "test", sender);
+        let names: Vec<Vec<String>> = receiver.try_iter().map(|state| state.name).collect();
+        assert_eq!(names, [["shoulder"], ["elbow"]]);
+    }
+}
@@ -0,0 +1,11 @@
+mod bindings;
+#[cfg(feature = "gamepad")]
+mod gamepad;
+#[cfg(feature = "joint-states")]
+mod joint_states;
+
+pub use bindings::{Action, KeyBindings};
+#[cfg(feature = "gamepad")]
+pub use gamepad::{GamepadInput, GAMEPAD_DEADZONE};
+#[cfg(feature = "joint-states")]
+pub use joint_states::{JointState, JointStateInput};
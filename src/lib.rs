@@ -1,9 +1,14 @@
 #[macro_use]
 extern crate lazy_static;
+#[macro_use]
+extern crate log;
 
 pub mod camera;
+pub mod input;
 pub mod light;
+pub mod lines;
 pub mod model;
+pub mod robot;
 pub mod shaders;
 pub mod state;
 pub mod texture;
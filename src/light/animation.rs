@@ -0,0 +1,67 @@
+use cgmath::{Deg, Point3, Rad, Vector3};
+
+
+/// Describes the path that an animated Light follows over time.
+pub trait LightAnimation {
+
+    /// The position of the light source at the given time.
+    ///
+    /// # Arguments
+    ///
+    /// * `t` - The time (in seconds) since the animation started.
+    fn position_at(&self, t: f32) -> Point3<f32>;
+}
+
+
+/// Animates a Light along a horizontal circle.
+#[derive(Copy, Clone, Debug)]
+pub struct CircularOrbit {
+
+    // The center of the circle.
+    pub center: Point3<f32>,
+
+    // The radius of the circle.
+    pub radius: f32,
+
+    // The angle swept per second. Positive angles orbit counter-clockwise when viewed from above.
+    pub angular_speed: Deg<f32>,
+
+    // The angle about the center at the start of the animation, measured from the +Z axis.
+    pub phase: Deg<f32>,
+}
+
+impl CircularOrbit {
+
+    /// Creates a new CircularOrbit object.
+    ///
+    /// # Arguments
+    ///
+    /// * `center`        - The center of the circle.
+    /// * `radius`        - The radius of the circle.
+    /// * `angular_speed` - The angle swept per second.
+    pub fn new(center: Point3<f32>, radius: f32, angular_speed: Deg<f32>) -> Self {
+        return CircularOrbit { center, radius, angular_speed, phase: Deg(0.0) }
+    }
+
+    /// Creates a CircularOrbit about the vertical (Y) axis that passes through the given starting position.
+    ///
+    /// # Arguments
+    ///
+    /// * `start`         - The position of the light source at the start of the animation.
+    /// * `angular_speed` - The angle swept per second.
+    pub fn around_y(start: Vector3<f32>, angular_speed: Deg<f32>) -> Self {
+        let radius = (start.x * start.x + start.z * start.z).sqrt();
+        let phase = Rad(start.x.atan2(start.z)).into();
+        return CircularOrbit { center: Point3::new(0.0, start.y, 0.0), radius, angular_speed, phase }
+    }
+}
+
+impl LightAnimation for CircularOrbit {
+    fn position_at(&self, t: f32) -> Point3<f32> {
+        use cgmath::Angle;
+
+        let angle = self.phase + self.angular_speed * t;
+        let (sin, cos) = angle.sin_cos();
+        return self.center + Vector3::new(self.radius * sin, 0.0, self.radius * cos)
+    }
+}
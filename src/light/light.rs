@@ -0,0 +1,346 @@
+use cgmath::Vector3;
+use wgpu::Device;
+
+use crate::state::{StagingBuffers, StateCore};
+use super::LightAnimation;
+
+
+/// The maximum number of light sources in a scene. The shader programs light the scene with a single Light.
+pub const MAX_LIGHTS: usize = 1;
+
+/// Structure for holding information about the light source 
+///   that is sent to the Shader programs.
+pub struct Light {
+
+    // The Bind Group used for rendering.
+    pub bind_group: wgpu::BindGroup,
+
+    // The Layout used for the Uniforms BindGroup.
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    
+    // The Buffer used to send data to the GPU.
+    buffer: wgpu::Buffer,
+
+    // The staging buffers that the data is written through, reused between updates.
+    staging: StagingBuffers,
+
+    // The RGB value for the color of the light.
+    color: cgmath::Vector3<f32>,
+
+    // The 3D Position of the light source.
+    position: cgmath::Vector3<f32>,
+
+    // The RGB value for the color of the ambient light, which is applied regardless of position.
+    ambient_color: cgmath::Vector3<f32>,
+
+    // The strength of the ambient light.
+    ambient_intensity: f32,
+
+    // The (constant, linear, quadratic) coefficients of the attenuation over distance.
+    attenuation: cgmath::Vector3<f32>,
+
+    // The path the light source follows when animated.
+    animation: Option<Box<dyn LightAnimation>>,
+
+    // The time (in seconds) that the animation has been running.
+    animation_time: f32,
+}
+
+impl Light {
+
+    const WHITE: [f32; 3] = [1.0, 1.0, 1.0];
+    pub const DEFAULT_AMBIENT_INTENSITY: f32 = 0.1;
+    pub const NO_ATTENUATION: [f32; 3] = [1.0, 0.0, 0.0];
+
+    /// Creates a new Light object.
+    ///
+    /// # Arguments
+    ///
+    /// * `device`   - The connection to the graphics device. Used to create the rendering resources.
+    /// * `position` - The 3D position of the light source.
+    /// * `color`    - The RGB value for the color of the light.
+    pub fn new(device: &Device, position: Vector3<f32>, color: Vector3<f32>) -> Self {
+        let ambient_color = Self::WHITE.into();
+        let ambient_intensity = Self::DEFAULT_AMBIENT_INTENSITY;
+        let attenuation = Self::NO_ATTENUATION.into();
+        let light_raw = LightRaw::new(position, color, ambient_color, ambient_intensity, attenuation);
+        let light_raw_size = std::mem::size_of_val(&light_raw) as wgpu::BufferAddress;
+
+        let buffer = device.create_buffer_with_data(
+            bytemuck::cast_slice(&[light_raw]),
+            wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+        );
+
+        let bind_group_layout =
+            device.create_bind_group_layout(
+                &wgpu::BindGroupLayoutDescriptor {
+                    bindings: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: wgpu::ShaderStage::VERTEX | wgpu::ShaderStage::FRAGMENT,
+                            ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+                        },
+                    ],
+                    label: None,
+            }
+        );
+
+        let bind_group = 
+            device.create_bind_group(
+            &wgpu::BindGroupDescriptor {
+                layout: &bind_group_layout,
+                bindings: &[
+                    wgpu::Binding {
+                        binding: 0,
+                        resource: wgpu::BindingResource::Buffer {
+                            buffer: &buffer,
+                            range: 0..light_raw_size,
+                        },
+                    },
+                ],
+                label: None,
+            }
+        );
+        
+        return Light{
+            position,
+            color,
+            ambient_color,
+            ambient_intensity,
+            attenuation,
+            animation: None,
+            animation_time: 0.0,
+            bind_group,
+            bind_group_layout,
+            buffer,
+            staging: StagingBuffers::new(light_raw_size),
+        }
+    }
+
+    /// Creates a new white Light object.
+    ///
+    /// # Arguments
+    ///
+    /// * `device`   - The connection to the graphics device. Used to create the rendering resources.
+    /// * `position` - The 3D position of the light source.
+    pub fn new_white(device: &Device, position: Vector3<f32>) -> Self {
+        return Self::new(device, position, Self::WHITE.into())
+    }
+
+    /// Get the color of the Light object.
+    pub fn get_color(&self) -> Vector3<f32> { self.color }
+
+    /// Set the color of the Light object.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The RGB value for the new color of the light.
+    /// * `core`  - Structure for holding the WGPU primitives for running a windowed application.
+    pub fn set_color(&mut self, color: Vector3<f32>, core: &StateCore) {
+        self.color = color;
+        self.update_buffer(core)
+    }
+
+    /// Get the position of the Light object.
+    pub fn get_position(&self) -> Vector3<f32> { self.position }
+
+    /// Set the color of the Light object.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The new 3D position of the light source.
+    /// * `core`     - Structure for holding the WGPU primitives for running a windowed application.
+    pub fn set_position(&mut self, position: Vector3<f32>, core: &StateCore) {
+        self.position = position;
+        self.update_buffer(core)
+    }
+
+    /// Get the color of the ambient light.
+    pub fn get_ambient_color(&self) -> Vector3<f32> { self.ambient_color }
+
+    /// Set the color of the ambient light.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The RGB value for the new color of the ambient light.
+    /// * `core`  - Structure for holding the WGPU primitives for running a windowed application.
+    pub fn set_ambient_color(&mut self, color: Vector3<f32>, core: &StateCore) {
+        self.ambient_color = color;
+        self.update_buffer(core)
+    }
+
+    /// Get the strength of the ambient light.
+    pub fn get_ambient_intensity(&self) -> f32 { self.ambient_intensity }
+
+    /// Set the strength of the ambient light.
+    ///
+    /// # Arguments
+    ///
+    /// * `intensity` - The new strength of the ambient light, where 0 disables it.
+    /// * `core`      - Structure for holding the WGPU primitives for running a windowed application.
+    pub fn set_ambient_intensity(&mut self, intensity: f32, core: &StateCore) {
+        self.ambient_intensity = intensity.max(0.0);
+        self.update_buffer(core)
+    }
+
+    /// Set the path the light source follows in `update`, restarting the animation.
+    ///
+    /// # Arguments
+    ///
+    /// * `animation` - The path to follow, or None to hold the light source in place.
+    pub fn set_animation(&mut self, animation: Option<Box<dyn LightAnimation>>) {
+        self.animation = animation;
+        self.animation_time = 0.0;
+    }
+
+    /// Advance the animation of the light source, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `dt`   - The time (in seconds) since the last update.
+    /// * `core` - Structure for holding the WGPU primitives for running a windowed application.
+    ///
+    /// # Returns
+    ///
+    /// Boolean of whether the light source moved. The buffer is only restaged if it did.
+    pub fn update(&mut self, dt: f32, core: &StateCore) -> bool {
+        let position = match &self.animation {
+            Some(animation) => {
+                self.animation_time += dt;
+                animation.position_at(self.animation_time)
+            }
+            None => return false,
+        };
+
+        let position = Vector3::new(position.x, position.y, position.z);
+        if position == self.position {
+            return false
+        }
+        self.set_position(position, core);
+        return true
+    }
+
+    /// Get the (constant, linear, quadratic) coefficients of the attenuation over distance.
+    pub fn get_attenuation(&self) -> Vector3<f32> { self.attenuation }
+
+    /// Get the number of staging buffers allocated by the updates of the Light.
+    pub fn get_staging_allocation_count(&self) -> u64 { self.staging.get_allocation_count() }
+
+    /// Set the coefficients of the attenuation over distance.
+    ///   The direct light is scaled by `1 / (constant + linear * d + quadratic * d * d)`,
+    ///   so `(1, 0, 0)` (the default) disables the attenuation.
+    ///
+    /// # Arguments
+    ///
+    /// * `constant`  - The constant coefficient.
+    /// * `linear`    - The coefficient of the distance.
+    /// * `quadratic` - The coefficient of the squared distance.
+    /// * `core`      - Structure for holding the WGPU primitives for running a windowed application.
+    pub fn set_attenuation(&mut self, constant: f32, linear: f32, quadratic: f32, core: &StateCore) {
+        self.attenuation = Vector3::new(constant, linear, quadratic);
+        self.update_buffer(core)
+    }
+
+    /// Update the buffer of LightRaw objects that is sent to the GPU.
+    ///
+    /// # Arguments
+    ///
+    /// * `core` - Structure for holding the WGPU primitives for running a windowed application.
+    fn update_buffer(&mut self, core: &StateCore) {
+        // Write the updated Buffer data through the reused staging buffers.
+        let light_raw = LightRaw::new(
+            self.position, self.color, self.ambient_color, self.ambient_intensity, self.attenuation
+        );
+        self.staging.write(core, bytemuck::cast_slice(&[light_raw]), &self.buffer);
+    }
+}
+
+
+/// The Raw Light structure that is sent to the GPU.
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct LightRaw {
+
+    // The Vector representing the 3D position of the light source.
+    pub position: cgmath::Vector3<f32>,
+
+    // Due to uniforms requiring 16 byte (4 float) spacing, we need to use a padding field.
+    _padding: f32,
+
+    // The RGB value for the color of the light.
+    pub color: cgmath::Vector3<f32>,
+
+    // The strength of the ambient light. This fills the space left after the color vector.
+    pub ambient_intensity: f32,
+
+    // The RGB value for the color of the ambient light.
+    pub ambient_color: cgmath::Vector3<f32>,
+
+    // The constant coefficient of the attenuation. This fills the space left after the ambient color.
+    pub attenuation_constant: f32,
+
+    // The coefficient of the distance in the attenuation.
+    pub attenuation_linear: f32,
+
+    // The coefficient of the squared distance in the attenuation.
+    pub attenuation_quadratic: f32,
+
+    // Padding to fill the remainder of the 16 byte spacing.
+    _padding_attenuation: [f32; 2],
+}
+
+unsafe impl bytemuck::Zeroable for LightRaw {}
+unsafe impl bytemuck::Pod for LightRaw {}
+
+impl LightRaw {
+    const PADDING: f32 = 0.0;
+    pub fn new(
+        position: Vector3<f32>,
+        color: Vector3<f32>,
+        ambient_color: Vector3<f32>,
+        ambient_intensity: f32,
+        attenuation: Vector3<f32>,
+    ) -> Self {
+        return LightRaw{
+            position,
+            _padding: Self::PADDING,
+            color,
+            ambient_intensity,
+            ambient_color,
+            attenuation_constant: attenuation.x,
+            attenuation_linear: attenuation.y,
+            attenuation_quadratic: attenuation.z,
+            _padding_attenuation: [Self::PADDING; 2],
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn light_is_padded_to_16_bytes() {
+        assert_eq!(std::mem::size_of::<LightRaw>() % 16, 0);
+        assert_eq!(std::mem::size_of::<LightRaw>(), std::mem::size_of::<f32>() * 16);
+    }
+
+    #[test]
+    fn light_fields_fill_the_padding_in_order() {
+        let light_raw = LightRaw::new(
+            Vector3::new(1.0, 2.0, 3.0),
+            Vector3::new(0.4, 0.5, 0.6),
+            Vector3::new(0.7, 0.8, 0.9),
+            0.25,
+            Vector3::new(1.5, 2.5, 3.5),
+        );
+        let floats: &[f32] = bytemuck::cast_slice(std::slice::from_ref(&light_raw));
+        assert_eq!(floats, &[
+            1.0, 2.0, 3.0, 0.0,
+            0.4, 0.5, 0.6, 0.25,
+            0.7, 0.8, 0.9, 1.5,
+            2.5, 3.5, 0.0, 0.0,
+        ]);
+    }
+}
@@ -0,0 +1,5 @@
+mod animation;
+mod light;
+
+pub use animation::{CircularOrbit, LightAnimation};
+pub use light::{Light, LightRaw, MAX_LIGHTS};
@@ -0,0 +1,111 @@
+use cgmath::Vector3;
+use wgpu::{BindGroup, Device};
+use super::LineVertex;
+
+/// A set of colored line segments generated in code, e.g. a ground grid.
+///   Lines are unlit and drawn with a `LineList` pipeline, two vertices per segment.
+pub struct Lines {
+
+    // The Buffer of LineVertex objects.
+    vertex_buffer: wgpu::Buffer,
+
+    // The number of vertices in the vertex buffer.
+    num_vertices: u32,
+
+    // Whether the Lines are visible, i.e. whether they should be rendered.
+    pub visible: bool,
+}
+
+impl Lines {
+
+    /// Create a new set of Lines.
+    ///
+    /// # Arguments
+    ///
+    /// * `device`   - The connection to the graphics device. Used to create the rendering resources.
+    /// * `vertices` - The end points of the line segments, two vertices per segment.
+    pub fn new(device: &Device, vertices: &[LineVertex]) -> Self {
+        let vertex_buffer = device.create_buffer_with_data(
+            bytemuck::cast_slice(vertices),
+            wgpu::BufferUsage::VERTEX,
+        );
+        return Lines { vertex_buffer, num_vertices: vertices.len() as u32, visible: true }
+    }
+
+    /// Create a square grid on the ground (XZ) plane, centered at the origin.
+    ///
+    /// # Arguments
+    ///
+    /// * `device`    - The connection to the graphics device. Used to create the rendering resources.
+    /// * `size`      - The length of the sides of the grid.
+    /// * `divisions` - The number of cells along each side of the grid.
+    /// * `color`     - The RGB color of the grid lines.
+    pub fn grid(device: &Device, size: f32, divisions: u32, color: Vector3<f32>) -> Self {
+        let divisions = divisions.max(1);
+        let half_size = size / 2.0;
+        let color: [f32; 3] = color.into();
+
+        let mut vertices = Vec::with_capacity(4 * (divisions as usize + 1));
+        for index in 0..=divisions {
+            let offset = -half_size + size * (index as f32 / divisions as f32);
+            // The line parallel to the X-axis.
+            vertices.push(LineVertex { position: [-half_size, 0.0, offset], color });
+            vertices.push(LineVertex { position: [half_size, 0.0, offset], color });
+            // The line parallel to the Z-axis.
+            vertices.push(LineVertex { position: [offset, 0.0, -half_size], color });
+            vertices.push(LineVertex { position: [offset, 0.0, half_size], color });
+        }
+        return Self::new(device, &vertices)
+    }
+
+    /// Create the coordinate axes at the origin: X in red, Y in green and Z in blue.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - The connection to the graphics device. Used to create the rendering resources.
+    /// * `length` - The length of each axis.
+    pub fn axes(device: &Device, length: f32) -> Self {
+        let origin = [0.0, 0.0, 0.0];
+        let vertices = [
+            LineVertex { position: origin, color: [1.0, 0.0, 0.0] },
+            LineVertex { position: [length, 0.0, 0.0], color: [1.0, 0.0, 0.0] },
+            LineVertex { position: origin, color: [0.0, 1.0, 0.0] },
+            LineVertex { position: [0.0, length, 0.0], color: [0.0, 1.0, 0.0] },
+            LineVertex { position: origin, color: [0.0, 0.0, 1.0] },
+            LineVertex { position: [0.0, 0.0, length], color: [0.0, 0.0, 1.0] },
+        ];
+        return Self::new(device, &vertices)
+    }
+}
+
+
+/// Trait for rendering Lines.
+pub trait DrawLines<'a, 'b> where 'b: 'a {
+
+    /// Draw Lines to the screen. The pipeline must use the `LineList` topology.
+    ///
+    /// # Arguments
+    ///
+    /// `lines`    - The Lines object to be drawn.
+    /// `uniforms` - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
+    fn draw_lines(&mut self, lines: &'b Lines, uniforms: &'b BindGroup);
+}
+
+/// Implement Lines drawing for the `wgpu::RenderPass` object.
+impl<'a, 'b> DrawLines<'a, 'b> for wgpu::RenderPass<'a> where 'b: 'a {
+
+    /// Draw Lines to the screen. The pipeline must use the `LineList` topology.
+    ///
+    /// # Arguments
+    ///
+    /// `lines`    - The Lines object to be drawn.
+    /// `uniforms` - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
+    fn draw_lines(&mut self, lines: &'b Lines, uniforms: &'b BindGroup) {
+        if !lines.visible {
+            return
+        }
+        self.set_vertex_buffer(0, &lines.vertex_buffer, 0, 0);
+        self.set_bind_group(0, uniforms, &[]);
+        self.draw(0..lines.num_vertices, 0..1);
+    }
+}
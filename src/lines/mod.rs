@@ -0,0 +1,5 @@
+mod lines;
+mod vertex;
+
+pub use lines::{DrawLines, Lines};
+pub use vertex::LineVertex;
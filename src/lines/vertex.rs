@@ -0,0 +1,48 @@
+use wgpu::{BufferAddress, VertexBufferDescriptor};
+use crate::model::Vertex;
+
+/// Describes a single vertex of a set of Lines.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct LineVertex {
+
+    // The 3D position of the vertex.
+    pub position: [f32; 3],
+
+    // The RGB color of the vertex.
+    pub color: [f32; 3],
+}
+
+/// Used for serializing the LineVertex structure.
+unsafe impl bytemuck::Pod for LineVertex {}
+unsafe impl bytemuck::Zeroable for LineVertex {}
+
+/// Constants describing the location in memory of the items in the structure.
+impl LineVertex {
+    pub const SIZE: BufferAddress = std::mem::size_of::<Self>() as BufferAddress;
+    pub const POSITION_OFFSET: BufferAddress = 0 as BufferAddress;
+    pub const COLOR_OFFSET: BufferAddress = std::mem::size_of::<[f32; 3]>() as BufferAddress;
+}
+
+impl Vertex for LineVertex {
+
+    /// Creates a `wgpu::VertexBufferDecriptor` that describes the `LineVertex` struct.
+    fn describe<'a>() -> VertexBufferDescriptor<'a> {
+        return VertexBufferDescriptor {
+            stride: Self::SIZE,
+            step_mode: wgpu::InputStepMode::Vertex,
+            attributes: &[
+                wgpu::VertexAttributeDescriptor {
+                    offset: Self::POSITION_OFFSET,
+                    shader_location: 0,
+                    format: wgpu::VertexFormat::Float3,
+                },
+                wgpu::VertexAttributeDescriptor {
+                    offset: Self::COLOR_OFFSET,
+                    shader_location: 1,
+                    format: wgpu::VertexFormat::Float3,
+                },
+            ]
+        }
+    }
+}
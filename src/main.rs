@@ -4,26 +4,79 @@ use winit::{
     window::WindowBuilder,
 };
 
-use arm_viz::state::State;
+use arm_viz::{
+    model::{Instance, DEFAULT_GRID_SIZE, DEFAULT_GRID_SPACING},
+    state::State,
+};
 
 
 fn main() {
+    // Logging is configured through the `RUST_LOG` environment variable.
+    env_logger::init();
+
+    // The size of the instance grid can be set from the command line:
+    //   arm_viz [rows] [columns] [spacing]
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (rows, columns, spacing) = match parse_grid_args(&args) {
+        Ok(grid) => grid,
+        Err(error) => {
+            log::error!("{}", error);
+            log::error!("usage: arm_viz [rows] [columns] [spacing]");
+            std::process::exit(2);
+        }
+    };
+
     let event_loop = EventLoop::new();
     let window = WindowBuilder::new()
+        .with_title("arm_viz")
         .build(&event_loop)
         .unwrap();
-    
-    let mut state = State::new(&window);
+
+    // The demo scene embeds its sphere, so the binary can run from any working directory.
+    let mut state = match State::new_demo(&window) {
+        Ok(state) => state,
+        Err(error) => {
+            log::error!("Failed to initialize the renderer: {}", error);
+            std::process::exit(1);
+        }
+    };
+    let sphere = state.get_model_ids()[0];
+    state.set_model_instances(sphere, Instance::grid(rows, columns, spacing));
+
+    // The framerate is shown in the window title if `ARM_VIZ_SHOW_FPS` is set.
+    let show_fps = std::env::var_os("ARM_VIZ_SHOW_FPS").is_some();
+    let mut last_title_update = std::time::Instant::now();
 
     event_loop.run(move |event, _, control_flow| {
         match event {
             Event::MainEventsCleared => {
                 // RedrawRequested will only trigger once, unless we manually request it.
-                window.request_redraw();
+                // If nothing in the scene changed, wait for the next event instead of spinning.
+                // If a target framerate is set, wait until the next frame is due.
+                if !state.needs_redraw() {
+                    *control_flow = ControlFlow::Wait;
+                } else if let Some(next_frame) = state.next_frame_time() {
+                    *control_flow = ControlFlow::WaitUntil(next_frame);
+                } else {
+                    *control_flow = ControlFlow::Poll;
+                    window.request_redraw();
+                }
             }
             Event::RedrawRequested(_) => {
                 state.update();
                 state.render();
+                if show_fps && last_title_update.elapsed().as_secs_f32() >= 1.0 {
+                    last_title_update = std::time::Instant::now();
+                    let title = format!(
+                        "arm_viz - {:.1} fps ({:.2} ms)",
+                        state.fps(),
+                        state.average_frame_time().as_secs_f64() * 1000.0,
+                    );
+                    state.set_window_title(title);
+                }
+                if let Some(title) = state.take_window_title() {
+                    window.set_title(&title);
+                }
             }
             Event::WindowEvent { ref event, window_id } if window_id == window.id() => 
                 if !state.input(event) {
@@ -54,3 +107,28 @@ fn main() {
         }
     });
 }
+
+/// Parse the size of the instance grid from the command line arguments (without the program name).
+///   The columns default to the rows, so a single argument makes a square grid.
+///
+/// # Returns
+///
+/// The (rows, columns, spacing) of the grid, or a description of the first invalid argument.
+fn parse_grid_args(args: &[String]) -> Result<(u32, u32, f32), String> {
+    let parse_count = |arg: &String, name: &str| -> Result<u32, String> {
+        return match arg.parse() {
+            Ok(count) if count > 0 => Ok(count),
+            _ => Err(format!("{} must be a positive integer, found {:?}", name, arg)),
+        }
+    };
+    let rows = args.get(0).map_or(Ok(DEFAULT_GRID_SIZE), |arg| parse_count(arg, "rows"))?;
+    let columns = args.get(1).map_or(Ok(rows), |arg| parse_count(arg, "columns"))?;
+    let spacing = match args.get(2) {
+        Some(arg) => match arg.parse::<f32>() {
+            Ok(spacing) if spacing.is_finite() => spacing,
+            _ => return Err(format!("spacing must be a finite number, found {:?}", arg)),
+        },
+        None => DEFAULT_GRID_SPACING,
+    };
+    return Ok((rows, columns, spacing))
+}
@@ -0,0 +1,23 @@
+use cgmath::{Matrix4, SquareMatrix};
+use wgpu::{BufferAddress, Device};
+
+/// The most bones that can move the vertices of a Model. This must match `MAX_BONES` in `model.vert`.
+pub const MAX_BONES: usize = 64;
+
+/// The size (in bytes) of the buffer of bone transforms.
+pub const BONES_SIZE: BufferAddress = (MAX_BONES * std::mem::size_of::<[[f32; 4]; 4]>()) as BufferAddress;
+
+/// Pack the bone transforms for the GPU. The bones past the given transforms don't move.
+pub(super) fn bones_to_raw(transforms: &[Matrix4<f32>]) -> Vec<[[f32; 4]; 4]> {
+    let mut bones_raw: Vec<[[f32; 4]; 4]> = transforms.iter().take(MAX_BONES).map(|&transform| transform.into()).collect();
+    bones_raw.resize(MAX_BONES, Matrix4::identity().into());
+    return bones_raw
+}
+
+/// Create the buffer of bone transforms, with none of the bones moved.
+pub(super) fn create_bones_buffer(device: &Device) -> wgpu::Buffer {
+    return device.create_buffer_with_data(
+        bytemuck::cast_slice(&bones_to_raw(&[])),
+        wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+    )
+}
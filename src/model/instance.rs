@@ -1,8 +1,12 @@
-use cgmath::{Matrix3, Matrix4, Vector3};
+use cgmath::{Matrix, Matrix3, Matrix4, Quaternion, SquareMatrix, Vector3};
 use wgpu::{BufferAddress, VertexBufferDescriptor};
 
+pub const DEFAULT_GRID_SIZE: u32 = 10;
+pub const DEFAULT_GRID_SPACING: f32 = 3.0;
+
 
 /// Describes an instance of an object for the model.
+#[derive(PartialEq)]
 pub struct Instance {
 
     // The position of the instance object.
@@ -10,9 +14,14 @@ pub struct Instance {
 
     // The rotation of the instance object.
     pub rotation: cgmath::Quaternion<f32>,
+
+    // The scale of the instance object along each of its axes. This is applied before the rotation.
+    pub scale: cgmath::Vector3<f32>,
 }
 
 impl Instance {
+
+    const UNIT_SCALE: [f32; 3] = [1.0, 1.0, 1.0];
     
     /// Construct an Instance object using a position vector.
     /// This is useful when the rotation of the instance does not matter.
@@ -22,14 +31,62 @@ impl Instance {
             use cgmath::One;
             Quaternion::one()
         };
-        return Instance { position, rotation }
+        return Instance { position, rotation, scale: Self::UNIT_SCALE.into() }
+    }
+
+    /// Construct a grid of Instances on the XZ plane, centered about the origin.
+    /// Each Instance is rotated about the vector pointing from the origin to its position.
+    ///
+    /// # Arguments
+    ///
+    /// * `rows`    - The number of rows of the grid (along the Z axis).
+    /// * `columns` - The number of columns of the grid (along the X axis).
+    /// * `spacing` - The distance between neighboring Instances.
+    pub fn grid(rows: u32, columns: u32, spacing: f32) -> Vec<Self> {
+        use cgmath::{InnerSpace, One, Quaternion, Rotation3, Zero};
+
+        return
+        (0..rows).flat_map(|z| {
+            (0..columns).map(move |x| {
+                let x = spacing * (x as f32 - columns as f32 / 2.0);
+                let z = spacing * (z as f32 - rows as f32 / 2.0);
+                let position = cgmath::Vector3 { x, y: 0.0, z };
+
+                let rotation = if position.is_zero() {
+                    // this is needed so an object at (0, 0, 0) won't get scaled to zero
+                    // as Quaternions can effect scale if they're not create correctly
+                    Quaternion::one()
+                } else {
+                    Quaternion::from_axis_angle(position.clone().normalize(), cgmath::Deg(45.0))
+                };
+
+                Instance { position, rotation, scale: Self::UNIT_SCALE.into() }
+            })
+        }).collect();
     }
 
     /// Construct an InstanceRaw object from this object.
     pub fn to_raw(&self) -> InstanceRaw {
+        InstanceRaw::new(self.model_matrix(), self.rotation)
+    }
+
+    /// Construct the Model matrix describing the position, rotation and scale of this Instance.
+    pub fn model_matrix(&self) -> Matrix4<f32> {
         let position_matrix = Matrix4::from_translation(self.position);
         let rotation_matrix = Matrix4::from(self.rotation);
-        InstanceRaw::new(position_matrix * rotation_matrix)
+        let scale_matrix = Matrix4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z);
+        return position_matrix * rotation_matrix * scale_matrix
+    }
+
+    /// The largest (absolute) scale of this Instance along any axis.
+    ///   This scales the bounding sphere of the Model for this Instance.
+    pub fn max_scale(&self) -> f32 {
+        return self.scale.x.abs().max(self.scale.y.abs()).max(self.scale.z.abs())
+    }
+
+    /// Pack a slice of Instances into the InstanceRaw objects that are sent to the GPU.
+    pub fn to_raw_vec(instances: &[Instance]) -> Vec<InstanceRaw> {
+        return instances.iter().map(Instance::to_raw).collect()
     }
 }
 
@@ -39,6 +96,7 @@ impl Default for Instance {
         return Instance {
             position: cgmath::Vector3::zero(),
             rotation: cgmath::Quaternion::one(),
+            scale: Self::UNIT_SCALE.into(),
         }
     }
 }
@@ -49,10 +107,11 @@ impl Default for Instance {
 #[derive(Copy, Clone)]
 pub struct InstanceRaw {
 
-    // Matrix describing the position and rotation of the istance object.
+    // Matrix describing the position, rotation and scale of the istance object.
     model: cgmath::Matrix4<f32>,
 
-    // The Normal matrix. This is derived from the Model matrix.
+    // The Normal matrix. This is the inverse transpose of the Model matrix, so that normals
+    // stay perpendicular to the surface under non-uniform scaling.
     // The computation is done on the CPU as it is more efficient.
     normal: cgmath::Matrix3<f32>,
 }
@@ -65,19 +124,31 @@ impl InstanceRaw {
     const FLOAT_SIZE: BufferAddress = std::mem::size_of::<f32>() as BufferAddress;
     const MODEL_SIZE: BufferAddress = Self::FLOAT_SIZE * 16;
 
-    pub fn new(model: cgmath::Matrix4<f32>) -> Self {
-        
-        let normal = {
-            use cgmath::SquareMatrix;
-            let m = model.invert().expect("Matrix not invertible");
-            // Do the transposition and conversion to 3x3 matrix in one step.
-            Matrix3::new(
-                m.x.x, m.y.x, m.z.x,
-                m.x.y, m.y.y, m.z.y,
-                m.x.z, m.y.z, m.z.z,
-            )
-        };
-        return InstanceRaw { model, normal }
+    /// Construct an InstanceRaw object from a Model matrix.
+    ///
+    /// # Arguments
+    ///
+    /// * `model`    - The Model matrix of the instance.
+    /// * `rotation` - The rotation of the instance, which turns the normals if the Model matrix
+    ///                  can't be inverted (i.e. the instance is scaled to zero along an axis).
+    pub fn new(model: Matrix4<f32>, rotation: Quaternion<f32>) -> Self {
+        return InstanceRaw { model, normal: normal_matrix(&model, rotation) }
+    }
+
+    /// Getter for the Model matrix.
+    pub fn model_matrix(&self) -> Matrix4<f32> { self.model }
+
+    /// Getter for the Normal matrix.
+    pub fn normal_matrix(&self) -> Matrix3<f32> { self.normal }
+}
+
+/// Compute the Normal matrix of a Model matrix, i.e. the inverse transpose of its 3x3 part.
+///   The translation doesn't move normals, so it is left out.
+fn normal_matrix(model: &Matrix4<f32>, rotation: Quaternion<f32>) -> Matrix3<f32> {
+    let linear = Matrix3::from_cols(model.x.truncate(), model.y.truncate(), model.z.truncate());
+    return match linear.invert() {
+        Some(inverse) => inverse.transpose(),
+        None => Matrix3::from(rotation),
     }
 }
 
@@ -92,40 +163,104 @@ impl super::Vertex for InstanceRaw {
                 wgpu::VertexAttributeDescriptor {
                     offset: 0,
                     format: wgpu::VertexFormat::Float4,
-                    shader_location: 3,
+                    shader_location: 5,
                 },
                 wgpu::VertexAttributeDescriptor {
                     offset: Self::FLOAT_SIZE * 4,
                     format: wgpu::VertexFormat::Float4,
-                    shader_location: 4,
+                    shader_location: 6,
                 },
                 wgpu::VertexAttributeDescriptor {
                     offset: Self::FLOAT_SIZE * 4 * 2,
                     format: wgpu::VertexFormat::Float4,
-                    shader_location: 5,
+                    shader_location: 7,
                 },
                 wgpu::VertexAttributeDescriptor {
                     offset: Self::FLOAT_SIZE * 4 * 3,
                     format: wgpu::VertexFormat::Float4,
-                    shader_location: 6,
+                    shader_location: 8,
                 },
                 // Describe the Normal matrix (3x3).
                 wgpu::VertexAttributeDescriptor {
                     offset: Self::MODEL_SIZE,
                     format: wgpu::VertexFormat::Float3,
-                    shader_location: 7,
+                    shader_location: 9,
                 },
                 wgpu::VertexAttributeDescriptor {
                     offset: Self::MODEL_SIZE + (Self::FLOAT_SIZE * 3),
                     format: wgpu::VertexFormat::Float3,
-                    shader_location: 8,
+                    shader_location: 10,
                 },
                 wgpu::VertexAttributeDescriptor {
                     offset: Self::MODEL_SIZE + (Self::FLOAT_SIZE * 3 * 2),
                     format: wgpu::VertexFormat::Float3,
-                    shader_location: 9,
+                    shader_location: 11,
                 },
             ]
         }
     }
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{Deg, InnerSpace, One, Rotation3};
+    use super::*;
+
+    fn scaled(x: f32, y: f32, z: f32) -> Instance {
+        let mut instance = Instance::from_position(Vector3::new(1.0, 2.0, 3.0));
+        instance.scale = Vector3::new(x, y, z);
+        return instance
+    }
+
+    #[test]
+    fn non_uniform_scale_keeps_normals_perpendicular() {
+        let normal = scaled(2.0, 1.0, 1.0).to_raw().normal_matrix();
+
+        let up = (normal * Vector3::unit_y()).normalize();
+        assert!((up - Vector3::unit_y()).magnitude() < 1e-6);
+
+        // Stretching along X flattens the surface, so its normal tilts toward +Y.
+        let diagonal = (normal * Vector3::new(1.0, 1.0, 0.0)).normalize();
+        assert!(diagonal.y > std::f32::consts::FRAC_1_SQRT_2);
+        assert!(diagonal.x > 0.0 && diagonal.z.abs() < 1e-6);
+    }
+
+    #[test]
+    fn instances_are_packed_in_order() {
+        let mut rotated = Instance::from_position(Vector3::new(-1.0, 0.0, 4.0));
+        rotated.rotation = Quaternion::from_angle_y(Deg(90.0));
+        let rotation = rotated.rotation;
+        let instances = [scaled(2.0, 3.0, 4.0), rotated];
+        let raw = Instance::to_raw_vec(&instances);
+
+        assert_eq!(raw.len(), 2);
+        for (instance, raw) in instances.iter().zip(raw.iter()) {
+            assert_eq!(raw.model_matrix(), instance.model_matrix());
+        }
+        assert_eq!(raw[1].model_matrix().w.truncate(), Vector3::new(-1.0, 0.0, 4.0));
+
+        // Without scaling, the Normal matrix is the rotation.
+        let difference = raw[1].normal_matrix() - Matrix3::from(rotation);
+        assert!(difference.x.magnitude() + difference.y.magnitude() + difference.z.magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn packed_instances_are_a_model_then_a_normal_matrix() {
+        assert_eq!(std::mem::size_of::<InstanceRaw>(), std::mem::size_of::<f32>() * (16 + 9));
+        assert_eq!(InstanceRaw::SIZE, InstanceRaw::MODEL_SIZE + InstanceRaw::FLOAT_SIZE * 9);
+
+        let raw = scaled(2.0, 1.0, 1.0).to_raw();
+        let floats: &[f32] = bytemuck::cast_slice(std::slice::from_ref(&raw));
+        let model: &[f32; 16] = raw.model_matrix().as_ref();
+        let normal: &[f32; 9] = raw.normal_matrix().as_ref();
+        assert_eq!(floats[..16], model[..]);
+        assert_eq!(floats[16..], normal[..]);
+    }
+
+    #[test]
+    fn zero_scale_falls_back_to_the_rotation() {
+        let normal = scaled(0.0, 1.0, 1.0).to_raw().normal_matrix();
+        assert_eq!(normal, Matrix3::from(Quaternion::one()));
+    }
+}
@@ -0,0 +1,956 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+};
+use cgmath::Vector3;
+use image::DynamicImage;
+use super::{model::compute_bounds, ModelVertex, MAX_BONES};
+
+
+type ModelDataResult = Result<ModelData, failure::Error>;
+
+/// The contents of a model file, read and decoded on the CPU but not yet uploaded to the GPU.
+///   This holds no GPU resources, so it can be created off of the main thread (see `PendingModel`).
+///   Use `Model::from_data` to upload it.
+pub struct ModelData {
+
+    // The meshes that make up the model.
+    pub meshes: Vec<MeshData>,
+
+    // The materials used by the meshes.
+    pub materials: Vec<MaterialData>,
+
+    // The unique images used by the materials. Materials referencing the same image share an entry.
+    pub textures: Vec<TextureData>,
+
+    // The corners of the axis-aligned box bounding the meshes (in model space).
+    pub bounds: (Vector3<f32>, Vector3<f32>),
+}
+
+/// The vertices and indices of a Mesh, before they are uploaded to the GPU.
+pub struct MeshData {
+
+    // An identifying name.
+    pub name: String,
+
+    // The vertices of the mesh.
+    pub vertices: Vec<ModelVertex>,
+
+    // The indices of the vertices making up each triangle.
+    pub indices: Vec<u32>,
+
+    // The index of the MaterialData for the mesh.
+    pub material: usize,
+}
+
+/// The textures of a Material, before they are uploaded to the GPU.
+pub struct MaterialData {
+
+    // An identifying name for the material.
+    pub name: String,
+
+    // The index of the diffuse TextureData.
+    pub diffuse_texture: usize,
+
+    // The index of the normal map TextureData.
+    pub normal_texture: usize,
+
+    // Whether the file marks the material as alpha blended. Materials whose diffuse texture
+    //   has translucent pixels are also treated as transparent (see `Material::transparent`).
+    pub transparent: bool,
+}
+
+/// A decoded image, before it is uploaded to the GPU as a Texture.
+pub struct TextureData {
+
+    // The label of the Texture.
+    pub label: String,
+
+    // The decoded image.
+    pub image: DynamicImage,
+
+    // Whether the image is a normal map, i.e. whether it holds linear rather than sRGB data.
+    pub is_normal_map: bool,
+}
+
+impl TextureData {
+
+    /// Whether any pixel of the image is translucent, i.e. whether it needs to be alpha blended.
+    pub fn has_translucency(&self) -> bool {
+        if self.is_normal_map || !self.image.color().has_alpha() {
+            return false
+        }
+        return self.image.to_rgba().pixels().any(|pixel| pixel[3] < 255)
+    }
+}
+
+impl ModelData {
+
+    /// Read and decode a model file and all corresponding textures.
+    ///   Files ending in `.gltf` or `.glb` are loaded as glTF, everything else as `.obj`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the model file. The corresponding texture files are assumed
+    ///              to be in the same directory as the model file.
+    pub fn load<P: AsRef<Path>>(path: P) -> ModelDataResult {
+        let path = path.as_ref();
+        let extension = path.extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_lowercase());
+        return match extension.as_ref().map(String::as_str) {
+            Some("gltf") | Some("glb") => Self::load_gltf(path),
+            _ => Self::load_obj(path),
+        }
+    }
+
+    /// Read and decode an `.obj` file and all corresponding textures.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the `.obj` file. The corresponding texture files are assumed
+    ///              to be in the same directory as the `.obj` file.
+    pub fn load_obj<P: AsRef<Path>>(path: P) -> ModelDataResult {
+        let path = path.as_ref();
+
+        // Parse the `.obj` file. Optional is enabled to triangulate mesh.
+        let (obj_models, obj_materials) = tobj::load_obj(path, true)?;
+
+        // We're assuming that the texture files are stored with the `.obj` file.
+        return Ok(Self::from_obj(obj_models, obj_materials, path.parent()))
+    }
+
+    /// Parse an `.obj` file and its `.mtl` file from memory, e.g. from `include_bytes!`.
+    ///   Textures can't be resolved without a directory, so the materials use white textures.
+    ///
+    /// # Arguments
+    ///
+    /// * `obj` - The contents of the `.obj` file.
+    /// * `mtl` - The contents of the `.mtl` file referenced by the `.obj` file.
+    pub fn load_obj_bytes(obj: &[u8], mtl: &[u8]) -> ModelDataResult {
+        let (obj_models, obj_materials) = tobj::load_obj_buf(
+            &mut std::io::Cursor::new(obj),
+            true,
+            |_| tobj::load_mtl_buf(&mut std::io::Cursor::new(mtl)),
+        )?;
+        return Ok(Self::from_obj(obj_models, obj_materials, None))
+    }
+
+    /// The sphere of the demo scene, which is compiled into the binary.
+    pub fn sphere() -> ModelDataResult {
+        return Self::load_obj_bytes(include_bytes!("../res/sphere.obj"), include_bytes!("../res/sphere.mtl"))
+    }
+
+    /// The box drawn at the position of the Light, which is compiled into the binary.
+    pub fn light_box() -> ModelDataResult {
+        return Self::load_obj_bytes(include_bytes!("../res/light.obj"), include_bytes!("../res/light.mtl"))
+    }
+
+    /// Convert the parsed `.obj` file into a ModelData object, decoding the textures.
+    ///
+    /// # Arguments
+    ///
+    /// * `obj_models`        - The models of the `.obj` file.
+    /// * `obj_materials`     - The materials of the `.mtl` file.
+    /// * `containing_folder` - The directory holding the texture files. If None, white textures are used.
+    fn from_obj(
+        obj_models: Vec<tobj::Model>,
+        obj_materials: Vec<tobj::Material>,
+        containing_folder: Option<&Path>,
+    ) -> ModelData {
+        // Iterate over the `tobj::Material` objects and decode their textures.
+        //    Materials referencing the same image file share a single texture.
+        let mut textures = Vec::new();
+        let mut materials = Vec::new();
+        let mut diffuse_textures: HashMap<Option<PathBuf>, usize> = HashMap::new();
+        let mut normal_textures: HashMap<Option<PathBuf>, usize> = HashMap::new();
+        for material in obj_materials {
+            let path = containing_folder.map(|folder| folder.join(&material.diffuse_texture));
+            let diffuse_texture = cached_texture(&mut diffuse_textures, path.clone(), &mut textures, || {
+                match &path {
+                    None => TextureData { label: String::from("white"), image: solid_color_image(WHITE), is_normal_map: false },
+                    Some(path) => {
+                        debug!("Loading texture {:?}", path);
+                        let image = image::open(path).unwrap_or_else(|error| {
+                            warn!("Failed to load texture {:?} ({}), falling back to a white texture", path, error);
+                            solid_color_image(WHITE)
+                        });
+                        TextureData { label: path.to_string_lossy().into_owned(), image, is_normal_map: false }
+                    }
+                }
+            });
+
+            // Materials without a normal map (`map_Bump`/`norm`) use a flat one.
+            let normal_path = containing_folder
+                .filter(|_| !material.normal_texture.is_empty())
+                .map(|folder| folder.join(&material.normal_texture));
+            let normal_texture = cached_texture(&mut normal_textures, normal_path.clone(), &mut textures, || {
+                match &normal_path {
+                    None => flat_normal_map(),
+                    Some(path) => {
+                        debug!("Loading normal map {:?}", path);
+                        let image = image::open(path).unwrap_or_else(|error| {
+                            warn!("Failed to load normal map {:?} ({}), falling back to a flat normal map", path, error);
+                            solid_color_image(FLAT_NORMAL)
+                        });
+                        TextureData { label: path.to_string_lossy().into_owned(), image, is_normal_map: true }
+                    }
+                }
+            });
+            materials.push(MaterialData { name: material.name.clone(), diffuse_texture, normal_texture, transparent: false });
+        }
+
+        // Meshes without a material (e.g. files without a `.mtl` file) use an additional white material.
+        let num_file_materials = materials.len();
+        let has_material = |model: &tobj::Model| model.mesh.material_id.map_or(false, |id| id < num_file_materials);
+        if !obj_models.iter().all(has_material) {
+            let diffuse_texture = cached_texture(&mut diffuse_textures, None, &mut textures, || {
+                TextureData { label: String::from("default"), image: solid_color_image(WHITE), is_normal_map: false }
+            });
+            let normal_texture = cached_texture(&mut normal_textures, None, &mut textures, flat_normal_map);
+            materials.push(MaterialData { name: String::from("default"), diffuse_texture, normal_texture, transparent: false });
+        }
+        let default_material = num_file_materials;
+
+        // Compute the bounds of the meshes, used for culling and framing.
+        let bounds = compute_bounds(
+            obj_models.iter()
+                .flat_map(|model| model.mesh.positions.chunks(3))
+                .map(|position| Vector3::new(position[0], position[1], position[2]))
+        );
+
+        // Iterate over the `tobj::Model` objects and convert them into `MeshData` objects.
+        let meshes = obj_models.into_iter()
+            .map(|model| {
+                let num_coords = model.mesh.positions.len() / 3;
+
+                // Files exported without texture coordinates or normals leave those vectors empty.
+                let has_tex_coords = model.mesh.texcoords.len() >= num_coords * 2;
+                let has_normals = model.mesh.normals.len() >= num_coords * 3;
+                if !has_tex_coords || !has_normals {
+                    debug!("Mesh {:?} is missing texture coordinates or normals", model.name);
+                }
+
+                let mut vertices: Vec<ModelVertex> = (0..num_coords)
+                    .map(|index| {
+                        ModelVertex {
+                            position: [
+                                model.mesh.positions[index * 3],
+                                model.mesh.positions[index * 3 + 1],
+                                model.mesh.positions[index * 3 + 2],
+                            ],
+                            tex_coords: if has_tex_coords {
+                                [model.mesh.texcoords[index * 2], model.mesh.texcoords[index * 2 + 1]]
+                            } else {
+                                [0.0, 0.0]
+                            },
+                            normal: if has_normals {
+                                [
+                                    model.mesh.normals[index * 3],
+                                    model.mesh.normals[index * 3 + 1],
+                                    model.mesh.normals[index * 3 + 2],
+                                ]
+                            } else {
+                                [0.0, 0.0, 0.0]
+                            },
+                            tangent: [0.0, 0.0, 0.0],
+                            bitangent: [0.0, 0.0, 0.0],
+                            bone_indices: ModelVertex::UNSKINNED_BONE_INDICES,
+                            bone_weights: ModelVertex::UNSKINNED_BONE_WEIGHTS,
+                        }
+                    }).collect();
+                if !has_normals {
+                    compute_smooth_normals(&mut vertices, &model.mesh.indices);
+                }
+                compute_tangents(&mut vertices, &model.mesh.indices);
+
+                MeshData {
+                    name: model.name,
+                    vertices,
+                    indices: model.mesh.indices,
+                    material: model.mesh.material_id.filter(|&id| id < num_file_materials).unwrap_or(default_material),
+                }
+            }).collect();
+
+        return ModelData { meshes, materials, textures, bounds }
+    }
+
+    /// Read and decode a `.gltf` or `.glb` file.
+    ///   The node transforms of the default scene are baked into the vertices of the meshes,
+    ///   and primitives without a material use an additional white material.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the glTF file.
+    pub fn load_gltf<P: AsRef<Path>>(path: P) -> ModelDataResult {
+        let (document, buffers, images) = gltf::import(path)?;
+
+        // Convert the glTF materials, using the base color texture (or factor) as the diffuse texture.
+        //    Materials referencing the same image (or color) share a single texture.
+        let mut textures = Vec::new();
+        let mut materials = Vec::new();
+        let mut diffuse_textures: HashMap<Result<usize, [u8; 4]>, usize> = HashMap::new();
+        let mut normal_textures: HashMap<Option<usize>, usize> = HashMap::new();
+        let images: Vec<Option<DynamicImage>> = images.iter().map(gltf_image_to_dynamic).collect();
+        for material in document.materials() {
+            let pbr = material.pbr_metallic_roughness();
+            let name = gltf_material_name(&material);
+
+            // Images in unsupported formats fall back to the base color factor.
+            let image_index = pbr.base_color_texture()
+                .map(|info| info.texture().source().index())
+                .filter(|index| images[*index].is_some());
+            let diffuse_key = image_index.ok_or_else(|| {
+                let [r, g, b, a] = pbr.base_color_factor();
+                let to_byte = |channel: f32| (channel.max(0.0).min(1.0) * 255.0) as u8;
+                [to_byte(r), to_byte(g), to_byte(b), to_byte(a)]
+            });
+            let diffuse_texture = cached_texture(&mut diffuse_textures, diffuse_key, &mut textures, || {
+                let image = match diffuse_key {
+                    Ok(index) => images[index].clone().unwrap(),
+                    Err(color) => solid_color_image(color),
+                };
+                TextureData { label: name.clone(), image, is_normal_map: false }
+            });
+
+            let normal_index = material.normal_texture()
+                .map(|info| info.texture().source().index())
+                .filter(|index| images[*index].is_some());
+            let normal_texture = cached_texture(&mut normal_textures, normal_index, &mut textures, || {
+                match normal_index {
+                    Some(index) => {
+                        TextureData { label: name.clone(), image: images[index].clone().unwrap(), is_normal_map: true }
+                    }
+                    None => flat_normal_map(),
+                }
+            });
+            let transparent = material.alpha_mode() == gltf::material::AlphaMode::Blend;
+            materials.push(MaterialData { name, diffuse_texture, normal_texture, transparent });
+        }
+        let default_material = materials.len();
+        let diffuse_texture = cached_texture(&mut diffuse_textures, Err(WHITE), &mut textures, || {
+            TextureData { label: String::from("default"), image: solid_color_image(WHITE), is_normal_map: false }
+        });
+        let normal_texture = cached_texture(&mut normal_textures, None, &mut textures, flat_normal_map);
+        materials.push(MaterialData { name: String::from("default"), diffuse_texture, normal_texture, transparent: false });
+
+        // Walk the scene graph, baking the node transforms into the vertices.
+        let mut primitives = Vec::new();
+        match document.default_scene().or_else(|| document.scenes().next()) {
+            Some(scene) => {
+                for node in scene.nodes() {
+                    collect_gltf_primitives(&node, cgmath::One::one(), &buffers, &mut primitives);
+                }
+            }
+            None => {
+                for mesh in document.meshes() {
+                    read_gltf_mesh(&mesh, cgmath::One::one(), &buffers, &mut primitives);
+                }
+            }
+        }
+
+        // Compute the bounds of the meshes, used for culling and framing.
+        let bounds = compute_bounds(
+            primitives.iter()
+                .flat_map(|primitive| primitive.vertices.iter())
+                .map(|vertex| Vector3::from(vertex.position))
+        );
+
+        let meshes = primitives.into_iter()
+            .map(|primitive| {
+                MeshData {
+                    name: primitive.name,
+                    vertices: primitive.vertices,
+                    indices: primitive.indices,
+                    material: primitive.material.unwrap_or(default_material),
+                }
+            }).collect();
+
+        return Ok(ModelData { meshes, materials, textures, bounds })
+    }
+}
+
+
+/// A model file that is being read and decoded on a background thread.
+///   Poll it with `try_take` (e.g. once per frame, to show a loading state) or block with `wait`,
+///   then upload the ModelData on the thread that owns the `wgpu::Device` with `Model::from_data`.
+pub struct PendingModel {
+
+    // The path to the model file.
+    path: PathBuf,
+
+    // Receives the result of the background thread.
+    receiver: Receiver<ModelDataResult>,
+
+    // Whether the result was already taken.
+    taken: bool,
+}
+
+impl PendingModel {
+
+    /// Start reading and decoding a model file (see `ModelData::load`) on a new thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the model file.
+    pub fn spawn<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let (sender, receiver) = mpsc::channel();
+
+        let thread_path = path.clone();
+        thread::spawn(move || {
+            info!("Loading model {:?} in the background", thread_path);
+            // The receiver may have been dropped, in which case nobody wants the result.
+            let _ = sender.send(ModelData::load(&thread_path));
+        });
+        return PendingModel { path, receiver, taken: false }
+    }
+
+    /// Get the path to the model file.
+    pub fn get_path(&self) -> &Path { &self.path }
+
+    /// Get the result of loading the model file without blocking.
+    ///
+    /// # Returns
+    ///
+    /// None while the model file is still loading (or after the result was taken), otherwise the result.
+    pub fn try_take(&mut self) -> Option<ModelDataResult> {
+        if self.taken {
+            return None
+        }
+        let result = match self.receiver.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => Err(failure::err_msg("the model loading thread panicked")),
+        };
+        self.taken = true;
+        return Some(result)
+    }
+
+    /// Block until the model file is loaded.
+    pub fn wait(self) -> ModelDataResult {
+        if self.taken {
+            return Err(failure::err_msg("the loaded model was already taken"))
+        }
+        return self.receiver.recv()
+            .unwrap_or_else(|_| Err(failure::err_msg("the model loading thread panicked")))
+    }
+}
+
+
+/// The color of the texture used when a material has no (loadable) diffuse texture.
+const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+/// The color of a flat normal map: the tangent space normal (0, 0, 1), mapped from [-1, 1] into [0, 1].
+const FLAT_NORMAL: [u8; 4] = [128, 128, 255, 255];
+
+/// Create a single pixel image of the given color.
+fn solid_color_image(color: [u8; 4]) -> DynamicImage {
+    use image::GenericImage;
+    let mut image = DynamicImage::new_rgba8(1, 1);
+    image.put_pixel(0, 0, image::Rgba(color));
+    return image
+}
+
+/// Create a flat normal map, i.e. one that leaves the normals of a surface unchanged.
+fn flat_normal_map() -> TextureData {
+    return TextureData {
+        label: String::from("Flat Normal Map"),
+        image: solid_color_image(FLAT_NORMAL),
+        is_normal_map: true,
+    }
+}
+
+/// Get the index of a texture from the cache, or decode it if it isn't cached yet.
+///   This way each unique texture is decoded and later uploaded to the GPU exactly once.
+///
+/// # Arguments
+///
+/// * `cache`    - The indices of the textures that were already decoded, by key.
+/// * `key`      - The key identifying the texture, e.g. the path of its image file.
+/// * `textures` - The decoded textures. A new texture is added here.
+/// * `load`     - Decodes the texture if it isn't in the cache.
+fn cached_texture<K, F>(cache: &mut HashMap<K, usize>, key: K, textures: &mut Vec<TextureData>, load: F) -> usize
+where
+    K: Eq + Hash,
+    F: FnOnce() -> TextureData,
+{
+    if let Some(&index) = cache.get(&key) {
+        return index
+    }
+    textures.push(load());
+    let index = textures.len() - 1;
+    cache.insert(key, index);
+    return index
+}
+
+/// A glTF primitive whose vertices have been transformed into model space.
+struct GltfPrimitive {
+    name: String,
+    vertices: Vec<ModelVertex>,
+    indices: Vec<u32>,
+    material: Option<usize>,
+}
+
+/// Recursively read the meshes of a glTF node and its children.
+fn collect_gltf_primitives(
+    node: &gltf::Node,
+    parent_transform: cgmath::Matrix4<f32>,
+    buffers: &[gltf::buffer::Data],
+    primitives: &mut Vec<GltfPrimitive>,
+) {
+    let transform = parent_transform * cgmath::Matrix4::from(node.transform().matrix());
+    if let Some(mesh) = node.mesh() {
+        // Skinned meshes are placed by their bones, so the transforms of the nodes are ignored.
+        let mesh_transform = if node.skin().is_some() {
+            use cgmath::SquareMatrix;
+            cgmath::Matrix4::identity()
+        } else {
+            transform
+        };
+        read_gltf_mesh(&mesh, mesh_transform, buffers, primitives);
+    }
+    for child in node.children() {
+        collect_gltf_primitives(&child, transform, buffers, primitives);
+    }
+}
+
+/// Read the primitives of a glTF mesh, transforming the positions and normals.
+fn read_gltf_mesh(
+    mesh: &gltf::Mesh,
+    transform: cgmath::Matrix4<f32>,
+    buffers: &[gltf::buffer::Data],
+    primitives: &mut Vec<GltfPrimitive>,
+) {
+    use cgmath::{InnerSpace, Matrix, Matrix3, SquareMatrix, Transform};
+
+    // Normals are transformed by the inverse transpose, so that scaling doesn't skew them.
+    let normal_transform = Matrix3::from_cols(transform.x.truncate(), transform.y.truncate(), transform.z.truncate())
+        .invert()
+        .map(|inverse| inverse.transpose())
+        .unwrap_or_else(Matrix3::identity);
+    let name = mesh.name().unwrap_or("mesh");
+
+    for primitive in mesh.primitives() {
+        if primitive.mode() != gltf::mesh::Mode::Triangles {
+            warn!("Skipping non-triangle primitive in mesh {:?}", name);
+            continue
+        }
+        let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()].0[..]));
+        let positions: Vec<[f32; 3]> = match reader.read_positions() {
+            Some(positions) => positions.collect(),
+            None => continue,
+        };
+        let normals: Vec<[f32; 3]> = reader.read_normals()
+            .map(|normals| normals.collect())
+            .unwrap_or_default();
+        let tex_coords: Vec<[f32; 2]> = reader.read_tex_coords(0)
+            .map(|tex_coords| tex_coords.into_f32().collect())
+            .unwrap_or_default();
+        let bone_indices: Vec<[u16; 4]> = reader.read_joints(0)
+            .map(|joints| joints.into_u16().collect())
+            .unwrap_or_default();
+        let bone_weights: Vec<[f32; 4]> = reader.read_weights(0)
+            .map(|weights| weights.into_f32().collect())
+            .unwrap_or_default();
+        let indices: Vec<u32> = match reader.read_indices() {
+            Some(indices) => indices.into_u32().collect(),
+            None => (0..positions.len() as u32).collect(),
+        };
+
+        // The shader only holds `MAX_BONES` bone matrices, so the influences of the other joints are dropped.
+        let mut dropped_influences = 0;
+        let mut vertices: Vec<ModelVertex> = positions.iter().enumerate()
+            .map(|(index, position)| {
+                let weights = bone_weights.get(index).cloned().unwrap_or(ModelVertex::UNSKINNED_BONE_WEIGHTS);
+                let (bone_indices, bone_weights) = match bone_indices.get(index) {
+                    Some(&joints) => {
+                        let (bone_indices, bone_weights, dropped) = clamp_bone_influences(joints, weights);
+                        dropped_influences += dropped as usize;
+                        (bone_indices, bone_weights)
+                    }
+                    None => (ModelVertex::UNSKINNED_BONE_INDICES, weights),
+                };
+                let position = transform.transform_point(cgmath::Point3::from(*position));
+                let normal = normals.get(index)
+                    .map(|normal| normal_transform * Vector3::from(*normal))
+                    .filter(|normal| normal.magnitude2() > 0.0)
+                    .map(|normal| normal.normalize())
+                    .unwrap_or(Vector3::new(0.0, 0.0, 0.0));
+                ModelVertex {
+                    position: position.into(),
+                    tex_coords: tex_coords.get(index).cloned().unwrap_or([0.0, 0.0]),
+                    normal: normal.into(),
+                    tangent: [0.0, 0.0, 0.0],
+                    bitangent: [0.0, 0.0, 0.0],
+                    bone_indices,
+                    bone_weights,
+                }
+            }).collect();
+        if dropped_influences > 0 {
+            warn!(
+                "Dropped the influences of joints past the first {} from {} vertices of mesh {:?}",
+                MAX_BONES, dropped_influences, name,
+            );
+        }
+        if normals.is_empty() {
+            compute_smooth_normals(&mut vertices, &indices);
+        }
+        compute_tangents(&mut vertices, &indices);
+
+        primitives.push(GltfPrimitive {
+            name: String::from(name),
+            vertices,
+            indices,
+            material: primitive.material().index(),
+        });
+    }
+}
+
+/// Drop the influences of the bones that the shader can't hold (see `MAX_BONES`) from a vertex.
+///   The remaining weights are rescaled to sum to one. A vertex left without any influence isn't skinned.
+///
+/// # Arguments
+///
+/// * `indices` - The `JOINTS_0` bone indices of the vertex.
+/// * `weights` - The `WEIGHTS_0` bone weights of the vertex.
+///
+/// # Returns
+///
+/// The bone indices and weights of the vertex, and whether any (weighted) influence was dropped.
+fn clamp_bone_influences(indices: [u16; 4], weights: [f32; 4]) -> ([u32; 4], [f32; 4], bool) {
+    let mut clamped_indices = [0; 4];
+    let mut clamped_weights = [0.0; 4];
+    let mut dropped = false;
+    for slot in 0..4 {
+        if (indices[slot] as usize) < MAX_BONES {
+            clamped_indices[slot] = indices[slot] as u32;
+            clamped_weights[slot] = weights[slot];
+        } else {
+            dropped |= weights[slot] != 0.0;
+        }
+    }
+    if !dropped {
+        return (clamped_indices, clamped_weights, false)
+    }
+
+    let total: f32 = clamped_weights.iter().sum();
+    if total <= 0.0 {
+        return (ModelVertex::UNSKINNED_BONE_INDICES, ModelVertex::UNSKINNED_BONE_WEIGHTS, true)
+    }
+    for weight in clamped_weights.iter_mut() {
+        *weight /= total;
+    }
+    return (clamped_indices, clamped_weights, true)
+}
+
+/// The name of a glTF material. Unnamed materials are named after their index, so that the names stay unique.
+fn gltf_material_name(material: &gltf::Material) -> String {
+    return match material.name() {
+        Some(name) => String::from(name),
+        None => format!("material {}", material.index().unwrap_or(0)),
+    }
+}
+
+/// Convert a decoded glTF image into an `image::DynamicImage`.
+///   Returns None for pixel formats that aren't 8-bit RGB(A).
+fn gltf_image_to_dynamic(image: &gltf::image::Data) -> Option<image::DynamicImage> {
+    use gltf::image::Format;
+    use image::{DynamicImage, RgbImage, RgbaImage};
+
+    let pixels = image.pixels.clone();
+    let converted = match image.format {
+        Format::R8G8B8A8 => RgbaImage::from_raw(image.width, image.height, pixels).map(DynamicImage::ImageRgba8),
+        Format::R8G8B8 => RgbImage::from_raw(image.width, image.height, pixels).map(DynamicImage::ImageRgb8),
+        _ => None,
+    };
+    if converted.is_none() {
+        warn!("Unsupported glTF image format {:?}, falling back to the base color", image.format);
+    }
+    return converted
+}
+
+/// Compute smooth vertex normals by averaging the normals of the triangles that share each vertex.
+///   Triangles are assumed to be wound counter-clockwise, matching the `Ccw` front face of the pipelines,
+///   and each face normal is weighted by the area of its triangle.
+///
+/// # Arguments
+///
+/// * `vertices` - The vertices whose normals are overwritten.
+/// * `indices`  - The indices of the vertices making up each triangle.
+fn compute_smooth_normals(vertices: &mut [ModelVertex], indices: &[u32]) {
+    use cgmath::{InnerSpace, Zero};
+
+    let mut normals = vec![Vector3::<f32>::zero(); vertices.len()];
+    for triangle in indices.chunks_exact(3) {
+        let (a, b, c) = (triangle[0] as usize, triangle[1] as usize, triangle[2] as usize);
+        if a >= vertices.len() || b >= vertices.len() || c >= vertices.len() {
+            continue
+        }
+        let position_a = Vector3::from(vertices[a].position);
+        let position_b = Vector3::from(vertices[b].position);
+        let position_c = Vector3::from(vertices[c].position);
+
+        // The cross product's length is twice the triangle's area, which gives the weighting.
+        let face_normal = (position_b - position_a).cross(position_c - position_a);
+        normals[a] += face_normal;
+        normals[b] += face_normal;
+        normals[c] += face_normal;
+    }
+
+    for (vertex, normal) in vertices.iter_mut().zip(normals) {
+        vertex.normal = if normal.magnitude2() > 0.0 { normal.normalize().into() } else { [0.0, 0.0, 0.0] };
+    }
+}
+
+/// Compute the tangent and bitangent of each vertex from the texture coordinates of its triangles,
+///   orthogonalized against the vertex normal. These define the tangent space used by normal maps.
+///   Vertices whose texture coordinates are degenerate get an arbitrary basis perpendicular to the normal.
+///
+/// # Arguments
+///
+/// * `vertices` - The vertices whose tangents and bitangents are overwritten.
+/// * `indices`  - The indices of the vertices making up each triangle.
+fn compute_tangents(vertices: &mut [ModelVertex], indices: &[u32]) {
+    use cgmath::{InnerSpace, Vector2, Zero};
+
+    let mut tangents = vec![Vector3::<f32>::zero(); vertices.len()];
+    let mut bitangents = vec![Vector3::<f32>::zero(); vertices.len()];
+    for triangle in indices.chunks_exact(3) {
+        let (a, b, c) = (triangle[0] as usize, triangle[1] as usize, triangle[2] as usize);
+        if a >= vertices.len() || b >= vertices.len() || c >= vertices.len() {
+            continue
+        }
+        let edge_1 = Vector3::from(vertices[b].position) - Vector3::from(vertices[a].position);
+        let edge_2 = Vector3::from(vertices[c].position) - Vector3::from(vertices[a].position);
+        let delta_uv_1 = Vector2::from(vertices[b].tex_coords) - Vector2::from(vertices[a].tex_coords);
+        let delta_uv_2 = Vector2::from(vertices[c].tex_coords) - Vector2::from(vertices[a].tex_coords);
+
+        let determinant = delta_uv_1.x * delta_uv_2.y - delta_uv_2.x * delta_uv_1.y;
+        if determinant.abs() < std::f32::EPSILON {
+            continue
+        }
+        let r = 1.0 / determinant;
+        let tangent = (edge_1 * delta_uv_2.y - edge_2 * delta_uv_1.y) * r;
+        let bitangent = (edge_2 * delta_uv_1.x - edge_1 * delta_uv_2.x) * r;
+        for &index in &[a, b, c] {
+            tangents[index] += tangent;
+            bitangents[index] += bitangent;
+        }
+    }
+
+    for (vertex, (tangent, bitangent)) in vertices.iter_mut().zip(tangents.into_iter().zip(bitangents)) {
+        let normal = Vector3::from(vertex.normal);
+
+        // Gram-Schmidt orthogonalize the tangent against the normal.
+        let tangent = tangent - normal * normal.dot(tangent);
+        let tangent = if tangent.magnitude2() > 0.0 {
+            tangent.normalize()
+        } else {
+            // Any vector perpendicular to the normal will do.
+            let axis = if normal.x.abs() < 0.9 { Vector3::unit_x() } else { Vector3::unit_y() };
+            let tangent = axis - normal * normal.dot(axis);
+            if tangent.magnitude2() > 0.0 { tangent.normalize() } else { axis }
+        };
+
+        // Keep the handedness of the texture coordinates.
+        let mut bitangent_out = normal.cross(tangent);
+        if bitangent.dot(bitangent_out) < 0.0 {
+            bitangent_out = -bitangent_out;
+        }
+        vertex.tangent = tangent.into();
+        vertex.bitangent = bitangent_out.into();
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use cgmath::InnerSpace;
+    use super::*;
+
+    fn vertex(position: [f32; 3]) -> ModelVertex {
+        return ModelVertex {
+            position,
+            tex_coords: [0.0, 0.0],
+            normal: [0.0, 0.0, 0.0],
+            tangent: [0.0, 0.0, 0.0],
+            bitangent: [0.0, 0.0, 0.0],
+            bone_indices: ModelVertex::UNSKINNED_BONE_INDICES,
+            bone_weights: ModelVertex::UNSKINNED_BONE_WEIGHTS,
+        }
+    }
+
+    /// A cube about the origin, with the corners shared between its faces.
+    ///   The bits of each corner's index pick its (x, y, z) side, and each face is wound counter-clockwise
+    ///   when seen from outside.
+    fn cube() -> (Vec<ModelVertex>, Vec<u32>) {
+        let side = |bit: u32| if bit == 0 { -1.0 } else { 1.0 };
+        let vertices = (0..8u32)
+            .map(|corner| vertex([side(corner >> 2 & 1), side(corner >> 1 & 1), side(corner & 1)]))
+            .collect();
+        let faces: [[u32; 4]; 6] = [
+            [4, 6, 7, 5], // +X
+            [0, 1, 3, 2], // -X
+            [2, 3, 7, 6], // +Y
+            [0, 4, 5, 1], // -Y
+            [1, 5, 7, 3], // +Z
+            [0, 2, 6, 4], // -Z
+        ];
+        let indices = faces.iter()
+            .flat_map(|&[a, b, c, d]| vec![a, b, c, a, c, d])
+            .collect();
+        return (vertices, indices)
+    }
+
+    #[test]
+    fn smooth_normals_of_a_cube_point_outward() {
+        let (mut vertices, indices) = cube();
+        compute_smooth_normals(&mut vertices, &indices);
+        for vertex in &vertices {
+            let normal = Vector3::from(vertex.normal);
+            let outward = Vector3::from(vertex.position).normalize();
+            assert!((normal.magnitude() - 1.0).abs() < 1e-5, "{:?} isn't unit length", normal);
+            assert!(normal.dot(outward) > 0.5, "{:?} doesn't point out of {:?}", normal, vertex.position);
+        }
+    }
+
+    #[test]
+    fn smooth_normals_skip_out_of_range_indices() {
+        let mut vertices = vec![vertex([0.0, 0.0, 0.0]), vertex([1.0, 0.0, 0.0]), vertex([0.0, 1.0, 0.0])];
+        compute_smooth_normals(&mut vertices, &[0, 1, 2, 0, 1, 9]);
+        for vertex in &vertices {
+            assert_eq!(vertex.normal, [0.0, 0.0, 1.0]);
+        }
+    }
+
+    #[test]
+    fn positions_only_obj_loads() {
+        let obj = b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+        let data = ModelData::load_obj_bytes(obj, b"").unwrap();
+        assert_eq!(data.meshes.len(), 1);
+        assert_eq!(data.bounds, (Vector3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 1.0, 0.0)));
+
+        // The missing texture coordinates are zeroed, and the missing normals are computed from the faces.
+        let mesh = &data.meshes[0];
+        assert_eq!(mesh.indices, vec![0, 1, 2]);
+        assert_eq!(mesh.vertices.len(), 3);
+        for vertex in &mesh.vertices {
+            assert_eq!(vertex.tex_coords, [0.0, 0.0]);
+            assert_eq!(vertex.normal, [0.0, 0.0, 1.0]);
+            assert!(vertex.tangent.iter().chain(vertex.bitangent.iter()).all(|value| value.is_finite()));
+        }
+
+        // Without a `.mtl` file the mesh uses the default material, so it can be drawn.
+        let material = &data.materials[mesh.material];
+        assert_eq!(material.name, "default");
+        assert!(!data.textures[material.diffuse_texture].is_normal_map);
+        assert!(data.textures[material.normal_texture].is_normal_map);
+    }
+
+    #[test]
+    fn embedded_light_box_parses_without_the_file_system() {
+        let (models, materials) = tobj::load_obj_buf(
+            &mut std::io::Cursor::new(&include_bytes!("../res/light.obj")[..]),
+            true,
+            |_| tobj::load_mtl_buf(&mut std::io::Cursor::new(&include_bytes!("../res/light.mtl")[..])),
+        ).unwrap();
+        assert!(!models.is_empty());
+        assert!(!materials.is_empty());
+
+        let data = ModelData::light_box().unwrap();
+        assert!(data.meshes.iter().all(|mesh| !mesh.indices.is_empty() && mesh.material < data.materials.len()));
+    }
+
+    #[test]
+    fn obj_materials_keep_their_names() {
+        let obj = b"mtllib two.mtl\n\
+            v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\n\
+            o red_part\nusemtl Red\nf 1 2 3\n\
+            o blue_part\nusemtl Blue\nf 1 3 4\n";
+        let mtl = b"newmtl Blue\nKd 0 0 1\n\nnewmtl Red\nKd 1 0 0\n";
+        let data = ModelData::load_obj_bytes(obj, mtl).unwrap();
+
+        let names: Vec<&str> = data.materials.iter().map(|material| material.name.as_str()).collect();
+        assert_eq!(names, vec!["Blue", "Red"]);
+        let mesh_materials: Vec<(&str, &str)> = data.meshes.iter()
+            .map(|mesh| (mesh.name.as_str(), data.materials[mesh.material].name.as_str()))
+            .collect();
+        assert_eq!(mesh_materials, vec![("red_part", "Red"), ("blue_part", "Blue")]);
+    }
+
+    #[test]
+    fn texture_cache_decodes_each_key_once() {
+        let mut cache = HashMap::new();
+        let mut textures = Vec::new();
+        let mut decoded = 0;
+        let mut load = |key: &str| cached_texture(&mut cache, PathBuf::from(key), &mut textures, || {
+            decoded += 1;
+            TextureData { label: String::from(key), image: solid_color_image(WHITE), is_normal_map: false }
+        });
+
+        let first = load("shared.png");
+        let second = load("shared.png");
+        let other = load("other.png");
+        assert_eq!(first, second);
+        assert_ne!(first, other);
+        assert_eq!(decoded, 2);
+        assert_eq!(textures.len(), 2);
+    }
+
+    #[test]
+    fn materials_sharing_an_image_share_a_texture() {
+        let obj = b"mtllib shared.mtl\nv 0 0 0\nv 1 0 0\nv 0 1 0\n\
+            o first\nusemtl First\nf 1 2 3\no second\nusemtl Second\nf 1 3 2\n";
+        let mtl = b"newmtl First\nmap_Kd shared.png\n\nnewmtl Second\nmap_Kd shared.png\n";
+        let (obj_models, obj_materials) = tobj::load_obj_buf(
+            &mut std::io::Cursor::new(&obj[..]),
+            true,
+            |_| tobj::load_mtl_buf(&mut std::io::Cursor::new(&mtl[..])),
+        ).unwrap();
+
+        // The image doesn't exist, so it falls back to white, but it is still only decoded once.
+        let data = ModelData::from_obj(obj_models, obj_materials, Some(Path::new("missing-textures")));
+        assert_eq!(data.materials.len(), 2);
+        assert_eq!(data.materials[0].diffuse_texture, data.materials[1].diffuse_texture);
+        assert_eq!(data.materials[0].normal_texture, data.materials[1].normal_texture);
+
+        // One diffuse texture and one flat normal map, i.e. one GPU texture each in `Model::from_data`.
+        assert_eq!(data.textures.len(), 2);
+    }
+
+    #[test]
+    fn bones_within_the_limit_are_kept() {
+        let bones = clamp_bone_influences([0, 5, 63, 0], [0.25, 0.25, 0.5, 0.0]);
+        assert_eq!(bones, ([0, 5, 63, 0], [0.25, 0.25, 0.5, 0.0], false));
+    }
+
+    #[test]
+    fn bones_past_the_limit_are_dropped() {
+        // An unweighted influence past the limit is dropped silently.
+        let bones = clamp_bone_influences([1, 64, 0, 0], [1.0, 0.0, 0.0, 0.0]);
+        assert_eq!(bones, ([1, 0, 0, 0], [1.0, 0.0, 0.0, 0.0], false));
+
+        let (indices, weights, dropped) = clamp_bone_influences([2, 100, 3, 0], [0.25, 0.5, 0.25, 0.0]);
+        assert!(dropped);
+        assert_eq!(indices, [2, 0, 3, 0]);
+        assert_eq!(weights, [0.5, 0.0, 0.5, 0.0]);
+    }
+
+    #[test]
+    fn vertices_without_bones_in_the_limit_are_not_skinned() {
+        let bones = clamp_bone_influences([64, 65, 0, 0], [0.5, 0.5, 0.0, 0.0]);
+        assert_eq!(bones, (ModelVertex::UNSKINNED_BONE_INDICES, ModelVertex::UNSKINNED_BONE_WEIGHTS, true));
+    }
+
+    #[test]
+    fn unnamed_gltf_materials_are_named_after_their_index() {
+        let json = r#"{"asset": {"version": "2.0"}, "materials": [{"name": "steel"}, {}, {}]}"#;
+        let gltf = gltf::Gltf::from_slice(json.as_bytes()).unwrap();
+        let names: Vec<String> = gltf.document.materials().map(|material| gltf_material_name(&material)).collect();
+        assert_eq!(names, ["steel", "material 1", "material 2"]);
+    }
+}
@@ -1,12 +1,141 @@
+use std::rc::Rc;
+use crate::texture::Texture;
+use super::BONES_SIZE;
+
 /// Decribes the Testure and the associated components for rendering.
 pub struct Material {
 
     // An identifying name for the material.
     pub name: String,
 
-    // The Texture object.
-    pub diffuse_texture: crate::texture::Texture,
+    // The Texture object. This may be shared with other Materials that use the same image.
+    pub diffuse_texture: Rc<Texture>,
+
+    // The tangent space normal map. This is flat when the material has no normal map.
+    pub normal_texture: Rc<Texture>,
 
     // The bind group used for rendering.
     pub bind_group: wgpu::BindGroup,
-}
\ No newline at end of file
+
+    // The buffer of bone transforms of the Model that the Material belongs to, bound along with the Textures.
+    bones: Rc<wgpu::Buffer>,
+
+    // Whether the Material is alpha blended. Transparent Materials are drawn after the opaque ones,
+    //   without writing to the depth buffer.
+    pub transparent: bool,
+
+    // The diffuse Texture that was replaced by an override (see `override_diffuse_texture`), if any.
+    original_diffuse_texture: Option<Rc<Texture>>,
+}
+
+impl Material {
+
+    /// Create a Material, along with the bind group used for rendering, from its Textures.
+    ///
+    /// # Arguments
+    ///
+    /// * `device`          - The connection to the graphics device. Used to create the rendering resources.
+    /// * `layout`          - The `wgpu::BindGroupLayout` object corresponding to the textures bind group.
+    /// * `name`            - An identifying name for the material.
+    /// * `diffuse_texture` - The Texture object.
+    /// * `normal_texture`  - The tangent space normal map (see `Texture::flat_normal_map`).
+    /// * `bones`           - The buffer of bone transforms of the Model (see `Model::set_bone_transforms`).
+    pub fn new(
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        name: String,
+        diffuse_texture: Rc<Texture>,
+        normal_texture: Rc<Texture>,
+        bones: Rc<wgpu::Buffer>,
+    ) -> Self {
+        let bind_group = create_bind_group(device, layout, &diffuse_texture, &normal_texture, &bones);
+        return Material {
+            name,
+            diffuse_texture,
+            normal_texture,
+            bind_group,
+            bones,
+            transparent: false,
+            original_diffuse_texture: None,
+        }
+    }
+
+    /// Replace the diffuse Texture, rebuilding the bind group. The original Texture is kept,
+    ///   so the override can be reverted with `revert_diffuse_texture`.
+    ///
+    /// # Arguments
+    ///
+    /// * `device`  - The connection to the graphics device. Used to create the rendering resources.
+    /// * `layout`  - The `wgpu::BindGroupLayout` object corresponding to the textures bind group.
+    /// * `texture` - The Texture that replaces the diffuse Texture.
+    pub fn override_diffuse_texture(
+        &mut self,
+        device: &wgpu::Device,
+        layout: &wgpu::BindGroupLayout,
+        texture: Rc<Texture>,
+    ) {
+        let replaced = std::mem::replace(&mut self.diffuse_texture, texture);
+        // Overriding an override keeps the Texture the Material was created with.
+        self.original_diffuse_texture.get_or_insert(replaced);
+        self.bind_group = create_bind_group(device, layout, &self.diffuse_texture, &self.normal_texture, &self.bones);
+    }
+
+    /// Restore the diffuse Texture the Material was created with, rebuilding the bind group.
+    ///
+    /// # Returns
+    ///
+    /// Whether the diffuse Texture was overridden.
+    pub fn revert_diffuse_texture(&mut self, device: &wgpu::Device, layout: &wgpu::BindGroupLayout) -> bool {
+        return match self.original_diffuse_texture.take() {
+            Some(original) => {
+                self.diffuse_texture = original;
+                self.bind_group = create_bind_group(
+                    device, layout, &self.diffuse_texture, &self.normal_texture, &self.bones
+                );
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether the diffuse Texture is overridden.
+    pub fn is_overridden(&self) -> bool { self.original_diffuse_texture.is_some() }
+}
+
+/// Create the bind group used for rendering a Material.
+fn create_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    diffuse_texture: &Texture,
+    normal_texture: &Texture,
+    bones: &wgpu::Buffer,
+) -> wgpu::BindGroup {
+    return device.create_bind_group(
+        &wgpu::BindGroupDescriptor {
+            layout,
+            bindings: &[
+                wgpu::Binding {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&diffuse_texture.view)
+                },
+                wgpu::Binding {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&diffuse_texture.sampler)
+                },
+                wgpu::Binding {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(&normal_texture.view)
+                },
+                wgpu::Binding {
+                    binding: 3,
+                    resource: wgpu::BindingResource::Sampler(&normal_texture.sampler)
+                },
+                wgpu::Binding {
+                    binding: 4,
+                    resource: wgpu::BindingResource::Buffer { buffer: bones, range: 0..BONES_SIZE }
+                },
+            ],
+            label: None,
+        }
+    )
+}
@@ -13,7 +13,108 @@ pub struct Mesh {
     // The number of elements in the mesh.
     pub num_elements: u32,
 
+    // The format of the indices in the index buffers. Meshes with few enough vertices use 16-bit indices.
+    pub index_format: wgpu::IndexFormat,
+
+    // The Buffer for indices of the unique triangle edges, drawn as a line list in wireframe mode.
+    pub edge_index_buffer: wgpu::Buffer,
+
+    // The number of elements in the edge index buffer.
+    pub num_edge_elements: u32,
+
     // The index of the Material for the Mesh.
     // This is used for lookup in the Model's vector of Materials.
     pub material: usize,
-}
\ No newline at end of file
+}
+
+impl Mesh {
+
+    /// Create a Mesh, uploading the vertices and indices into GPU Buffers.
+    ///
+    /// # Arguments
+    ///
+    /// * `device`   - The connection to the graphics device. Used to create the rendering resources.
+    /// * `name`     - An identifying name.
+    /// * `vertices` - The vertices of the mesh.
+    /// * `indices`  - The indices of the vertices making up each triangle.
+    /// * `material` - The index of the Material for the Mesh.
+    pub fn new(
+        device: &wgpu::Device,
+        name: String,
+        vertices: &[super::ModelVertex],
+        indices: &[u32],
+        material: usize,
+    ) -> Self {
+        let vertex_buffer = device.create_buffer_with_data(
+            bytemuck::cast_slice(vertices),
+            wgpu::BufferUsage::VERTEX
+        );
+        let edge_indices = edge_indices(indices);
+        let index_format = index_format_for(vertices.len());
+        let index_buffer = create_index_buffer(device, indices, index_format);
+        let edge_index_buffer = create_index_buffer(device, &edge_indices, index_format);
+        return Mesh {
+            name,
+            vertex_buffer,
+            index_buffer,
+            num_elements: indices.len() as u32,
+            index_format,
+            edge_index_buffer,
+            num_edge_elements: edge_indices.len() as u32,
+            material,
+        }
+    }
+}
+
+/// Choose the smallest index format that can address the given number of vertices.
+fn index_format_for(num_vertices: usize) -> wgpu::IndexFormat {
+    if num_vertices <= std::u16::MAX as usize {
+        return wgpu::IndexFormat::Uint16
+    }
+    return wgpu::IndexFormat::Uint32
+}
+
+/// Upload the indices into an index Buffer with the given format.
+fn create_index_buffer(device: &wgpu::Device, indices: &[u32], index_format: wgpu::IndexFormat) -> wgpu::Buffer {
+    return match index_format {
+        wgpu::IndexFormat::Uint16 => {
+            let indices: Vec<u16> = indices.iter().map(|&index| index as u16).collect();
+            device.create_buffer_with_data(bytemuck::cast_slice(&indices), wgpu::BufferUsage::INDEX)
+        }
+        wgpu::IndexFormat::Uint32 => {
+            device.create_buffer_with_data(bytemuck::cast_slice(indices), wgpu::BufferUsage::INDEX)
+        }
+    }
+}
+
+/// Build line list indices of each unique edge of the triangles, so shared edges are only drawn once.
+fn edge_indices(indices: &[u32]) -> Vec<u32> {
+    let mut seen = std::collections::HashSet::new();
+    let mut edges = Vec::new();
+    for triangle in indices.chunks_exact(3) {
+        for &(a, b) in &[(triangle[0], triangle[1]), (triangle[1], triangle[2]), (triangle[2], triangle[0])] {
+            if seen.insert((a.min(b), a.max(b))) {
+                edges.push(a);
+                edges.push(b);
+            }
+        }
+    }
+    return edges
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_meshes_use_16_bit_indices() {
+        assert_eq!(index_format_for(3), wgpu::IndexFormat::Uint16);
+        assert_eq!(index_format_for(65535), wgpu::IndexFormat::Uint16);
+    }
+
+    #[test]
+    fn large_meshes_use_32_bit_indices() {
+        assert_eq!(index_format_for(65536), wgpu::IndexFormat::Uint32);
+    }
+}
@@ -1,13 +1,17 @@
+mod bones;
 mod instance;
+mod loader;
 mod material;
 mod mesh;
 mod model;
 mod traits;
 mod vertex;
 
-pub use instance::{Instance, InstanceRaw};
+pub use bones::{BONES_SIZE, MAX_BONES};
+pub use instance::{Instance, InstanceRaw, DEFAULT_GRID_SIZE, DEFAULT_GRID_SPACING};
+pub use loader::{MaterialData, MeshData, ModelData, PendingModel, TextureData};
 pub use material::Material;
 pub use mesh::Mesh;
 pub use model::Model;
-pub use traits::{DrawModel, Vertex};
+pub use traits::{DrawLight, DrawMode, DrawModel, Renderable, Vertex};
 pub use vertex::ModelVertex;
\ No newline at end of file
@@ -1,7 +1,11 @@
-use std::path::Path;
-use wgpu::{BindGroupLayout, BindingResource, BufferUsage, Device};
-use crate::texture::Texture;
-use super::{Instance, InstanceRaw, Material, Mesh, ModelVertex};
+use std::{ops::Range, path::Path, rc::Rc};
+use cgmath::{Matrix4, Point3, Vector3, Vector4};
+use wgpu::{BindGroupLayout, Device};
+use crate::{state::{StagingBuffers, StateCore}, texture::Texture};
+use super::{
+    bones::{bones_to_raw, create_bones_buffer},
+    Instance, InstanceRaw, Material, Mesh, ModelData, PendingModel, TextureData, BONES_SIZE, MAX_BONES,
+};
 
 
 type ModelResult = Result<(Model, Vec<wgpu::CommandBuffer>), failure::Error>;
@@ -18,126 +22,652 @@ pub struct Model {
 
     // The instances of the Model to be rendered.
     pub instances: Vec<Instance>,
-    instance_buffer: wgpu::Buffer,
+
+    // Whether the Model is visible, i.e. whether its instances should be rendered.
+    pub visible: bool,
+
+    // The Buffers of InstanceRaw objects. When double-buffered, writes alternate between the two.
+    instance_buffers: Vec<wgpu::Buffer>,
+
+    // The index of the instance buffer that is drawn.
+    front_buffer: usize,
+
+    // The number of instances that the instance buffers can hold.
+    instance_capacity: usize,
+
+    // The ranges of instances that are within the view of the Camera, i.e. that are drawn.
+    visible_ranges: Vec<Range<u32>>,
+
+    // The corners of the axis-aligned box bounding the meshes (in model space).
+    bounds: (Vector3<f32>, Vector3<f32>),
+
+    // The radius of the sphere about the model origin that bounds the meshes.
+    bounding_radius: f32,
+
+    // The uniform buffer of bone transforms, which is bound with each of the Materials.
+    bones: Rc<wgpu::Buffer>,
+
+    // The staging buffers that the bone transforms are written through.
+    bones_staging: StagingBuffers,
 }
 
 impl Model {
 
-    /// Load the `.obj` file and all corresponding textures into a `Model` object.
+    /// Load the model file and all corresponding textures into a `Model` object.
+    ///   Files ending in `.gltf` or `.glb` are loaded as glTF, everything else as `.obj`.
     ///
     /// # Arguments
     ///
     /// * `device` - The connection to the graphics device. Used to create the rendering resources.
     /// * `layout` - The `wgpu::BindGroupLayout` object corresponding to the textures bind group.
-    /// * `path`   - The path to the `.obj` file. The corresponding texture files are assumed
-    ///                to be in the same directory as the `.obj` file.
+    /// * `path`   - The path to the model file. The corresponding texture files are assumed
+    ///                to be in the same directory as the model file.
     pub fn load<P: AsRef<Path>>(device: &Device, layout: &BindGroupLayout, path: P) -> ModelResult {
-        // Parse the `.obj` file. Optional is enabled to triangulate mesh.
-        let (obj_models, obj_materials) = tobj::load_obj(path.as_ref(), true)?;
+        info!("Loading model {:?}", path.as_ref());
+        let data = ModelData::load(path)?;
+        return Self::from_data_with_buffers(device, layout, data, 1)
+    }
+
+    /// Load the model file into a `Model` object whose instances are double-buffered.
+    ///   Each call to `update_instances` or `update_instance` writes into the buffer that was not drawn last,
+    ///   so Models that are animated every frame don't stall on a buffer that is in use.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - The connection to the graphics device. Used to create the rendering resources.
+    /// * `layout` - The `wgpu::BindGroupLayout` object corresponding to the textures bind group.
+    /// * `path`   - The path to the model file. The corresponding texture files are assumed
+    ///                to be in the same directory as the model file.
+    pub fn load_double_buffered<P: AsRef<Path>>(device: &Device, layout: &BindGroupLayout, path: P) -> ModelResult {
+        info!("Loading model {:?}", path.as_ref());
+        let data = ModelData::load(path)?;
+        return Self::from_data_with_buffers(device, layout, data, 2)
+    }
 
-        // We're assuming that the texture files are stored with the `.obj` file.
-        let containing_folder = path.as_ref().parent().unwrap();
+    /// Load a `.gltf` or `.glb` file into a `Model` object.
+    ///   The node transforms of the default scene are baked into the vertices of the meshes.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - The connection to the graphics device. Used to create the rendering resources.
+    /// * `layout` - The `wgpu::BindGroupLayout` object corresponding to the textures bind group.
+    /// * `path`   - The path to the glTF file.
+    pub fn load_gltf<P: AsRef<Path>>(device: &Device, layout: &BindGroupLayout, path: P) -> ModelResult {
+        info!("Loading model {:?}", path.as_ref());
+        let data = ModelData::load_gltf(path)?;
+        return Self::from_data_with_buffers(device, layout, data, 1)
+    }
 
-        // Iterate over the `tobj::Material` objects and convert them into 
-        //    `crate::model::Material` objects with corresponding `wgpu::CommandBuffer` objects.
-        let mut command_buffers = Vec::new();
-        let mut materials = Vec::new();
+    /// Create the box drawn at the position of the Light, with double-buffered instances (see `load_double_buffered`).
+    ///   The box is compiled into the binary, so this doesn't access the file system.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - The connection to the graphics device. Used to create the rendering resources.
+    /// * `layout` - The `wgpu::BindGroupLayout` object corresponding to the textures bind group.
+    pub fn new_light(device: &Device, layout: &BindGroupLayout) -> ModelResult {
+        return Self::from_data_with_buffers(device, layout, ModelData::light_box()?, 2)
+    }
+
+    /// Start loading the model file on a background thread.
+    ///   Parsing the file and decoding the textures happen off of the calling thread;
+    ///   once the returned `PendingModel` is ready, upload its ModelData with `from_data`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the model file. The corresponding texture files are assumed
+    ///              to be in the same directory as the model file.
+    pub fn load_async<P: AsRef<Path>>(path: P) -> PendingModel {
+        return PendingModel::spawn(path)
+    }
+
+    /// Upload a loaded model file to the GPU, creating a `Model` object with a single default instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - The connection to the graphics device. Used to create the rendering resources.
+    /// * `layout` - The `wgpu::BindGroupLayout` object corresponding to the textures bind group.
+    /// * `data`   - The meshes, materials and textures read from the model file.
+    pub fn from_data(device: &Device, layout: &BindGroupLayout, data: ModelData) -> ModelResult {
+        return Self::from_data_with_buffers(device, layout, data, 1)
+    }
 
-        let mut texture_results = Vec::new();
-        for material in obj_materials {
-            let path = containing_folder.join( material.diffuse_texture);
-            if let Ok(texture_result) = Texture::load(&device, path) {
-                texture_results.push(texture_result);
+    /// Upload a loaded model file to the GPU, creating a `Model` object with the given number of instance buffers.
+    fn from_data_with_buffers(
+        device: &Device,
+        layout: &BindGroupLayout,
+        data: ModelData,
+        num_instance_buffers: usize,
+    ) -> ModelResult {
+        let ModelData { meshes, materials, textures, bounds } = data;
+
+        // Upload each unique texture once; materials referencing the same texture share it.
+        let mut command_buffers = Vec::new();
+        let mut uploaded_textures = Vec::new();
+        let translucent_textures: Vec<bool> = textures.iter().map(TextureData::has_translucency).collect();
+        for texture in textures {
+            let (uploaded, command_buffer) = if texture.is_normal_map {
+                Texture::normal_map_from_image(device, &texture.image, Some(texture.label.as_str()))?
             } else {
-                texture_results.push(Texture::from_color(device, [255, 255, 255, 255].into()).unwrap());
-            }
+                Texture::from_image(device, &texture.image, Some(texture.label.as_str()))?
+            };
+            uploaded_textures.push(Rc::new(uploaded));
+            command_buffers.push(command_buffer);
         }
 
-        for (diffuse_texture, command_buffer) in texture_results {
-            let bind_group = device.create_bind_group(
-                &wgpu::BindGroupDescriptor {
+        let bones = Rc::new(create_bones_buffer(device));
+        let materials: Vec<Material> = materials.into_iter()
+            .map(|material| {
+                let mut uploaded = Material::new(
+                    device,
                     layout,
-                    bindings: &[
-                        wgpu::Binding {
-                            binding: 0,
-                            resource: BindingResource::TextureView(&diffuse_texture.view)
-                        },
-                        wgpu::Binding {
-                            binding: 1,
-                            resource: BindingResource::Sampler(&diffuse_texture.sampler)
-                        },
-                    ],
-                    label: None,
-                }
-            );
-            command_buffers.push(command_buffer);
-            materials.push( Material { name: String::from("name"), diffuse_texture, bind_group } )
+                    material.name,
+                    Rc::clone(&uploaded_textures[material.diffuse_texture]),
+                    Rc::clone(&uploaded_textures[material.normal_texture]),
+                    Rc::clone(&bones),
+                );
+                uploaded.transparent = material.transparent || translucent_textures[material.diffuse_texture];
+                uploaded
+            }).collect();
+
+        let meshes = meshes.into_iter()
+            .map(|mesh| Mesh::new(device, mesh.name, &mesh.vertices, &mesh.indices, mesh.material))
+            .collect();
+
+        let instances = vec![Instance::default()];
+        let instance_buffers = (0..num_instance_buffers)
+            .map(|_| create_instance_buffer(&instances, instances.len(), device))
+            .collect();
+        let instance_capacity = instances.len();
+        let visible_ranges = vec![0..instances.len() as u32];
+        let bounding_radius = {
+            use cgmath::InnerSpace;
+            let (min, max) = bounds;
+            Vector3::new(min.x.abs().max(max.x.abs()), min.y.abs().max(max.y.abs()), min.z.abs().max(max.z.abs()))
+                .magnitude()
+        };
+
+        let model = Model {
+            meshes,
+            materials,
+            instances,
+            visible: true,
+            instance_buffers,
+            front_buffer: 0,
+            instance_capacity,
+            visible_ranges,
+            bounds,
+            bounding_radius,
+            bones,
+            bones_staging: StagingBuffers::new(BONES_SIZE),
+        };
+        return Ok((model, command_buffers))
+    }
+
+    /// Set the transforms of the bones that move the skinned vertices (see `ModelVertex::bone_indices`).
+    ///   Each transform takes a vertex from model space to its posed position, e.g. the transform of a
+    ///   glTF joint times its inverse bind matrix, in the order of the joints of the skin.
+    ///   The bones past the given transforms don't move; transforms past `MAX_BONES` are ignored.
+    ///   Only the Model shaders are skinned, so the light box and outline shaders draw the rest pose.
+    ///
+    /// # Arguments
+    ///
+    /// * `transforms` - The transforms of the bones, in model space.
+    /// * `core`       - Structure for holding the WGPU primitives for running a windowed application.
+    pub fn set_bone_transforms(&mut self, transforms: &[Matrix4<f32>], core: &StateCore) {
+        if transforms.len() > MAX_BONES {
+            warn!("Ignoring {} bone transforms past the maximum of {}", transforms.len() - MAX_BONES, MAX_BONES);
         }
+        let bones_raw = bones_to_raw(transforms);
+        self.bones_staging.write(core, bytemuck::cast_slice(&bones_raw), &self.bones);
+    }
 
-        // Iterate over the `tobj::Model` objects and convert them into `crate::model::Mesh` objects.
-        let meshes: Vec<Mesh> = obj_models.into_iter()
-            .map(|model| {
-                let num_coords = model.mesh.positions.len() / 3;
-                let vertices: Vec<ModelVertex> = (0..num_coords)
-                    .map(|index| {
-                        ModelVertex {
-                            position: [
-                                model.mesh.positions[index * 3],
-                                model.mesh.positions[index * 3 + 1],
-                                model.mesh.positions[index * 3 + 2],
-                            ],
-                            tex_coords: [
-                                model.mesh.texcoords[index * 2],
-                                model.mesh.texcoords[index * 2 + 1]
-                            ],
-                            normal: [
-                                model.mesh.normals[index * 3],
-                                model.mesh.normals[index * 3 + 1],
-                                model.mesh.normals[index * 3 + 2],
-                            ],
-                        }
-                    }).collect();
-
-                let vertex_buffer = device.create_buffer_with_data(
-                    bytemuck::cast_slice(&vertices),
-                    BufferUsage::VERTEX
-                );
-                let index_buffer = device.create_buffer_with_data(
-                    bytemuck::cast_slice(&model.mesh.indices),
-                    BufferUsage::INDEX
-                );
+    /// Whether any of the Meshes uses a transparent Material.
+    pub fn has_transparency(&self) -> bool {
+        return self.meshes.iter().any(|mesh| self.materials[mesh.material].transparent)
+    }
 
-                Mesh {
-                    name: model.name,
-                    vertex_buffer,
-                    index_buffer,
-                    num_elements: model.mesh.indices.len() as u32,
-                    material: model.mesh.material_id.unwrap_or(0),
-                }
-            }).collect();
-        
-            let instances = vec![Instance::default()];
-            let instance_buffer = create_instance_buffer(&instances, device);
+    /// Get the first Material with the given name, e.g. the name of a `newmtl` statement in a `.mtl` file.
+    pub fn material_by_name(&self, name: &str) -> Option<&Material> {
+        return self.materials.iter().find(|material| material.name == name)
+    }
+
+    /// Recolor a Material with a single color, replacing its diffuse Texture until `reset_material_color`.
+    ///
+    /// # Arguments
+    ///
+    /// * `device`         - The connection to the graphics device. Used to create the rendering resources.
+    /// * `layout`         - The `wgpu::BindGroupLayout` object corresponding to the textures bind group.
+    /// * `material_index` - The index of the Material in `materials`.
+    /// * `color`          - The color of the Material.
+    ///
+    /// # Returns
+    ///
+    /// Result object that wraps the CommandBuffer which uploads the color Texture.
+    pub fn set_material_color(
+        &mut self,
+        device: &Device,
+        layout: &BindGroupLayout,
+        material_index: usize,
+        color: image::Rgba<u8>,
+    ) -> Result<wgpu::CommandBuffer, failure::Error> {
+        let material = self.materials.get_mut(material_index)
+            .ok_or_else(|| failure::format_err!("The model has no material {}", material_index))?;
+        let (texture, command_buffer) = Texture::from_color(device, color)?;
+        material.override_diffuse_texture(device, layout, Rc::new(texture));
+        return Ok(command_buffer)
+    }
 
-        Ok((Model { meshes, materials, instances, instance_buffer }, command_buffers))
+    /// Restore the original diffuse Texture of a Material recolored with `set_material_color`.
+    ///
+    /// # Returns
+    ///
+    /// Whether the Material was recolored.
+    pub fn reset_material_color(&mut self, device: &Device, layout: &BindGroupLayout, material_index: usize) -> bool {
+        return self.materials.get_mut(material_index)
+            .map_or(false, |material| material.revert_diffuse_texture(device, layout))
     }
 
-    pub fn get_instance_buffer(&self) -> &wgpu::Buffer { &self.instance_buffer }
+    /// Get the corners (min, max) of the axis-aligned box bounding the meshes, in model space.
+    pub fn get_bounds(&self) -> (Vector3<f32>, Vector3<f32>) { self.bounds }
+
+    /// Get the corners (min, max) of the axis-aligned box (in world space) bounding all of the instances.
+    ///   Returns None if the Model has no instances.
+    pub fn get_world_bounds(&self) -> Option<(Vector3<f32>, Vector3<f32>)> {
+        if self.instances.is_empty() {
+            return None
+        }
+        let corners = self.instances.iter()
+            .flat_map(|instance| {
+                let radius = self.bounding_radius * instance.max_scale();
+                let radius = Vector3::new(radius, radius, radius);
+                vec![instance.position - radius, instance.position + radius]
+            });
+        return Some(compute_bounds(corners))
+    }
+
+    /// Intersect a ray with the boxes bounding each of the instances.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin`    - The origin of the ray, in world space.
+    /// * `direction` - The direction of the ray, in world space.
+    ///
+    /// # Returns
+    ///
+    /// The distance along the ray (in multiples of `direction`) to the nearest hit, or None if nothing is hit.
+    pub fn intersect_ray(&self, origin: Point3<f32>, direction: Vector3<f32>) -> Option<f32> {
+        use cgmath::{EuclideanSpace, SquareMatrix, Transform};
+
+        let (min, max) = self.bounds;
+        return self.instances.iter()
+            .filter_map(|instance| {
+                // Transform the ray into model space, where the bounding box is axis aligned.
+                //   The direction isn't renormalized, so distances along the ray are preserved.
+                let inverse = instance.model_matrix().invert()?;
+                let origin = inverse.transform_point(origin).to_vec();
+                let direction = inverse.transform_vector(direction);
+                intersect_box(origin, direction, min, max)
+            })
+            .fold(None, |nearest: Option<f32>, distance| Some(nearest.map_or(distance, |nearest| nearest.min(distance))))
+    }
+
+    /// Get the radius of the sphere about the model origin that bounds the meshes.
+    pub fn get_bounding_radius(&self) -> f32 { self.bounding_radius }
+
+    /// Get the number of staging buffers allocated by the updates of the bone matrices.
+    pub fn get_staging_allocation_count(&self) -> u64 { self.bones_staging.get_allocation_count() }
+
+    /// Get the ranges of instances that are drawn.
+    pub fn get_visible_ranges(&self) -> &[Range<u32>] { &self.visible_ranges }
+
+    /// Only draw the instances whose bounding sphere is within the view of the Camera.
+    ///   The instance buffer is left untouched; contiguous runs of visible instances are drawn together.
+    ///
+    /// # Arguments
+    ///
+    /// * `planes` - The planes of the Camera frustum (see `Camera::frustum_planes`).
+    pub fn cull_instances(&mut self, planes: &[Vector4<f32>; 6]) {
+        use crate::camera::sphere_in_frustum;
+
+        self.visible_ranges.clear();
+        for (index, instance) in self.instances.iter().enumerate() {
+            let index = index as u32;
+            if !sphere_in_frustum(planes, instance.position, self.bounding_radius * instance.max_scale()) {
+                continue
+            }
+            match self.visible_ranges.last_mut() {
+                Some(range) if range.end == index => range.end += 1,
+                _ => self.visible_ranges.push(index..index + 1),
+            }
+        }
+    }
+
+    /// Draw all of the instances, undoing any culling.
+    pub fn clear_culling(&mut self) {
+        self.visible_ranges.clear();
+        self.visible_ranges.push(0..self.instances.len() as u32);
+    }
+
+    /// Get the instance buffer that is drawn.
+    pub fn get_instance_buffer(&self) -> &wgpu::Buffer { &self.instance_buffers[self.front_buffer] }
+
+    /// Replace the instances of the Model, reallocating the instance buffers.
+    ///   The instance buffers are kept if the instances are unchanged.
     pub fn set_instances(&mut self, instances: Vec<Instance>, device: &Device) {
+        if !needs_restage(&self.instances, &instances) {
+            return
+        }
         self.instances = instances;
-        self.instance_buffer = create_instance_buffer(&self.instances, device);
+        self.reallocate_instance_buffers(self.instances.len(), device);
+        self.clear_culling();
+    }
+
+    /// Append an instance to the Model, only writing its data into the instance buffer.
+    ///   The instance buffers are reallocated (doubling their capacity) only when they are full.
+    ///
+    /// # Arguments
+    ///
+    /// * `instance` - The new instance of the Model.
+    /// * `core`     - Structure for holding the WGPU primitives for running a windowed application.
+    pub fn push_instance(&mut self, instance: Instance, core: &StateCore) {
+        self.instances.push(instance);
+        if self.instances.len() > self.instance_capacity {
+            let capacity = (self.instance_capacity * 2).max(self.instances.len());
+            self.reallocate_instance_buffers(capacity, &core.device);
+        } else {
+            self.write_instance(self.instances.len() - 1, core);
+        }
+        self.clear_culling();
+    }
+
+    /// Replace a single instance of the Model, only writing its data into the instance buffer.
+    ///   Nothing is written if the instance is unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `index`    - The index of the instance to replace.
+    /// * `instance` - The new value of the instance.
+    /// * `core`     - Structure for holding the WGPU primitives for running a windowed application.
+    ///
+    /// # Returns
+    ///
+    /// Result object that is an error if the Model has no instance with the index.
+    pub fn update_instance(&mut self, index: usize, instance: Instance, core: &StateCore) -> Result<(), failure::Error> {
+        if replace_instance(&mut self.instances, index, instance)? {
+            self.write_instance(index, core);
+        }
+        return Ok(())
+    }
+
+    /// Get the number of instances that the instance buffers can hold without reallocating.
+    pub fn get_instance_capacity(&self) -> usize { self.instance_capacity }
+
+    /// Recreate the instance buffers with room for the given number of instances.
+    fn reallocate_instance_buffers(&mut self, capacity: usize, device: &Device) {
+        for buffer in self.instance_buffers.iter_mut() {
+            *buffer = create_instance_buffer(&self.instances, capacity, device);
+        }
+        self.front_buffer = 0;
+        self.instance_capacity = capacity;
+    }
+
+    /// Write the data of a single instance into the instance buffer.
+    ///   If the Model is double-buffered, the back buffer may hold stale instances,
+    ///   so it is rewritten entirely and then becomes the drawn buffer.
+    fn write_instance(&mut self, index: usize, core: &StateCore) {
+        if self.instance_buffers.len() > 1 {
+            let back_buffer = (self.front_buffer + 1) % self.instance_buffers.len();
+            self.copy_instances(back_buffer, 0..self.instances.len(), core);
+            self.front_buffer = back_buffer;
+        } else {
+            self.copy_instances(self.front_buffer, index..index + 1, core);
+        }
+    }
+
+    /// Copy a range of the instances into one of the instance buffers through a staging buffer.
+    fn copy_instances(&self, buffer_index: usize, range: Range<usize>, core: &StateCore) {
+        if range.start >= range.end {
+            return
+        }
+
+        // Create a staging buffer with the updated Buffer data.
+        let instances_data = Instance::to_raw_vec(&self.instances[range.clone()]);
+        let staging_buffer = core.device.create_buffer_with_data(
+            bytemuck::cast_slice(&instances_data),
+            wgpu::BufferUsage::COPY_SRC
+        );
+
+        // Copy the data from the staging buffer into its place in the instance buffer, along with the next frame.
+        let offset = InstanceRaw::SIZE * range.start as wgpu::BufferAddress;
+        let copy_size = InstanceRaw::SIZE * instances_data.len() as wgpu::BufferAddress;
+        let instance_buffer = &self.instance_buffers[buffer_index];
+        core.encode(|encoder| encoder.copy_buffer_to_buffer(&staging_buffer, 0, instance_buffer, offset, copy_size));
+    }
+
+    /// Replace the instances of the Model, reusing the instance buffers if they are large enough.
+    ///   If the Model is double-buffered, the buffer that was not drawn last is written
+    ///   and then becomes the drawn buffer. Nothing is written if the instances are unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `instances` - The new instances of the Model.
+    /// * `core`      - Structure for holding the WGPU primitives for running a windowed application.
+    pub fn update_instances(&mut self, instances: Vec<Instance>, core: &StateCore) {
+        if !needs_restage(&self.instances, &instances) {
+            return
+        }
+        if instances.len() > self.instance_capacity || instances.is_empty() {
+            return self.set_instances(instances, &core.device)
+        }
+        self.instances = instances;
+
+        // Copy the instances into the back instance buffer, then swap.
+        let back_buffer = (self.front_buffer + 1) % self.instance_buffers.len();
+        self.copy_instances(back_buffer, 0..self.instances.len(), core);
+        self.front_buffer = back_buffer;
+        self.clear_culling();
+    }
+
+    /// Get the squared distance from a point to the farthest of the instances.
+    ///
+    /// # Arguments
+    ///
+    /// * `eye` - The point to measure from (e.g. the position of the Camera).
+    pub fn get_farthest_instance_distance2(&self, eye: Point3<f32>) -> Option<f32> {
+        use cgmath::{EuclideanSpace, InnerSpace};
+        return self.instances.iter()
+            .map(|instance| (instance.position - eye.to_vec()).magnitude2())
+            .fold(None, |farthest: Option<f32>, distance| Some(farthest.map_or(distance, |farthest| farthest.max(distance))))
+    }
+
+    /// Order the instances from the farthest to the nearest to a point, so that blended instances
+    ///   are drawn over the ones behind them. The instance buffer is only rewritten if the order changed.
+    ///   Note this changes the indices of the instances (see `update_instance`).
+    ///
+    /// # Arguments
+    ///
+    /// * `eye`  - The point to sort about (e.g. the position of the Camera).
+    /// * `core` - Structure for holding the WGPU primitives for running a windowed application.
+    ///
+    /// # Returns
+    ///
+    /// Boolean of whether the instances were reordered.
+    pub fn sort_instances_back_to_front(&mut self, eye: Point3<f32>, core: &StateCore) -> bool {
+        let order = back_to_front_order(&self.instances, eye);
+        let sorted = order.iter().enumerate().all(|(position, &index)| position == index);
+        if sorted {
+            return false
+        }
+
+        let mut instances: Vec<Option<Instance>> = std::mem::take(&mut self.instances).into_iter().map(Some).collect();
+        self.instances = order.into_iter().filter_map(|index| instances[index].take()).collect();
+
+        // Every instance may have moved, so the whole (back) buffer is rewritten.
+        let back_buffer = (self.front_buffer + 1) % self.instance_buffers.len();
+        self.copy_instances(back_buffer, 0..self.instances.len(), core);
+        self.front_buffer = back_buffer;
+        self.clear_culling();
+        return true
+    }
+}
+
+/// Whether replacing the instances requires the instance buffers to be written (or reallocated),
+///   i.e. whether the new instances differ from the current ones.
+fn needs_restage(current: &[Instance], new: &[Instance]) -> bool {
+    return current != new
+}
+
+/// Get the order in which to draw the instances so that blended instances are drawn over the ones behind them,
+///   i.e. the indices of the instances from the farthest to the nearest to a point.
+///   Instances at the same distance keep their order, so sorted instances give the indices in order.
+fn back_to_front_order(instances: &[Instance], eye: Point3<f32>) -> Vec<usize> {
+    use cgmath::{EuclideanSpace, InnerSpace};
+    let distances2: Vec<f32> = instances.iter()
+        .map(|instance| (instance.position - eye.to_vec()).magnitude2())
+        .collect();
+    let mut order: Vec<usize> = (0..instances.len()).collect();
+    order.sort_by(|&a, &b| distances2[b].partial_cmp(&distances2[a]).unwrap_or(std::cmp::Ordering::Equal));
+    return order
+}
+
+/// Replace one of the instances, leaving the others untouched.
+///
+/// # Returns
+///
+/// Result object that wraps whether the instance changed, or an error if there is no instance with the index.
+fn replace_instance(instances: &mut [Instance], index: usize, instance: Instance) -> Result<bool, failure::Error> {
+    let count = instances.len();
+    let slot = instances.get_mut(index)
+        .ok_or_else(|| failure::format_err!("The model has no instance {} (it has {})", index, count))?;
+    if *slot == instance {
+        return Ok(false)
     }
+    *slot = instance;
+    return Ok(true)
 }
 
-fn create_instance_buffer(instances: &Vec<Instance>, device: &Device) -> wgpu::Buffer {
-    let instances_data: Vec<InstanceRaw> = 
-        instances
-            .iter()
-            .map(Instance::to_raw)
-            .collect::<Vec<_>>();
-    
+/// Create an instance buffer holding the instances, with room for `capacity` instances in total.
+fn create_instance_buffer(instances: &[Instance], capacity: usize, device: &Device) -> wgpu::Buffer {
+    let mut instances_data: Vec<InstanceRaw> = Instance::to_raw_vec(instances);
+    if instances_data.len() < capacity {
+        instances_data.resize(capacity, bytemuck::Zeroable::zeroed());
+    }
+
     return device.create_buffer_with_data(
         bytemuck::cast_slice(&instances_data),
-        wgpu::BufferUsage::VERTEX,
+        wgpu::BufferUsage::VERTEX | wgpu::BufferUsage::COPY_DST,
     );
 }
+
+/// Compute the corners (min, max) of the axis-aligned box bounding a set of positions.
+pub(super) fn compute_bounds<I: Iterator<Item = Vector3<f32>>>(positions: I) -> (Vector3<f32>, Vector3<f32>) {
+    use cgmath::Zero;
+    let mut bounds: Option<(Vector3<f32>, Vector3<f32>)> = None;
+    for position in positions {
+        bounds = Some(match bounds {
+            None => (position, position),
+            Some((min, max)) => (
+                Vector3::new(min.x.min(position.x), min.y.min(position.y), min.z.min(position.z)),
+                Vector3::new(max.x.max(position.x), max.y.max(position.y), max.z.max(position.z)),
+            ),
+        });
+    }
+    return bounds.unwrap_or((Vector3::zero(), Vector3::zero()))
+}
+
+/// Intersect a ray with an axis-aligned box (using the slab method).
+///   Returns the distance along the ray to where it enters the box, or 0 if the origin is inside the box.
+fn intersect_box(origin: Vector3<f32>, direction: Vector3<f32>, min: Vector3<f32>, max: Vector3<f32>) -> Option<f32> {
+    let mut t_enter = 0.0f32;
+    let mut t_exit = std::f32::INFINITY;
+    for axis in 0..3 {
+        if direction[axis] == 0.0 {
+            // A ray parallel to the slab misses it unless it starts between its planes.
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return None
+            }
+            continue
+        }
+        let t_min = (min[axis] - origin[axis]) / direction[axis];
+        let t_max = (max[axis] - origin[axis]) / direction[axis];
+        t_enter = t_enter.max(t_min.min(t_max));
+        t_exit = t_exit.min(t_min.max(t_max));
+        if t_enter > t_exit {
+            return None
+        }
+    }
+    return Some(t_enter)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instances_at(xs: &[f32]) -> Vec<Instance> {
+        return xs.iter().map(|&x| Instance::from_position(Vector3::new(x, 0.0, 0.0))).collect()
+    }
+
+    #[test]
+    fn replacing_an_instance_leaves_the_others() {
+        let mut instances = instances_at(&[0.0, 1.0, 2.0]);
+        let changed = replace_instance(&mut instances, 1, Instance::from_position(Vector3::new(5.0, 0.0, 0.0)));
+        assert!(changed.unwrap());
+        assert!(instances == instances_at(&[0.0, 5.0, 2.0]));
+    }
+
+    #[test]
+    fn replacing_an_instance_with_itself_changes_nothing() {
+        let mut instances = instances_at(&[0.0, 1.0]);
+        let changed = replace_instance(&mut instances, 0, Instance::from_position(Vector3::new(0.0, 0.0, 0.0)));
+        assert!(!changed.unwrap());
+        assert!(instances == instances_at(&[0.0, 1.0]));
+    }
+
+    #[test]
+    fn replacing_a_missing_instance_is_an_error() {
+        let mut instances = instances_at(&[0.0]);
+        assert!(replace_instance(&mut instances, 1, Instance::default()).is_err());
+        assert!(replace_instance(&mut [], 0, Instance::default()).is_err());
+        assert!(instances == instances_at(&[0.0]));
+    }
+
+    #[test]
+    fn farther_quad_is_drawn_first() {
+        // Two quads facing the eye, one behind the other along the line of sight.
+        let quads = vec![
+            Instance::from_position(Vector3::new(0.0, 0.0, -1.0)),
+            Instance::from_position(Vector3::new(0.2, 0.0, -2.0)),
+        ];
+        let eye = Point3::new(0.0, 0.0, 1.0);
+        assert_eq!(back_to_front_order(&quads, eye), vec![1, 0]);
+
+        // From the other side the nearer quad is the one behind.
+        let eye = Point3::new(0.0, 0.0, -4.0);
+        assert_eq!(back_to_front_order(&quads, eye), vec![0, 1]);
+    }
+
+    #[test]
+    fn equally_distant_instances_keep_their_order() {
+        let instances = instances_at(&[-1.0, 1.0]);
+        assert_eq!(back_to_front_order(&instances, Point3::new(0.0, 0.0, 0.0)), vec![0, 1]);
+    }
+
+    #[test]
+    fn unchanged_instances_are_not_restaged() {
+        assert!(!needs_restage(&instances_at(&[0.0, 1.0]), &instances_at(&[0.0, 1.0])));
+        assert!(!needs_restage(&[], &[]));
+    }
+
+    #[test]
+    fn changed_instances_are_restaged() {
+        assert!(needs_restage(&instances_at(&[0.0, 1.0]), &instances_at(&[0.0, 1.5])));
+        assert!(needs_restage(&instances_at(&[0.0, 1.0]), &instances_at(&[0.0])));
+
+        use cgmath::{Deg, Quaternion, Rotation3};
+        let mut rotated = instances_at(&[0.0]);
+        rotated[0].rotation = Quaternion::from_angle_y(Deg(10.0));
+        assert!(needs_restage(&instances_at(&[0.0]), &rotated));
+    }
+}
@@ -1,5 +1,6 @@
 use std::ops::Range;
-use wgpu::{BindGroup, Buffer};
+use wgpu::{BindGroup, Buffer, RenderPass};
+use crate::state::RenderPipelines;
 use super::{Material, Mesh, Model};
 
 
@@ -9,6 +10,99 @@ pub trait Vertex {
 }
 
 
+/// What a Renderer is drawing when it calls `Renderable::draw`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DrawMode {
+
+    // Every Mesh, with its Material.
+    Textured,
+
+    // Only the opaque Meshes, with their Materials. The transparent ones are drawn later, in the `Transparent` mode.
+    Opaque,
+
+    // Only the transparent Meshes, with their Materials. The pipelines alpha blend and don't write depth.
+    Transparent,
+
+    // The edges of every Mesh. The pipelines use the `LineList` topology.
+    Wireframe,
+
+    // Every Mesh without its Material, e.g. the light box or an outline.
+    //   The pipelines only take the uniforms and the second bind group, at sets 0 and 1.
+    Untextured,
+}
+
+/// Trait for an object that a Renderer can draw, e.g. procedural geometry with its own vertex format
+///   (see `Renderer::add_renderable`). Model is the default implementation.
+pub trait Renderable {
+
+    /// Draw the object to the screen. The object binds its own RenderPipeline and bind groups,
+    ///   and draws nothing in the modes that it doesn't support.
+    ///
+    /// # Arguments
+    ///
+    /// `render_pass` - An object that connect RenderPipelines to the GPU.
+    /// `pipelines`   - The RenderPipeline objects of the Renderer for the mode, for objects made of `ModelVertex` Meshes.
+    /// `mode`        - What is being drawn, i.e. which Meshes and which kind of pipeline.
+    /// `uniforms`    - The Uniforms objects needed by the shader progams, as a `wgpu::BindGroup` object.
+    /// `light`       - The Light object needed by the shader programs, as a `wgpu::BindGroup` object.
+    ///                   When drawing outlines, this is the bind group of the outline instead.
+    fn draw<'r>(
+        &'r self,
+        render_pass: &mut RenderPass<'r>,
+        pipelines: &'r RenderPipelines,
+        mode: DrawMode,
+        uniforms: &'r BindGroup,
+        light: &'r BindGroup,
+    );
+}
+
+impl Renderable for Model {
+    fn draw<'r>(
+        &'r self,
+        render_pass: &mut RenderPass<'r>,
+        pipelines: &'r RenderPipelines,
+        mode: DrawMode,
+        uniforms: &'r BindGroup,
+        light: &'r BindGroup,
+    ) {
+        if !self.visible {
+            return
+        }
+        let transparent = match mode {
+            DrawMode::Opaque => Some(false),
+            DrawMode::Transparent => Some(true),
+            _ => None,
+        };
+
+        // The index format is baked into the pipeline, so the Meshes are drawn grouped by index format.
+        for &index_format in &[wgpu::IndexFormat::Uint16, wgpu::IndexFormat::Uint32] {
+            let has_meshes = self.meshes.iter()
+                .filter(|mesh| transparent.map_or(true, |transparent| self.materials[mesh.material].transparent == transparent))
+                .any(|mesh| mesh.index_format == index_format);
+            if !has_meshes {
+                continue
+            }
+            render_pass.set_pipeline(pipelines.get(index_format));
+            match mode {
+                DrawMode::Textured => {
+                    render_pass.draw_model_with_index_format(self, index_format, uniforms, light);
+                }
+                DrawMode::Opaque | DrawMode::Transparent => {
+                    let transparent = mode == DrawMode::Transparent;
+                    render_pass.draw_model_meshes_with_index_format(self, index_format, transparent, uniforms, light);
+                }
+                DrawMode::Wireframe => {
+                    render_pass.draw_model_wireframe_with_index_format(self, index_format, uniforms, light);
+                }
+                DrawMode::Untextured => {
+                    render_pass.draw_light_model_with_index_format(self, index_format, uniforms, light);
+                }
+            }
+        }
+    }
+}
+
+
 /// Trait for rendering a Model.
 pub trait DrawModel<'a, 'b> where 'b: 'a {
 
@@ -32,7 +126,7 @@ pub trait DrawModel<'a, 'b> where 'b: 'a {
         instance_buffer: &'b Buffer,
     );
 
-    /// Draw a Model to the screen.
+    /// Draw the visible instances of a Model to the screen.
     ///
     /// # Arguments
     ///
@@ -40,6 +134,89 @@ pub trait DrawModel<'a, 'b> where 'b: 'a {
     /// `uniforms` - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
     /// `light`    - The Light object needed for rendering, as a `wgpu::BindGroup` object.
     fn draw_model(&mut self, model: &'b Model, uniforms: &'b wgpu::BindGroup, light: &'b wgpu::BindGroup);
+
+    /// Draw the edges of an instanced Mesh to the screen. The pipeline must use the `LineList` topology.
+    ///
+    /// # Arguments
+    ///
+    /// `mesh`            - The Mesh object to be drawn.
+    /// `material`        - The Material object associated with the Mesh.
+    /// `uniforms`        - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
+    /// `light`           - The Light object needed for rendering, as a `wgpu::BindGroup` object.
+    /// `instances`       - A Range object indexing the instances to be rendered.
+    /// `instances_buffer - The `wgpu::Buffer` objct containing the instancing data for each instance of the mesh.
+    fn draw_mesh_wireframe_instanced(
+        &mut self,
+        mesh: &'b Mesh,
+        material: &'b Material,
+        uniforms: &'b wgpu::BindGroup,
+        light: &'b wgpu::BindGroup,
+        instances: Range<u32>,
+        instance_buffer: &'b Buffer,
+    );
+
+    /// Draw the edges of the visible instances of a Model to the screen.
+    ///
+    /// # Arguments
+    ///
+    /// `model`    - The Model object to be drawn.
+    /// `uniforms` - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
+    /// `light`    - The Light object needed for rendering, as a `wgpu::BindGroup` object.
+    fn draw_model_wireframe(&mut self, model: &'b Model, uniforms: &'b wgpu::BindGroup, light: &'b wgpu::BindGroup);
+
+    /// Draw the visible instances of the Meshes of a Model that use the given index format.
+    ///   The pipeline must have been created with the same index format.
+    ///
+    /// # Arguments
+    ///
+    /// `model`        - The Model object to be drawn.
+    /// `index_format` - The index format of the Meshes to draw.
+    /// `uniforms`     - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
+    /// `light`        - The Light object needed for rendering, as a `wgpu::BindGroup` object.
+    fn draw_model_with_index_format(
+        &mut self,
+        model: &'b Model,
+        index_format: wgpu::IndexFormat,
+        uniforms: &'b wgpu::BindGroup,
+        light: &'b wgpu::BindGroup,
+    );
+
+    /// Draw the edges of the Meshes of a Model that use the given index format.
+    ///   The pipeline must use the `LineList` topology and have been created with the same index format.
+    ///
+    /// # Arguments
+    ///
+    /// `model`        - The Model object to be drawn.
+    /// `index_format` - The index format of the Meshes to draw.
+    /// `uniforms`     - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
+    /// `light`        - The Light object needed for rendering, as a `wgpu::BindGroup` object.
+    fn draw_model_wireframe_with_index_format(
+        &mut self,
+        model: &'b Model,
+        index_format: wgpu::IndexFormat,
+        uniforms: &'b wgpu::BindGroup,
+        light: &'b wgpu::BindGroup,
+    );
+
+    /// Draw the visible instances of either the opaque or the transparent Meshes of a Model
+    ///   that use the given index format (see `Material::transparent`).
+    ///   The pipeline must have been created with the same index format.
+    ///
+    /// # Arguments
+    ///
+    /// `model`        - The Model object to be drawn.
+    /// `index_format` - The index format of the Meshes to draw.
+    /// `transparent`  - Whether the transparent (rather than the opaque) Meshes are drawn.
+    /// `uniforms`     - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
+    /// `light`        - The Light object needed for rendering, as a `wgpu::BindGroup` object.
+    fn draw_model_meshes_with_index_format(
+        &mut self,
+        model: &'b Model,
+        index_format: wgpu::IndexFormat,
+        transparent: bool,
+        uniforms: &'b wgpu::BindGroup,
+        light: &'b wgpu::BindGroup,
+    );
 }
 
 /// Implement Model drawing for the `wgpu::RenderPass` object.
@@ -73,7 +250,7 @@ impl<'a, 'b> DrawModel<'a, 'b> for wgpu::RenderPass<'a> where 'b: 'a {
         self.draw_indexed(0..mesh.num_elements, 0, instances);
     }
 
-    /// Draw a Model to the screen.
+    /// Draw the visible instances of a Model to the screen.
     ///
     /// # Arguments
     ///
@@ -81,10 +258,230 @@ impl<'a, 'b> DrawModel<'a, 'b> for wgpu::RenderPass<'a> where 'b: 'a {
     /// `uniforms` - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
     /// `light`    - The Light object needed for rendering, as a `wgpu::BindGroup` object.
     fn draw_model(&mut self, model: &'b Model, uniforms: &'b BindGroup, light: &'b BindGroup) {
-        let instances = 0..model.instances.len() as u32;
         for mesh in &model.meshes {
             let material = &model.materials[mesh.material];
-            self.draw_mesh_instanced(mesh, material, uniforms, light, instances.clone(), model.get_instance_buffer());
+            for instances in model.get_visible_ranges() {
+                self.draw_mesh_instanced(mesh, material, uniforms, light, instances.clone(), model.get_instance_buffer());
+            }
         }
     }
-}
\ No newline at end of file
+
+    /// Draw the edges of an instanced Mesh to the screen. The pipeline must use the `LineList` topology.
+    ///
+    /// # Arguments
+    ///
+    /// `mesh`            - The Mesh object to be drawn.
+    /// `material`        - The Material object associated with the Mesh.
+    /// `uniforms`        - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
+    /// `light`           - The Light object needed for rendering, as a `wgpu::BindGroup` object.
+    /// `instances`       - A Range object indexing the instances to be rendered.
+    /// `instances_buffer - The `wgpu::Buffer` objct containing the instancing data for each instance of the mesh.
+    fn draw_mesh_wireframe_instanced(
+        &mut self,
+        mesh: &'b Mesh,
+        material: &'b Material,
+        uniforms: &'b BindGroup,
+        light: &'b wgpu::BindGroup,
+        instances: Range<u32>,
+        instance_buffer: &'b Buffer,
+    ) {
+        self.set_vertex_buffer(0, &mesh.vertex_buffer, 0, 0);
+        self.set_vertex_buffer(1, instance_buffer, 0, 0);
+        self.set_index_buffer(&mesh.edge_index_buffer, 0, 0);
+        self.set_bind_group(0, &material.bind_group, &[]);
+        self.set_bind_group(1, &uniforms, &[]);
+        self.set_bind_group(2, &light, &[]);
+        self.draw_indexed(0..mesh.num_edge_elements, 0, instances);
+    }
+
+    /// Draw the edges of the visible instances of a Model to the screen.
+    ///
+    /// # Arguments
+    ///
+    /// `model`    - The Model object to be drawn.
+    /// `uniforms` - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
+    /// `light`    - The Light object needed for rendering, as a `wgpu::BindGroup` object.
+    fn draw_model_wireframe(&mut self, model: &'b Model, uniforms: &'b BindGroup, light: &'b BindGroup) {
+        for mesh in &model.meshes {
+            let material = &model.materials[mesh.material];
+            for instances in model.get_visible_ranges() {
+                self.draw_mesh_wireframe_instanced(
+                    mesh, material, uniforms, light, instances.clone(), model.get_instance_buffer()
+                );
+            }
+        }
+    }
+
+    /// Draw the visible instances of the Meshes of a Model that use the given index format.
+    ///   The pipeline must have been created with the same index format.
+    ///
+    /// # Arguments
+    ///
+    /// `model`        - The Model object to be drawn.
+    /// `index_format` - The index format of the Meshes to draw.
+    /// `uniforms`     - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
+    /// `light`        - The Light object needed for rendering, as a `wgpu::BindGroup` object.
+    fn draw_model_with_index_format(
+        &mut self,
+        model: &'b Model,
+        index_format: wgpu::IndexFormat,
+        uniforms: &'b BindGroup,
+        light: &'b BindGroup,
+    ) {
+        for mesh in model.meshes.iter().filter(|mesh| mesh.index_format == index_format) {
+            let material = &model.materials[mesh.material];
+            for instances in model.get_visible_ranges() {
+                self.draw_mesh_instanced(mesh, material, uniforms, light, instances.clone(), model.get_instance_buffer());
+            }
+        }
+    }
+
+    /// Draw the edges of the Meshes of a Model that use the given index format.
+    ///   The pipeline must use the `LineList` topology and have been created with the same index format.
+    ///
+    /// # Arguments
+    ///
+    /// `model`        - The Model object to be drawn.
+    /// `index_format` - The index format of the Meshes to draw.
+    /// `uniforms`     - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
+    /// `light`        - The Light object needed for rendering, as a `wgpu::BindGroup` object.
+    fn draw_model_wireframe_with_index_format(
+        &mut self,
+        model: &'b Model,
+        index_format: wgpu::IndexFormat,
+        uniforms: &'b BindGroup,
+        light: &'b BindGroup,
+    ) {
+        for mesh in model.meshes.iter().filter(|mesh| mesh.index_format == index_format) {
+            let material = &model.materials[mesh.material];
+            for instances in model.get_visible_ranges() {
+                self.draw_mesh_wireframe_instanced(
+                    mesh, material, uniforms, light, instances.clone(), model.get_instance_buffer()
+                );
+            }
+        }
+    }
+
+    /// Draw the visible instances of either the opaque or the transparent Meshes of a Model
+    ///   that use the given index format (see `Material::transparent`).
+    ///   The pipeline must have been created with the same index format.
+    ///
+    /// # Arguments
+    ///
+    /// `model`        - The Model object to be drawn.
+    /// `index_format` - The index format of the Meshes to draw.
+    /// `transparent`  - Whether the transparent (rather than the opaque) Meshes are drawn.
+    /// `uniforms`     - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
+    /// `light`        - The Light object needed for rendering, as a `wgpu::BindGroup` object.
+    fn draw_model_meshes_with_index_format(
+        &mut self,
+        model: &'b Model,
+        index_format: wgpu::IndexFormat,
+        transparent: bool,
+        uniforms: &'b BindGroup,
+        light: &'b BindGroup,
+    ) {
+        let meshes = model.meshes.iter()
+            .filter(|mesh| mesh.index_format == index_format)
+            .filter(|mesh| model.materials[mesh.material].transparent == transparent);
+        for mesh in meshes {
+            let material = &model.materials[mesh.material];
+            for instances in model.get_visible_ranges() {
+                self.draw_mesh_instanced(mesh, material, uniforms, light, instances.clone(), model.get_instance_buffer());
+            }
+        }
+    }
+}
+
+
+/// Trait for rendering a Model without its Materials, e.g. the light box.
+///   The uniforms and light are bound to sets 0 and 1, as no texture bind group is used.
+pub trait DrawLight<'a, 'b> where 'b: 'a {
+
+    /// Draw an instanced Mesh to the screen without its Material.
+    ///
+    /// # Arguments
+    ///
+    /// `mesh`            - The Mesh object to be drawn.
+    /// `uniforms`        - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
+    /// `light`           - The Light object needed for rendering, as a `wgpu::BindGroup` object.
+    /// `instances`       - A Range object indexing the instances to be rendered.
+    /// `instances_buffer - The `wgpu::Buffer` objct containing the instancing data for each instance of the mesh.
+    fn draw_light_mesh_instanced(
+        &mut self,
+        mesh: &'b Mesh,
+        uniforms: &'b wgpu::BindGroup,
+        light: &'b wgpu::BindGroup,
+        instances: Range<u32>,
+        instance_buffer: &'b Buffer,
+    );
+
+    /// Draw the visible instances of the Meshes of a Model that use the given index format, without their Materials.
+    ///   The pipeline must have been created with the same index format.
+    ///
+    /// # Arguments
+    ///
+    /// `model`        - The Model object to be drawn.
+    /// `index_format` - The index format of the Meshes to draw.
+    /// `uniforms`     - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
+    /// `light`        - The Light object needed for rendering, as a `wgpu::BindGroup` object.
+    fn draw_light_model_with_index_format(
+        &mut self,
+        model: &'b Model,
+        index_format: wgpu::IndexFormat,
+        uniforms: &'b wgpu::BindGroup,
+        light: &'b wgpu::BindGroup,
+    );
+}
+
+/// Implement drawing without Materials for the `wgpu::RenderPass` object.
+impl<'a, 'b> DrawLight<'a, 'b> for wgpu::RenderPass<'a> where 'b: 'a {
+
+    /// Draw an instanced Mesh to the screen without its Material.
+    ///
+    /// # Arguments
+    ///
+    /// `mesh`            - The Mesh object to be drawn.
+    /// `uniforms`        - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
+    /// `light`           - The Light object needed for rendering, as a `wgpu::BindGroup` object.
+    /// `instances`       - A Range object indexing the instances to be rendered.
+    /// `instances_buffer - The `wgpu::Buffer` objct containing the instancing data for each instance of the mesh.
+    fn draw_light_mesh_instanced(
+        &mut self,
+        mesh: &'b Mesh,
+        uniforms: &'b BindGroup,
+        light: &'b BindGroup,
+        instances: Range<u32>,
+        instance_buffer: &'b Buffer,
+    ) {
+        self.set_vertex_buffer(0, &mesh.vertex_buffer, 0, 0);
+        self.set_vertex_buffer(1, instance_buffer, 0, 0);
+        self.set_index_buffer(&mesh.index_buffer, 0, 0);
+        self.set_bind_group(0, &uniforms, &[]);
+        self.set_bind_group(1, &light, &[]);
+        self.draw_indexed(0..mesh.num_elements, 0, instances);
+    }
+
+    /// Draw the visible instances of the Meshes of a Model that use the given index format, without their Materials.
+    ///   The pipeline must have been created with the same index format.
+    ///
+    /// # Arguments
+    ///
+    /// `model`        - The Model object to be drawn.
+    /// `index_format` - The index format of the Meshes to draw.
+    /// `uniforms`     - The Uniform objects needed for rendering, as a `wgpu::BindGroup` object.
+    /// `light`        - The Light object needed for rendering, as a `wgpu::BindGroup` object.
+    fn draw_light_model_with_index_format(
+        &mut self,
+        model: &'b Model,
+        index_format: wgpu::IndexFormat,
+        uniforms: &'b BindGroup,
+        light: &'b BindGroup,
+    ) {
+        for mesh in model.meshes.iter().filter(|mesh| mesh.index_format == index_format) {
+            for instances in model.get_visible_ranges() {
+                self.draw_light_mesh_instanced(mesh, uniforms, light, instances.clone(), model.get_instance_buffer());
+            }
+        }
+    }
+}
@@ -14,6 +14,18 @@ pub struct ModelVertex {
 
     // The normal vector.
     pub normal: [f32; 3],
+
+    // The tangent vector, pointing along the U direction of the texture coordinates.
+    pub tangent: [f32; 3],
+
+    // The bitangent vector, pointing along the V direction of the texture coordinates.
+    pub bitangent: [f32; 3],
+
+    // The indices of the (up to 4) bones that move the vertex (see `Model::set_bone_transforms`).
+    pub bone_indices: [u32; 4],
+
+    // The weights of the bones that move the vertex, which sum to 1.
+    pub bone_weights: [f32; 4],
 }
 
 /// Used for serializing the ModelVertex structure.
@@ -22,11 +34,23 @@ unsafe impl bytemuck::Zeroable for ModelVertex {}
 
 /// Constants describing the location in memory of the items in the structure.
 impl ModelVertex {
+    // Vertices that aren't skinned follow bone 0, which doesn't move unless the bone transforms are set.
+    pub const UNSKINNED_BONE_INDICES: [u32; 4] = [0, 0, 0, 0];
+    pub const UNSKINNED_BONE_WEIGHTS: [f32; 4] = [1.0, 0.0, 0.0, 0.0];
+
     pub const SIZE: BufferAddress = std::mem::size_of::<Self>() as BufferAddress;
     pub const POSITION_OFFSET: BufferAddress = 0  as BufferAddress;
     pub const TEX_COORDS_OFFSET: BufferAddress = std::mem::size_of::<[f32; 3]>() as BufferAddress;
     pub const NORMAL_OFFSET: BufferAddress = 
         Self::TEX_COORDS_OFFSET + (std::mem::size_of::<[f32; 2]>() as BufferAddress);
+    pub const TANGENT_OFFSET: BufferAddress =
+        Self::NORMAL_OFFSET + (std::mem::size_of::<[f32; 3]>() as BufferAddress);
+    pub const BITANGENT_OFFSET: BufferAddress =
+        Self::TANGENT_OFFSET + (std::mem::size_of::<[f32; 3]>() as BufferAddress);
+    pub const BONE_INDICES_OFFSET: BufferAddress =
+        Self::BITANGENT_OFFSET + (std::mem::size_of::<[f32; 3]>() as BufferAddress);
+    pub const BONE_WEIGHTS_OFFSET: BufferAddress =
+        Self::BONE_INDICES_OFFSET + (std::mem::size_of::<[u32; 4]>() as BufferAddress);
 }
 
 impl Vertex for ModelVertex {
@@ -52,6 +76,27 @@ impl Vertex for ModelVertex {
                     shader_location: 2,
                     format: wgpu::VertexFormat::Float3,
                 },
+                wgpu::VertexAttributeDescriptor {
+                    offset: Self::TANGENT_OFFSET,
+                    shader_location: 3,
+                    format: wgpu::VertexFormat::Float3,
+                },
+                wgpu::VertexAttributeDescriptor {
+                    offset: Self::BITANGENT_OFFSET,
+                    shader_location: 4,
+                    format: wgpu::VertexFormat::Float3,
+                },
+                // Locations 5 to 11 hold the InstanceRaw matrices.
+                wgpu::VertexAttributeDescriptor {
+                    offset: Self::BONE_INDICES_OFFSET,
+                    shader_location: 12,
+                    format: wgpu::VertexFormat::Uint4,
+                },
+                wgpu::VertexAttributeDescriptor {
+                    offset: Self::BONE_WEIGHTS_OFFSET,
+                    shader_location: 13,
+                    format: wgpu::VertexFormat::Float4,
+                },
             ]
         }
     }
@@ -0,0 +1,200 @@
+use cgmath::{InnerSpace, Quaternion, Rad, Rotation3, Vector3};
+use super::Pose;
+
+/// A revolute joint, which rotates its child links about an axis.
+pub struct Joint {
+
+    // The index of the parent Joint in the JointHierarchy, or None for a root Joint.
+    //   Parents always come before their children.
+    pub parent: Option<usize>,
+
+    // The Pose of the Joint relative to the parent Joint, at an angle of zero.
+    pub origin: Pose,
+
+    // The unit axis that the Joint rotates about, in the frame of the Joint.
+    pub axis: Vector3<f32>,
+
+    // The (lower, upper) bounds (in radians) of the angle of the Joint, or None if it turns freely.
+    //   A fixed Joint has the bounds (0, 0).
+    pub limits: Option<(f32, f32)>,
+
+    // The angle (in radians) of the Joint about its axis.
+    angle: f32,
+}
+
+impl Joint {
+
+    /// Get the angle (in radians) of the Joint about its axis.
+    pub fn get_angle(&self) -> f32 { self.angle }
+
+    /// Get the Pose of the Joint relative to its parent, at its current angle.
+    pub fn local_pose(&self) -> Pose {
+        let rotation = Quaternion::from_axis_angle(self.axis, Rad(self.angle));
+        return self.origin.then(&Pose::new(Vector3::new(0.0, 0.0, 0.0), rotation))
+    }
+}
+
+/// A tree of Joints, e.g. the chain of a robot arm, posed by forward kinematics.
+pub struct JointHierarchy {
+
+    // The Joints, with each parent before its children.
+    joints: Vec<Joint>,
+
+    // The Pose of each Joint in world space, kept up to date with the angles.
+    world_poses: Vec<Pose>,
+}
+
+impl JointHierarchy {
+
+    /// Create an empty JointHierarchy.
+    pub fn new() -> Self {
+        return JointHierarchy { joints: Vec::new(), world_poses: Vec::new() }
+    }
+
+    /// Add a Joint at an angle of zero.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent` - The index of the parent Joint, or None for a root Joint (placed relative to the world).
+    /// * `origin` - The Pose of the Joint relative to the parent Joint.
+    /// * `axis`   - The axis that the Joint rotates about, in the frame of the Joint.
+    ///
+    /// # Returns
+    ///
+    /// The index of the Joint.
+    pub fn add_joint(&mut self, parent: Option<usize>, origin: Pose, axis: Vector3<f32>) -> usize {
+        debug_assert!(parent.map_or(true, |parent| parent < self.joints.len()), "the parent joint must be added first");
+        let axis = if axis.magnitude2() > 0.0 { axis.normalize() } else { Vector3::unit_z() };
+        self.joints.push(Joint { parent, origin, axis, limits: None, angle: 0.0 });
+        let index = self.joints.len() - 1;
+        self.world_poses.push(self.compute_world_pose(index));
+        return index
+    }
+
+    /// Get the Joints, with each parent before its children.
+    pub fn get_joints(&self) -> &[Joint] { &self.joints }
+
+    /// Get the number of Joints.
+    pub fn len(&self) -> usize { self.joints.len() }
+
+    /// Whether there are no Joints.
+    pub fn is_empty(&self) -> bool { self.joints.is_empty() }
+
+    /// Get the angle (in radians) of a Joint about its axis.
+    pub fn get_joint_angle(&self, index: usize) -> f32 { self.joints[index].angle }
+
+    /// Set the bounds of the angle of a Joint, moving the Joint (and those below it) into the bounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `index`  - The index of the Joint.
+    /// * `limits` - The (lower, upper) bounds (in radians), or None if the Joint turns freely.
+    ///
+    /// # Returns
+    ///
+    /// The indices of the Joints whose world Pose changed.
+    pub fn set_joint_limits(&mut self, index: usize, limits: Option<(f32, f32)>) -> Vec<usize> {
+        self.joints[index].limits = limits;
+        let angle = self.joints[index].angle;
+        return self.set_joint_angle(index, angle)
+    }
+
+    /// Set the angle of a Joint, recomputing the world Poses of it and of the Joints below it.
+    ///   The angle is clamped to the limits of the Joint, if it has any.
+    ///
+    /// # Arguments
+    ///
+    /// * `index`   - The index of the Joint.
+    /// * `radians` - The new angle of the Joint about its axis.
+    ///
+    /// # Returns
+    ///
+    /// The indices of the Joints whose world Pose changed.
+    pub fn set_joint_angle(&mut self, index: usize, radians: f32) -> Vec<usize> {
+        let radians = match self.joints[index].limits {
+            Some((lower, upper)) => radians.max(lower).min(upper),
+            None => radians,
+        };
+        if self.joints[index].angle == radians {
+            return Vec::new()
+        }
+        self.joints[index].angle = radians;
+
+        // The children come after their parents, so a single pass reaches the whole subtree.
+        let mut moved = vec![index];
+        self.world_poses[index] = self.compute_world_pose(index);
+        for child in index + 1..self.joints.len() {
+            let parent_moved = self.joints[child].parent.map_or(false, |parent| moved.contains(&parent));
+            if parent_moved {
+                self.world_poses[child] = self.compute_world_pose(child);
+                moved.push(child);
+            }
+        }
+        return moved
+    }
+
+    /// Get the Pose of a Joint in world space.
+    pub fn get_world_pose(&self, index: usize) -> Pose { self.world_poses[index] }
+
+    /// Compute the Pose of a Joint in world space from the (up to date) Pose of its parent.
+    fn compute_world_pose(&self, index: usize) -> Pose {
+        let joint = &self.joints[index];
+        let parent_pose = joint.parent.map_or(Pose::identity(), |parent| self.world_poses[parent]);
+        return parent_pose.then(&joint.local_pose())
+    }
+}
+
+impl Default for JointHierarchy {
+    fn default() -> Self { Self::new() }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use cgmath::{InnerSpace, One};
+    use std::f32::consts::FRAC_PI_2;
+    use super::*;
+
+    /// A planar arm of three Joints turning about Z, each one unit along X from its parent.
+    fn planar_arm() -> JointHierarchy {
+        let mut joints = JointHierarchy::new();
+        let offset = Pose::new(Vector3::unit_x(), Quaternion::one());
+        let base = joints.add_joint(None, Pose::identity(), Vector3::unit_z());
+        let elbow = joints.add_joint(Some(base), offset, Vector3::unit_z());
+        joints.add_joint(Some(elbow), offset, Vector3::unit_z());
+        return joints
+    }
+
+    /// The tip of the arm, one unit along X from the last Joint.
+    fn end_effector(joints: &JointHierarchy) -> Vector3<f32> {
+        return joints.get_world_pose(2).transform_point(Vector3::unit_x())
+    }
+
+    fn assert_near(actual: Vector3<f32>, expected: Vector3<f32>) {
+        assert!((actual - expected).magnitude() < 1e-5, "expected {:?}, found {:?}", expected, actual);
+    }
+
+    #[test]
+    fn end_effector_follows_the_joint_angles() {
+        let mut joints = planar_arm();
+        assert_near(end_effector(&joints), Vector3::new(3.0, 0.0, 0.0));
+
+        // Turning the base swings the whole arm up to +Y.
+        assert_eq!(joints.set_joint_angle(0, FRAC_PI_2), vec![0, 1, 2]);
+        assert_near(end_effector(&joints), Vector3::new(0.0, 3.0, 0.0));
+
+        // Turning the elbow back points the rest of the arm along +X again.
+        assert_eq!(joints.set_joint_angle(1, -FRAC_PI_2), vec![1, 2]);
+        assert_near(end_effector(&joints), Vector3::new(2.0, 1.0, 0.0));
+
+        // Turning the last joint only moves its own link.
+        assert_eq!(joints.set_joint_angle(2, FRAC_PI_2), vec![2]);
+        assert_near(end_effector(&joints), Vector3::new(1.0, 2.0, 0.0));
+    }
+
+    #[test]
+    fn unchanged_angles_move_nothing() {
+        let mut joints = planar_arm();
+        assert!(joints.set_joint_angle(1, 0.0).is_empty());
+    }
+}
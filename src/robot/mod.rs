@@ -0,0 +1,11 @@
+mod joint;
+mod pose;
+mod robot;
+#[cfg(feature = "urdf")]
+mod urdf;
+
+pub use joint::{Joint, JointHierarchy};
+pub use pose::Pose;
+pub use robot::{Link, Robot};
+#[cfg(feature = "urdf")]
+pub use urdf::{UrdfLink, UrdfRobot};
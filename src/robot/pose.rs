@@ -0,0 +1,54 @@
+use cgmath::{One, Quaternion, Vector3, Zero};
+use crate::model::Instance;
+
+/// A rigid transform: a rotation followed by a translation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pose {
+
+    // The translation, i.e. where the origin of the frame is moved to.
+    pub position: Vector3<f32>,
+
+    // The rotation of the frame about its origin.
+    pub rotation: Quaternion<f32>,
+}
+
+impl Pose {
+
+    /// Construct a Pose from its translation and rotation.
+    pub fn new(position: Vector3<f32>, rotation: Quaternion<f32>) -> Self {
+        return Pose { position, rotation }
+    }
+
+    /// Construct the Pose that doesn't move anything.
+    pub fn identity() -> Self {
+        return Pose { position: Vector3::zero(), rotation: Quaternion::one() }
+    }
+
+    /// Compose a Pose given in the frame of this Pose, e.g. the Pose of a child relative to its parent.
+    ///
+    /// # Arguments
+    ///
+    /// * `child` - The Pose relative to this one.
+    pub fn then(&self, child: &Pose) -> Pose {
+        return Pose {
+            position: self.position + self.rotation * child.position,
+            rotation: self.rotation * child.rotation,
+        }
+    }
+
+    /// Move a point from the frame of this Pose into the parent frame.
+    pub fn transform_point(&self, point: Vector3<f32>) -> Vector3<f32> {
+        return self.position + self.rotation * point
+    }
+
+    /// Construct an Instance placed at this Pose, with a unit scale.
+    pub fn to_instance(&self) -> Instance {
+        let mut instance = Instance::from_position(self.position);
+        instance.rotation = self.rotation;
+        return instance
+    }
+}
+
+impl Default for Pose {
+    fn default() -> Self { Self::identity() }
+}
@@ -0,0 +1,118 @@
+use cgmath::Vector3;
+use crate::{model::Instance, state::ModelId};
+use super::{JointHierarchy, Pose};
+
+/// A rigid part of a Robot, drawn by a Model that follows one of the Joints.
+pub struct Link {
+
+    // The index of the Joint that moves the Link, or None if the Link is fixed in the world (e.g. the base).
+    pub joint: Option<usize>,
+
+    // The handle of the Model that draws the Link. The Robot places the first instance of the Model.
+    pub model: ModelId,
+
+    // The Pose of the Model relative to its Joint.
+    pub origin: Pose,
+
+    // The scale of the Model along each axis.
+    pub scale: Vector3<f32>,
+}
+
+/// A JointHierarchy whose Joints move the Models of its Links, e.g. the links of a robot arm.
+pub struct Robot {
+
+    // The Joints of the Robot.
+    joints: JointHierarchy,
+
+    // The Links that are moved by the Joints.
+    links: Vec<Link>,
+
+    // The names of the Joints, in the same order as the Joints. Empty if the Joints aren't named.
+    joint_names: Vec<String>,
+}
+
+impl Robot {
+
+    /// Create a Robot from its Joints and Links.
+    ///
+    /// # Arguments
+    ///
+    /// * `joints` - The Joints of the Robot.
+    /// * `links`  - The Links moved by the Joints. Each refers to its Joint by index.
+    pub fn new(joints: JointHierarchy, links: Vec<Link>) -> Self {
+        return Robot { joints, links, joint_names: Vec::new() }
+    }
+
+    /// Get the Joints of the Robot.
+    pub fn get_joints(&self) -> &JointHierarchy { &self.joints }
+
+    /// Get the names of the Joints, in the same order as the Joints.
+    pub fn get_joint_names(&self) -> &[String] { &self.joint_names }
+
+    /// Name the Joints, e.g. with the joint names of a URDF file, so they can be found by `find_joint`.
+    ///
+    /// # Arguments
+    ///
+    /// * `joint_names` - The names of the Joints, in the same order as the Joints.
+    pub fn set_joint_names(&mut self, joint_names: Vec<String>) {
+        self.joint_names = joint_names;
+    }
+
+    /// Find the index of a Joint by its name (see `set_joint_names`).
+    pub fn find_joint(&self, name: &str) -> Option<usize> {
+        return self.joint_names.iter().position(|joint_name| joint_name == name)
+    }
+
+    /// Get the Links of the Robot.
+    pub fn get_links(&self) -> &[Link] { &self.links }
+
+    /// Set the angle of a Joint, moving the Links below it.
+    ///
+    /// # Arguments
+    ///
+    /// * `index`   - The index of the Joint.
+    /// * `radians` - The new angle of the Joint about its axis.
+    ///
+    /// # Returns
+    ///
+    /// The handles of the Models of the Links that moved, along with their new Instances.
+    pub fn set_joint_angle(&mut self, index: usize, radians: f32) -> Vec<(ModelId, Instance)> {
+        let moved = self.joints.set_joint_angle(index, radians);
+        return self.links.iter()
+            .filter(|link| link.joint.map_or(false, |joint| moved.contains(&joint)))
+            .map(|link| (link.model, self.link_instance(link)))
+            .collect()
+    }
+
+    /// Get the Instances placing the Models of all of the Links.
+    pub fn get_link_instances(&self) -> Vec<(ModelId, Instance)> {
+        return self.links.iter().map(|link| (link.model, self.link_instance(link))).collect()
+    }
+
+    /// Get the Instance placing the Model of a Link in world space.
+    fn link_instance(&self, link: &Link) -> Instance {
+        let joint_pose = link.joint.map_or(Pose::identity(), |joint| self.joints.get_world_pose(joint));
+        let mut instance = joint_pose.then(&link.origin).to_instance();
+        instance.scale = link.scale;
+        return instance
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joints_are_found_by_name() {
+        let mut joints = JointHierarchy::new();
+        let shoulder = joints.add_joint(None, Pose::identity(), Vector3::unit_z());
+        let elbow = joints.add_joint(Some(shoulder), Pose::identity(), Vector3::unit_z());
+        let mut robot = Robot::new(joints, Vec::new());
+        assert_eq!(robot.find_joint("elbow"), None);
+
+        robot.set_joint_names(vec![String::from("shoulder"), String::from("elbow")]);
+        assert_eq!(robot.find_joint("elbow"), Some(elbow));
+        assert_eq!(robot.find_joint("gripper"), None);
+    }
+}
@@ -0,0 +1,369 @@
+use std::{collections::{HashMap, VecDeque}, path::{Path, PathBuf}};
+use cgmath::{Deg, Quaternion, Rad, Rotation3, Vector3};
+use super::{JointHierarchy, Pose};
+
+/// A link of a URDF robot description, with the mesh that draws it.
+pub struct UrdfLink {
+
+    // The name of the link.
+    pub name: String,
+
+    // The index of the Joint that moves the link, or None for the root link.
+    pub joint: Option<usize>,
+
+    // The path to the mesh file drawing the link, if it has one (see `resolve_mesh_path`).
+    pub mesh: Option<PathBuf>,
+
+    // The Pose of the mesh relative to the Joint.
+    pub origin: Pose,
+
+    // The scale of the mesh along each axis, e.g. 0.001 for a mesh modelled in millimetres.
+    pub scale: Vector3<f32>,
+}
+
+/// A robot read from a URDF file: its Joints, and the links they move.
+pub struct UrdfRobot {
+
+    // The name of the robot.
+    pub name: String,
+
+    // The Joints of the robot, with the limits from the file.
+    //   Fixed joints are limited to (0, 0), and continuous joints are unlimited.
+    pub joints: JointHierarchy,
+
+    // The names of the Joints, in the same order as the Joints.
+    pub joint_names: Vec<String>,
+
+    // The links of the robot, in the order of the file.
+    pub links: Vec<UrdfLink>,
+}
+
+/// A `<joint>` element of a URDF file.
+struct UrdfJoint {
+    name: String,
+    joint_type: String,
+    parent: String,
+    child: String,
+    origin: Pose,
+    axis: Vector3<f32>,
+    limits: (f32, f32),
+}
+
+impl UrdfRobot {
+
+    /// Read a URDF file. Mesh paths are resolved relative to the directory of the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the URDF file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, failure::Error> {
+        let path = path.as_ref();
+        info!("Loading robot description {:?}", path);
+        let text = std::fs::read_to_string(path)?;
+        let directory = path.parent().unwrap_or_else(|| Path::new(""));
+        return Self::parse(&text, directory)
+    }
+
+    /// Parse the text of a URDF file.
+    ///   URDF files are Z-up, so the robot is turned to stand on the XZ plane of the scene.
+    ///
+    /// # Arguments
+    ///
+    /// * `text`      - The XML text of the URDF file.
+    /// * `directory` - The directory that relative mesh paths are resolved against.
+    pub fn parse(text: &str, directory: &Path) -> Result<Self, failure::Error> {
+        let document = roxmltree::Document::parse(text)?;
+        let robot = document.root_element();
+        if !robot.has_tag_name("robot") {
+            return Err(failure::format_err!("The root element of a URDF file must be <robot>"))
+        }
+        let name = robot.attribute("name").unwrap_or("robot").to_string();
+
+        // Read the links, with the mesh and origin of their first visual.
+        let mut links = Vec::new();
+        for link in robot.children().filter(|node| node.has_tag_name("link")) {
+            let link_name = required_attribute(&link, "name")?.to_string();
+            let visual = child_element(&link, "visual");
+            let origin = parse_origin(visual.as_ref().and_then(|visual| child_element(visual, "origin")))?;
+            let mesh = visual.as_ref()
+                .and_then(|visual| child_element(visual, "geometry"))
+                .and_then(|geometry| child_element(&geometry, "mesh"));
+            let scale = parse_vector(mesh.and_then(|mesh| mesh.attribute("scale")), Vector3::new(1.0, 1.0, 1.0))?;
+            let mesh_path = match mesh {
+                Some(mesh) => Some(resolve_mesh_path(required_attribute(&mesh, "filename")?, directory)),
+                None => None,
+            };
+            links.push(UrdfLink { name: link_name, joint: None, mesh: mesh_path, origin, scale });
+        }
+
+        // Read the joints.
+        let mut urdf_joints = Vec::new();
+        for joint in robot.children().filter(|node| node.has_tag_name("joint")) {
+            let parent = child_element(&joint, "parent").ok_or_else(|| failure::format_err!("A joint has no <parent>"))?;
+            let child = child_element(&joint, "child").ok_or_else(|| failure::format_err!("A joint has no <child>"))?;
+            let axis = child_element(&joint, "axis").and_then(|axis| axis.attribute("xyz"));
+            let limit = child_element(&joint, "limit");
+            let parse_limit = |name: &str| -> Result<f32, failure::Error> {
+                return Ok(limit.and_then(|limit| limit.attribute(name)).map(str::parse).transpose()?.unwrap_or(0.0))
+            };
+            urdf_joints.push(UrdfJoint {
+                name: required_attribute(&joint, "name")?.to_string(),
+                joint_type: required_attribute(&joint, "type")?.to_string(),
+                parent: required_attribute(&parent, "link")?.to_string(),
+                child: required_attribute(&child, "link")?.to_string(),
+                origin: parse_origin(child_element(&joint, "origin"))?,
+                axis: parse_vector(axis, Vector3::new(1.0, 0.0, 0.0))?,
+                limits: (parse_limit("lower")?, parse_limit("upper")?),
+            });
+        }
+
+        // Add the Joints from the root link down, so that each parent comes before its children.
+        let base = Pose::new(Vector3::new(0.0, 0.0, 0.0), Quaternion::from_angle_x(Deg(-90.0)));
+        let child_links: Vec<&str> = urdf_joints.iter().map(|joint| joint.child.as_str()).collect();
+        let mut link_joints: HashMap<String, Option<usize>> = HashMap::new();
+        let mut queue: VecDeque<(String, Option<usize>)> = links.iter()
+            .filter(|link| !child_links.contains(&link.name.as_str()))
+            .map(|link| (link.name.clone(), None))
+            .collect();
+        let mut joints = JointHierarchy::new();
+        let mut joint_names = Vec::new();
+        while let Some((link_name, link_joint)) = queue.pop_front() {
+            if link_joints.contains_key(&link_name) {
+                continue
+            }
+            link_joints.insert(link_name.clone(), link_joint);
+            for urdf_joint in urdf_joints.iter().filter(|joint| joint.parent == link_name) {
+                let origin = if link_joint.is_none() { base.then(&urdf_joint.origin) } else { urdf_joint.origin };
+                let index = joints.add_joint(link_joint, origin, urdf_joint.axis);
+                let limits = match urdf_joint.joint_type.as_str() {
+                    "revolute" => Some(urdf_joint.limits),
+                    "continuous" => None,
+                    "fixed" => Some((0.0, 0.0)),
+                    joint_type => {
+                        warn!("Treating the {} joint {:?} as fixed", joint_type, urdf_joint.name);
+                        Some((0.0, 0.0))
+                    }
+                };
+                joints.set_joint_limits(index, limits);
+                joint_names.push(urdf_joint.name.clone());
+                queue.push_back((urdf_joint.child.clone(), Some(index)));
+            }
+        }
+
+        // Attach the links to their Joints. Links that no root reaches (i.e. in a cycle) are dropped.
+        let links = links.into_iter()
+            .filter_map(|mut link| {
+                let joint = match link_joints.get(&link.name) {
+                    Some(&joint) => joint,
+                    None => {
+                        warn!("Dropping link {:?}, which isn't connected to the root link", link.name);
+                        return None
+                    }
+                };
+                link.joint = joint;
+                if joint.is_none() {
+                    link.origin = base.then(&link.origin);
+                }
+                return Some(link)
+            })
+            .collect();
+
+        return Ok(UrdfRobot { name, joints, joint_names, links })
+    }
+}
+
+/// Resolve the `filename` of a URDF mesh into a path.
+///   `package://<package>/<path>` is looked up in the directories above the URDF file (and their children),
+///   falling back to the directory of the URDF file. Relative paths are relative to the URDF file.
+///
+/// # Arguments
+///
+/// * `filename`  - The `filename` attribute of the `<mesh>` element.
+/// * `directory` - The directory of the URDF file.
+fn resolve_mesh_path(filename: &str, directory: &Path) -> PathBuf {
+    const PACKAGE_SCHEME: &str = "package://";
+    const FILE_SCHEME: &str = "file://";
+    if filename.starts_with(PACKAGE_SCHEME) {
+        let uri = &filename[PACKAGE_SCHEME.len()..];
+        let (package, relative) = match uri.find('/') {
+            Some(slash) => (&uri[..slash], &uri[slash + 1..]),
+            None => (uri, ""),
+        };
+        for ancestor in directory.ancestors() {
+            if ancestor.file_name().map_or(false, |name| name == package) {
+                return ancestor.join(relative)
+            }
+            let candidate = ancestor.join(package);
+            if candidate.is_dir() {
+                return candidate.join(relative)
+            }
+        }
+        return directory.join(relative)
+    }
+    if filename.starts_with(FILE_SCHEME) {
+        return PathBuf::from(&filename[FILE_SCHEME.len()..])
+    }
+    return directory.join(filename)
+}
+
+/// Get the first child element with the tag name.
+fn child_element<'a, 'd>(node: &roxmltree::Node<'a, 'd>, name: &str) -> Option<roxmltree::Node<'a, 'd>> {
+    return node.children().find(|child| child.has_tag_name(name))
+}
+
+/// Get an attribute that the URDF format requires.
+fn required_attribute<'a>(node: &roxmltree::Node<'a, '_>, name: &str) -> Result<&'a str, failure::Error> {
+    return node.attribute(name).ok_or_else(|| failure::format_err!(
+        "The <{}> element has no {:?} attribute", node.tag_name().name(), name,
+    ))
+}
+
+/// Parse a vector of three space separated numbers, e.g. the `xyz` of an `<origin>`.
+fn parse_vector(text: Option<&str>, default: Vector3<f32>) -> Result<Vector3<f32>, failure::Error> {
+    let text = match text {
+        Some(text) => text,
+        None => return Ok(default),
+    };
+    let values = text.split_whitespace().map(str::parse).collect::<Result<Vec<f32>, _>>()?;
+    return match values.as_slice() {
+        &[x, y, z] => Ok(Vector3::new(x, y, z)),
+        _ => Err(failure::format_err!("Expected three numbers, found {:?}", text)),
+    }
+}
+
+/// Parse an `<origin>` element. The `rpy` rotation is a roll about X, then pitch about Y, then yaw about Z.
+fn parse_origin(origin: Option<roxmltree::Node>) -> Result<Pose, failure::Error> {
+    let origin = match origin {
+        Some(origin) => origin,
+        None => return Ok(Pose::identity()),
+    };
+    let position = parse_vector(origin.attribute("xyz"), Vector3::new(0.0, 0.0, 0.0))?;
+    let rpy = parse_vector(origin.attribute("rpy"), Vector3::new(0.0, 0.0, 0.0))?;
+    let rotation = Quaternion::from_angle_z(Rad(rpy.z)) * Quaternion::from_angle_y(Rad(rpy.y)) * Quaternion::from_angle_x(Rad(rpy.x));
+    return Ok(Pose::new(position, rotation))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use cgmath::InnerSpace;
+    use super::*;
+
+    // The joints are listed out of order, so that the wrist comes before the elbow that moves it.
+    const ARM: &str = r#"
+        <robot name="arm">
+            <link name="base"/>
+            <link name="upper">
+                <visual>
+                    <origin xyz="0 0 1"/>
+                    <geometry><mesh filename="meshes/upper.obj" scale="0.001 0.001 0.002"/></geometry>
+                </visual>
+            </link>
+            <link name="lower"/>
+            <link name="tool"/>
+            <joint name="shoulder" type="revolute">
+                <parent link="base"/>
+                <child link="upper"/>
+                <origin xyz="0 0 0.5"/>
+                <axis xyz="0 0 1"/>
+                <limit lower="-1.5" upper="1.5"/>
+            </joint>
+            <joint name="wrist" type="fixed">
+                <parent link="lower"/>
+                <child link="tool"/>
+            </joint>
+            <joint name="elbow" type="continuous">
+                <parent link="upper"/>
+                <child link="lower"/>
+                <origin xyz="0 0 1" rpy="0.3 0.2 0.1"/>
+                <axis xyz="0 1 0"/>
+            </joint>
+        </robot>
+    "#;
+
+    fn arm() -> UrdfRobot {
+        return UrdfRobot::parse(ARM, Path::new("/robots/arm")).unwrap()
+    }
+
+    /// Whether two rotations are the same, i.e. their quaternions are equal up to their sign.
+    fn same_rotation(a: Quaternion<f32>, b: Quaternion<f32>) -> bool {
+        return (a.dot(b).abs() - 1.0).abs() < 1e-5
+    }
+
+    #[test]
+    fn joints_are_ordered_from_the_root() {
+        let robot = arm();
+        assert_eq!(robot.name, "arm");
+        assert_eq!(robot.joint_names, ["shoulder", "elbow", "wrist"]);
+        let parents: Vec<Option<usize>> = robot.joints.get_joints().iter().map(|joint| joint.parent).collect();
+        assert_eq!(parents, [None, Some(0), Some(1)]);
+
+        let link_joints: Vec<(&str, Option<usize>)> = robot.links.iter()
+            .map(|link| (link.name.as_str(), link.joint))
+            .collect();
+        assert_eq!(link_joints, [("base", None), ("upper", Some(0)), ("lower", Some(1)), ("tool", Some(2))]);
+    }
+
+    #[test]
+    fn joint_types_set_the_limits() {
+        let limits: Vec<Option<(f32, f32)>> = arm().joints.get_joints().iter().map(|joint| joint.limits).collect();
+        assert_eq!(limits, [Some((-1.5, 1.5)), None, Some((0.0, 0.0))]);
+    }
+
+    #[test]
+    fn rpy_is_a_roll_then_a_pitch_then_a_yaw() {
+        let elbow = arm().joints.get_joints()[1].origin;
+        let (roll, pitch, yaw) = (Quaternion::from_angle_x(Rad(0.3)), Quaternion::from_angle_y(Rad(0.2)), Quaternion::from_angle_z(Rad(0.1)));
+        assert!(same_rotation(elbow.rotation, yaw * pitch * roll));
+        assert!(!same_rotation(elbow.rotation, roll * pitch * yaw));
+        assert_eq!(elbow.position, Vector3::new(0.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn root_joints_stand_the_robot_up() {
+        // The Z-up shoulder offset points up the Y axis of the scene.
+        let shoulder = arm().joints.get_joints()[0].origin;
+        assert!((shoulder.position - Vector3::new(0.0, 0.5, 0.0)).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn mesh_scales_are_kept() {
+        let robot = arm();
+        assert_eq!(robot.links[1].scale, Vector3::new(0.001, 0.001, 0.002));
+        assert_eq!(robot.links[0].scale, Vector3::new(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn relative_mesh_paths_resolve_against_the_urdf_directory() {
+        let robot = arm();
+        assert_eq!(robot.links[1].mesh, Some(PathBuf::from("/robots/arm/meshes/upper.obj")));
+        assert_eq!(robot.links[0].mesh, None);
+        assert_eq!(resolve_mesh_path("file:///meshes/base.obj", Path::new("/robots/arm")), PathBuf::from("/meshes/base.obj"));
+    }
+
+    #[test]
+    fn package_paths_resolve_to_the_package_directory() {
+        let root = std::env::temp_dir().join(format!("arm_viz_urdf_{}", std::process::id()));
+        let urdf_directory = root.join("arm_description").join("urdf");
+        std::fs::create_dir_all(&urdf_directory).unwrap();
+        std::fs::create_dir_all(root.join("arm_meshes")).unwrap();
+
+        // The package is the directory of the URDF file (or above it)...
+        assert_eq!(
+            resolve_mesh_path("package://arm_description/meshes/base.obj", &urdf_directory),
+            root.join("arm_description").join("meshes/base.obj"),
+        );
+        // ...or next to one of the directories above it...
+        assert_eq!(
+            resolve_mesh_path("package://arm_meshes/base.obj", &urdf_directory),
+            root.join("arm_meshes").join("base.obj"),
+        );
+        // ...and falls back to the directory of the URDF file.
+        assert_eq!(
+            resolve_mesh_path("package://missing/base.obj", &urdf_directory),
+            urdf_directory.join("base.obj"),
+        );
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
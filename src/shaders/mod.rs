@@ -1,62 +1,165 @@
 use shaderc::ShaderKind;
-use std::io::Cursor;
+use std::{io::Cursor, path::Path};
+
+#[cfg(feature = "hot-reload")]
+mod watcher;
+#[cfg(feature = "hot-reload")]
+pub use watcher::ShaderWatcher;
 
 pub struct ShaderData {
     pub fragment: Vec<u32>,
     pub vertex: Vec<u32>,
 }
 
+impl ShaderData {
+
+    /// Compile GLSL shader programs into SPIR-V.
+    ///
+    /// # Arguments
+    ///
+    /// * `vertex_source`   - The GLSL source of the vertex shader.
+    /// * `vertex_name`     - The file name of the vertex shader, used in the error messages.
+    /// * `fragment_source` - The GLSL source of the fragment shader.
+    /// * `fragment_name`   - The file name of the fragment shader, used in the error messages.
+    ///
+    /// # Returns
+    ///
+    /// The compiled shaders, or the error of `shaderc`. Its message holds the formatted compilation log.
+    pub fn try_compile(
+        vertex_source: &str,
+        vertex_name: &str,
+        fragment_source: &str,
+        fragment_name: &str,
+    ) -> Result<Self, shaderc::Error> {
+        return Ok(ShaderData {
+            fragment: compile(fragment_source, ShaderKind::Fragment, fragment_name)?,
+            vertex: compile(vertex_source, ShaderKind::Vertex, vertex_name)?,
+        })
+    }
+
+    /// Read GLSL shader programs from disk and compile them at runtime.
+    ///   This is meant for iterating on the shaders without rebuilding (see `State::reload_shaders`).
+    ///
+    /// # Arguments
+    ///
+    /// * `vertex_path`   - The path to the GLSL source of the vertex shader.
+    /// * `fragment_path` - The path to the GLSL source of the fragment shader.
+    pub fn load_from_path<P: AsRef<Path>>(vertex_path: P, fragment_path: P) -> Result<Self, failure::Error> {
+        let (vertex_path, fragment_path) = (vertex_path.as_ref(), fragment_path.as_ref());
+        let file_name = |path: &Path| path.file_name().and_then(|name| name.to_str()).unwrap_or("shader").to_string();
+        let vertex_source = std::fs::read_to_string(vertex_path)?;
+        let fragment_source = std::fs::read_to_string(fragment_path)?;
+        return Ok(Self::try_compile(&vertex_source, &file_name(vertex_path), &fragment_source, &file_name(fragment_path))?)
+    }
+}
+
+/// Compile a single GLSL shader into SPIR-V.
+fn compile(source: &str, kind: ShaderKind, name: &str) -> Result<Vec<u32>, shaderc::Error> {
+    debug!("Compiling shader {}", name);
+    let mut compiler = shaderc::Compiler::new()
+        .ok_or_else(|| shaderc::Error::InternalError(String::from("failed to create the shader compiler")))?;
+    let spirv = compiler.compile_into_spirv(source, kind, name, "main", None)?;
+    return wgpu::read_spirv(Cursor::new(spirv.as_binary_u8()))
+        .map_err(|error| shaderc::Error::InternalError(format!("invalid SPIR-V for {}: {}", name, error)))
+}
+
+/// Compile the GLSL shader programs that are built into the binary.
+///   These are expected to compile, so an error panics with the formatted compilation log.
+fn compile_baked(vertex_source: &str, vertex_name: &str, fragment_source: &str, fragment_name: &str) -> ShaderData {
+    return ShaderData::try_compile(vertex_source, vertex_name, fragment_source, fragment_name)
+        .unwrap_or_else(|error| panic!("Failed to compile the shaders {} and {}:\n{}", vertex_name, fragment_name, error))
+}
+
+
+/// The shader programs used by the pipelines of the State.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ShaderProgram {
+    Model,
+    Toon,
+    Light,
+    Line,
+    Axes,
+    Outline,
+    Skybox,
+}
+
+impl ShaderProgram {
+
+    pub const ALL: [ShaderProgram; 7] = [
+        ShaderProgram::Model,
+        ShaderProgram::Toon,
+        ShaderProgram::Light,
+        ShaderProgram::Line,
+        ShaderProgram::Axes,
+        ShaderProgram::Outline,
+        ShaderProgram::Skybox,
+    ];
+
+    /// Get the file names of the GLSL sources of the (vertex, fragment) shaders.
+    pub fn file_names(self) -> (&'static str, &'static str) {
+        return match self {
+            ShaderProgram::Model => ("model.vert", "model.frag"),
+            ShaderProgram::Toon => ("model.vert", "model_toon.frag"),
+            ShaderProgram::Light => ("light.vert", "light.frag"),
+            ShaderProgram::Line => ("line.vert", "line.frag"),
+            ShaderProgram::Axes => ("axes.vert", "line.frag"),
+            ShaderProgram::Outline => ("outline.vert", "outline.frag"),
+            ShaderProgram::Skybox => ("skybox.vert", "skybox.frag"),
+        }
+    }
+
+    /// Get the shader programs that were compiled into the binary.
+    pub fn baked(self) -> &'static ShaderData {
+        return match self {
+            ShaderProgram::Model => &MODEL_SHADER_DATA,
+            ShaderProgram::Toon => &TOON_SHADER_DATA,
+            ShaderProgram::Light => &LIGHT_SHADER_DATA,
+            ShaderProgram::Line => &LINE_SHADER_DATA,
+            ShaderProgram::Axes => &AXES_SHADER_DATA,
+            ShaderProgram::Outline => &OUTLINE_SHADER_DATA,
+            ShaderProgram::Skybox => &SKYBOX_SHADER_DATA,
+        }
+    }
+
+    /// Read and compile the GLSL sources of the shader programs from a directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The directory holding the GLSL sources, e.g. `src/shaders/src`.
+    pub fn load_from_dir<P: AsRef<Path>>(self, directory: P) -> Result<ShaderData, failure::Error> {
+        let (vertex, fragment) = self.file_names();
+        let directory = directory.as_ref();
+        return ShaderData::load_from_path(directory.join(vertex), directory.join(fragment))
+    }
+}
+
 
 lazy_static! {
-    pub static ref MODEL_SHADER_DATA: ShaderData = 
-        ShaderData {
-            fragment: {
-                let mut compiler = shaderc::Compiler::new().unwrap();
-                let spirv = compiler.compile_into_spirv(
-                    include_str!("src/model.frag"),
-                    ShaderKind::Fragment,
-                    "model.frag",
-                    "main",
-                    None,
-                ).unwrap();
-                wgpu::read_spirv(Cursor::new(spirv.as_binary_u8())).unwrap()
-            },
-            vertex: {
-                let mut compiler = shaderc::Compiler::new().unwrap();
-                let spirv = compiler.compile_into_spirv(
-                    include_str!("src/model.vert"),
-                    ShaderKind::Vertex,
-                    "model.vert",
-                    "main",
-                    None,
-                ).unwrap();
-                wgpu::read_spirv(Cursor::new(spirv.as_binary_u8())).unwrap()
-            },
-        };
-    
-    pub static ref LIGHT_SHADER_DATA: ShaderData = 
-        ShaderData {
-            fragment: {
-                let mut compiler = shaderc::Compiler::new().unwrap();
-                let spirv = compiler.compile_into_spirv(
-                    include_str!("src/light.frag"),
-                    ShaderKind::Fragment,
-                    "light.frag",
-                    "main",
-                    None,
-                ).unwrap();
-                wgpu::read_spirv(Cursor::new(spirv.as_binary_u8())).unwrap()
-            },
-            vertex: {
-                let mut compiler = shaderc::Compiler::new().unwrap();
-                let spirv = compiler.compile_into_spirv(
-                    include_str!("src/light.vert"),
-                    ShaderKind::Vertex,
-                    "light.vert",
-                    "main",
-                    None,
-                ).unwrap();
-                wgpu::read_spirv(Cursor::new(spirv.as_binary_u8())).unwrap()
-            },
-        };
+    pub static ref MODEL_SHADER_DATA: ShaderData = compile_baked(
+        include_str!("src/model.vert"), "model.vert", include_str!("src/model.frag"), "model.frag",
+    );
+
+    pub static ref LIGHT_SHADER_DATA: ShaderData = compile_baked(
+        include_str!("src/light.vert"), "light.vert", include_str!("src/light.frag"), "light.frag",
+    );
+
+    pub static ref LINE_SHADER_DATA: ShaderData = compile_baked(
+        include_str!("src/line.vert"), "line.vert", include_str!("src/line.frag"), "line.frag",
+    );
+
+    pub static ref AXES_SHADER_DATA: ShaderData = compile_baked(
+        include_str!("src/axes.vert"), "axes.vert", include_str!("src/line.frag"), "line.frag",
+    );
+
+    pub static ref TOON_SHADER_DATA: ShaderData = compile_baked(
+        include_str!("src/model.vert"), "model.vert", include_str!("src/model_toon.frag"), "model_toon.frag",
+    );
+
+    pub static ref OUTLINE_SHADER_DATA: ShaderData = compile_baked(
+        include_str!("src/outline.vert"), "outline.vert", include_str!("src/outline.frag"), "outline.frag",
+    );
+
+    pub static ref SKYBOX_SHADER_DATA: ShaderData = compile_baked(
+        include_str!("src/skybox.vert"), "skybox.vert", include_str!("src/skybox.frag"), "skybox.frag",
+    );
 }
\ No newline at end of file
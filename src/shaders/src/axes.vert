@@ -0,0 +1,22 @@
+#version 450
+
+layout(location=0) in vec3 a_position;
+layout(location=1) in vec3 a_color;
+
+layout(location=0) out vec3 v_color;
+
+layout(set=0, binding=0)
+uniform Uniforms {
+    vec3 u_view_position;
+    mat4 u_view_proj;
+};
+
+// The length of the axes relative to the distance between the Viewer and the origin.
+float screen_scale = 0.1;
+
+void main() {
+    // Scale the axes with the distance to the Viewer, so they keep a constant size on screen.
+    float scale = length(u_view_position) * screen_scale;
+    gl_Position = u_view_proj * vec4(a_position * scale, 1.0);
+    v_color = a_color;
+}
@@ -5,13 +5,13 @@ layout(location=0) in vec3 a_position;
 
 layout(location=0) out vec3 v_color;
 
-layout(set=1, binding=0)
+layout(set=0, binding=0)
 uniform Uniforms {
     vec3 u_view_position; // unused
     mat4 u_view_proj;
 };
 
-layout(set=2, binding=0)
+layout(set=1, binding=0)
 uniform Light {
     vec3 u_position;
     vec3 u_color;
@@ -4,30 +4,46 @@
 layout(location=0) in vec3 v_position;
 layout(location=1) in vec2 v_tex_coords;
 layout(location=2) in vec3 v_normal;
+layout(location=3) in vec3 v_tangent;
+layout(location=4) in vec3 v_bitangent;
 
 layout(location=0) out vec4 f_color;
 
 layout(set=0, binding=0) uniform texture2D t_diffuse;
 layout(set=0, binding=1) uniform sampler s_diffuse;
+layout(set=0, binding=2) uniform texture2D t_normal;
+layout(set=0, binding=3) uniform sampler s_normal;
 layout(set=1, binding=0)
 uniform Uniforms {
     vec3 u_view_position;
     mat4 u_view_proj;
+    vec3 u_fog_color;
+    float u_fog_density;
+    float u_toon_bands; // unused
+    float u_exposure;
 };
 layout(set=2, binding=0) 
 uniform Light {
     vec3 light_position;
     vec3 light_color;
+    float ambient_intensity;
+    vec3 ambient_color;
+    float attenuation_constant;
+    float attenuation_linear;
+    float attenuation_quadratic;
 };
 
 void main() {
     vec3 light_dir = normalize(light_position - v_position);
-    vec3 normal = normalize(v_normal);
+
+    // Perturb the surface normal with the normal map, which is stored in tangent space.
+    mat3 tangent_matrix = mat3(normalize(v_tangent), normalize(v_bitangent), normalize(v_normal));
+    vec3 tangent_normal = texture(sampler2D(t_normal, s_normal), v_tex_coords).rgb * 2.0 - 1.0;
+    vec3 normal = normalize(tangent_matrix * tangent_normal);
     vec4 object_color = texture(sampler2D(t_diffuse, s_diffuse), v_tex_coords);
 
-    // Compute the ambient color.
-    float ambient_strength = 0.1;
-    vec3 ambient_color = ambient_strength * light_color;
+    // Compute the ambient color. This is applied regardless of the light position.
+    vec3 ambient = ambient_intensity * ambient_color;
 
     // Compute the diffuse color.
     float diffuse_strength = max(dot(normal, light_dir), 0.0);
@@ -39,7 +55,24 @@ void main() {
     float specular_strength = pow(max(dot(normal, half_dir), 0.0), 32);
     vec3 specular_color = specular_strength * light_color;
 
+    // Dim the direct light with the distance from the light source.
+    float distance = length(light_position - v_position);
+    float attenuation = 1.0 / max(
+        attenuation_constant + attenuation_linear * distance + attenuation_quadratic * distance * distance,
+        1e-4
+    );
+
     // Combine the all the colors.
-    vec3 result = (ambient_color + diffuse_color + specular_color) * object_color.xyz;
+    vec3 result = (ambient + attenuation * (diffuse_color + specular_color)) * object_color.xyz;
+
+    // Fade into the fog with the distance from the viewer (exponential squared falloff).
+    float view_distance = length(u_view_position - v_position);
+    float fog_factor = exp(-pow(u_fog_density * view_distance, 2.0));
+    result = mix(u_fog_color, result, fog_factor);
+
+    // Tonemap the exposed color (Reinhard), so bright highlights roll off instead of clipping.
+    //   The color is still linear here; the sRGB swap chain encodes it when it is written.
+    result *= u_exposure;
+    result = result / (result + vec3(1.0));
     f_color = vec4(result, object_color.a);
 }
@@ -1,15 +1,29 @@
 // shader.vert
 #version 450
 
+// The most bones that can move the vertices. This must match `MAX_BONES` in `bones.rs`.
+#define MAX_BONES 64
+
 layout(location=0) in vec3 a_position;
 layout(location=1) in vec2 a_tex_coords;
 layout(location=2) in vec3 a_normal;
-layout(location=3) in mat4 a_model_matrix;
-layout(location=7) in mat3 a_normal_matrix;
+layout(location=3) in vec3 a_tangent;
+layout(location=4) in vec3 a_bitangent;
+layout(location=5) in mat4 a_model_matrix;
+layout(location=9) in mat3 a_normal_matrix;
+layout(location=12) in uvec4 a_bone_indices;
+layout(location=13) in vec4 a_bone_weights;
 
 layout(location=0) out vec3 v_position;
 layout(location=1) out vec2 v_tex_coords;
 layout(location=2) out vec3 v_normal;
+layout(location=3) out vec3 v_tangent;
+layout(location=4) out vec3 v_bitangent;
+
+layout(set=0, binding=4)
+uniform Bones {
+    mat4 u_bones[MAX_BONES];
+};
 
 layout(set=1, binding=0)
 uniform Uniforms {
@@ -19,10 +33,23 @@ uniform Uniforms {
 
 
 void main() {
-    vec4 model_space = a_model_matrix * vec4(a_position, 1.0);
+    // Move the vertex by the weighted transforms of its bones, before placing the instance.
+    mat4 skin_matrix =
+        a_bone_weights.x * u_bones[a_bone_indices.x] +
+        a_bone_weights.y * u_bones[a_bone_indices.y] +
+        a_bone_weights.z * u_bones[a_bone_indices.z] +
+        a_bone_weights.w * u_bones[a_bone_indices.w];
+    mat3 skin_3x3 = mat3(skin_matrix);
+
+    vec4 model_space = a_model_matrix * skin_matrix * vec4(a_position, 1.0);
     v_position = model_space.xyz;
     v_tex_coords = a_tex_coords;
-    v_normal = a_normal_matrix * a_normal;
+    v_normal = a_normal_matrix * skin_3x3 * a_normal;
+
+    // The tangents lie in the surface, so they are transformed by the Model matrix.
+    mat3 model_3x3 = mat3(a_model_matrix);
+    v_tangent = model_3x3 * skin_3x3 * a_tangent;
+    v_bitangent = model_3x3 * skin_3x3 * a_bitangent;
 
     gl_Position = u_view_proj * model_space;
-}
\ No newline at end of file
+}
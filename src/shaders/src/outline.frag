@@ -0,0 +1,14 @@
+#version 450
+
+layout(location=0) out vec4 f_color;
+
+layout(set=1, binding=0)
+uniform Outline {
+    vec4 u_color;
+    float u_width;
+};
+
+
+void main() {
+    f_color = u_color;
+}
@@ -0,0 +1,25 @@
+#version 450
+
+layout(location=0) in vec3 a_position;
+layout(location=2) in vec3 a_normal;
+layout(location=5) in mat4 a_model_matrix;
+
+layout(set=0, binding=0)
+uniform Uniforms {
+    vec3 u_view_position; // unused
+    mat4 u_view_proj;
+};
+
+layout(set=1, binding=0)
+uniform Outline {
+    vec4 u_color;
+    float u_width;
+};
+
+
+void main() {
+    // Push the surface outward along its normals (in model space). Only the back faces of this
+    //   inflated hull are drawn, so it shows as a rim around the Model drawn on top of it.
+    vec3 extruded = a_position + normalize(a_normal) * u_width;
+    gl_Position = u_view_proj * a_model_matrix * vec4(extruded, 1.0);
+}
@@ -0,0 +1,35 @@
+#version 450
+
+layout(location=0) out vec3 v_direction;
+
+layout(set=0, binding=0)
+uniform Uniforms {
+    vec3 u_view_position;
+    mat4 u_view_proj;
+};
+
+// The corners of a cube about the viewer, and the two triangles of each of its faces.
+//   The faces are drawn from the inside, so they aren't culled.
+const vec3 CORNERS[8] = vec3[8](
+    vec3(-1.0, -1.0, -1.0), vec3( 1.0, -1.0, -1.0), vec3( 1.0,  1.0, -1.0), vec3(-1.0,  1.0, -1.0),
+    vec3(-1.0, -1.0,  1.0), vec3( 1.0, -1.0,  1.0), vec3( 1.0,  1.0,  1.0), vec3(-1.0,  1.0,  1.0)
+);
+const int INDICES[36] = int[36](
+    1, 2, 6,  6, 5, 1,  // +X
+    0, 4, 7,  7, 3, 0,  // -X
+    3, 7, 6,  6, 2, 3,  // +Y
+    0, 1, 5,  5, 4, 0,  // -Y
+    4, 5, 6,  6, 7, 4,  // +Z
+    0, 3, 2,  2, 1, 0   // -Z
+);
+
+
+void main() {
+    vec3 corner = CORNERS[INDICES[gl_VertexIndex]];
+    v_direction = corner;
+
+    // The cube is centered on the viewer, so it never moves closer. Setting the depth to w places
+    //   every fragment on the far plane, behind everything else in the scene.
+    vec4 position = u_view_proj * vec4(u_view_position + corner, 1.0);
+    gl_Position = position.xyww;
+}
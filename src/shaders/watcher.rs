@@ -0,0 +1,55 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    time::Duration,
+};
+use notify::{DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Watches a directory of GLSL sources for changes, so the shaders can be reloaded at runtime.
+pub struct ShaderWatcher {
+
+    // The watcher of the directory. Dropping it stops the watching.
+    _watcher: RecommendedWatcher,
+
+    // Receives the (debounced) file system events of the directory.
+    receiver: Receiver<DebouncedEvent>,
+
+    // The directory that is watched.
+    directory: PathBuf,
+}
+
+impl ShaderWatcher {
+
+    /// Start watching a directory of GLSL sources.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The directory holding the GLSL sources, e.g. `src/shaders/src`.
+    pub fn new<P: AsRef<Path>>(directory: P) -> Result<Self, failure::Error> {
+        let directory = directory.as_ref().to_path_buf();
+        let (sender, receiver) = mpsc::channel();
+        // Editors often write a file in several steps, so the events are debounced.
+        let mut watcher: RecommendedWatcher = Watcher::new(sender, Duration::from_millis(200))?;
+        watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+        info!("Watching shaders in {:?}", directory);
+        return Ok(ShaderWatcher { _watcher: watcher, receiver, directory })
+    }
+
+    /// Get the directory that is watched.
+    pub fn get_directory(&self) -> &Path { &self.directory }
+
+    /// Get the file names of the shaders that changed since the last call, without blocking.
+    pub fn changed_files(&self) -> Vec<String> {
+        let mut changed: Vec<String> = self.receiver.try_iter()
+            .filter_map(|event| match event {
+                DebouncedEvent::Create(path) | DebouncedEvent::Write(path) | DebouncedEvent::Rename(_, path) => {
+                    path.file_name().and_then(|name| name.to_str()).map(String::from)
+                }
+                _ => None,
+            })
+            .collect();
+        changed.sort();
+        changed.dedup();
+        return changed
+    }
+}
@@ -0,0 +1,75 @@
+use std::time::{Duration, Instant};
+
+/// The number of frames that the frame time is averaged over.
+pub const FRAME_TIMER_WINDOW: usize = 60;
+
+
+/// Tracks the time between drawn frames, averaged over the last `FRAME_TIMER_WINDOW` frames.
+pub struct FrameTimer {
+
+    // The times between consecutive frames. This is used as a ring buffer.
+    frame_times: [Duration; FRAME_TIMER_WINDOW],
+
+    // The index of the ring buffer that is written next.
+    next_index: usize,
+
+    // The number of frame times in the ring buffer.
+    count: usize,
+
+    // The sum of the frame times in the ring buffer.
+    total: Duration,
+
+    // The time at which the last frame was drawn.
+    last_frame: Option<Instant>,
+}
+
+impl FrameTimer {
+
+    /// Create a new FrameTimer object with no recorded frames.
+    pub fn new() -> Self {
+        return FrameTimer {
+            frame_times: [Duration::from_secs(0); FRAME_TIMER_WINDOW],
+            next_index: 0,
+            count: 0,
+            total: Duration::from_secs(0),
+            last_frame: None,
+        }
+    }
+
+    /// Record that a frame is drawn.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The time at which the frame is drawn.
+    pub fn tick(&mut self, now: Instant) {
+        if let Some(last_frame) = self.last_frame {
+            let frame_time = now - last_frame;
+            if self.count == FRAME_TIMER_WINDOW {
+                self.total -= self.frame_times[self.next_index];
+            } else {
+                self.count += 1;
+            }
+            self.frame_times[self.next_index] = frame_time;
+            self.total += frame_time;
+            self.next_index = (self.next_index + 1) % FRAME_TIMER_WINDOW;
+        }
+        self.last_frame = Some(now);
+    }
+
+    /// The average time between the recently drawn frames. Zero if fewer than two frames were drawn.
+    pub fn average_frame_time(&self) -> Duration {
+        if self.count == 0 {
+            return Duration::from_secs(0)
+        }
+        return self.total / self.count as u32
+    }
+
+    /// The number of frames per second, based on the average frame time. Zero if unknown.
+    pub fn fps(&self) -> f32 {
+        let average = self.average_frame_time().as_secs_f32();
+        if average <= 0.0 {
+            return 0.0
+        }
+        return 1.0 / average
+    }
+}
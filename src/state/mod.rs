@@ -1,7 +1,18 @@
+mod frame_timer;
+mod outline;
 mod renderer;
+mod scene;
+mod skybox;
+mod staging;
 mod state;
 mod state_core;
 
-pub use renderer::Renderer;
-pub use state::State;
-pub use state_core::StateCore;
+pub use frame_timer::{FrameTimer, FRAME_TIMER_WINDOW};
+pub use outline::OUTLINE_WIDTH;
+pub use renderer::{ModelId, RenderPipelines, Renderer};
+pub use scene::SceneBuilder;
+pub use state::{
+    ShadingMode, State, DEFAULT_BACKGROUND_COLOR, DEFAULT_GROUND_GRID_COLOR, JOINT_JOG_STEP, SELECTED_JOINT_COLOR,
+};
+pub(crate) use staging::StagingBuffers;
+pub use state_core::{StateCore, StateCoreConfig, DEFAULT_SWAP_CHAIN_FORMATS};
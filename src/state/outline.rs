@@ -0,0 +1,92 @@
+use cgmath::Vector3;
+use wgpu::Device;
+
+/// The width of an outline, relative to the radius of the sphere bounding the Model.
+pub const OUTLINE_WIDTH: f32 = 0.03;
+
+/// Create the layout of the bind group holding the color and width of an outline (see `outline.vert`).
+pub(super) fn create_outline_bind_group_layout(device: &Device) -> wgpu::BindGroupLayout {
+    return device.create_bind_group_layout(
+        &wgpu::BindGroupLayoutDescriptor {
+            bindings: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStage::VERTEX | wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+                },
+            ],
+            label: Some("Outline Bind Group Layout"),
+        }
+    )
+}
+
+/// The flat colored rim drawn around a selected Model.
+///   The Model is drawn a second time, inflated along its normals with only its back faces visible.
+pub(super) struct Outline {
+
+    // The RGB value for the color of the outline.
+    color: Vector3<f32>,
+
+    // The Buffer used to send data to the GPU. This is kept alive for the bind group.
+    _buffer: wgpu::Buffer,
+
+    // The Bind Group used for rendering.
+    pub bind_group: wgpu::BindGroup,
+}
+
+impl Outline {
+
+    /// Create a new Outline object.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - The connection to the graphics device. Used to create the rendering resources.
+    /// * `layout` - The `wgpu::BindGroupLayout` object created by `create_outline_bind_group_layout`.
+    /// * `color`  - The RGB value for the color of the outline.
+    /// * `width`  - The width of the outline, in model space.
+    pub fn new(device: &Device, layout: &wgpu::BindGroupLayout, color: Vector3<f32>, width: f32) -> Self {
+        let outline_raw = OutlineRaw { color: [color.x, color.y, color.z, 1.0], width, _padding: [0.0; 3] };
+        let outline_raw_size = std::mem::size_of_val(&outline_raw) as wgpu::BufferAddress;
+        let buffer = device.create_buffer_with_data(
+            bytemuck::cast_slice(&[outline_raw]),
+            wgpu::BufferUsage::UNIFORM,
+        );
+        let bind_group = device.create_bind_group(
+            &wgpu::BindGroupDescriptor {
+                layout,
+                bindings: &[
+                    wgpu::Binding {
+                        binding: 0,
+                        resource: wgpu::BindingResource::Buffer {
+                            buffer: &buffer,
+                            range: 0..outline_raw_size,
+                        },
+                    },
+                ],
+                label: Some("Outline Bind Group"),
+            }
+        );
+        return Outline { color, _buffer: buffer, bind_group }
+    }
+
+    /// Get the RGB value for the color of the outline.
+    pub fn get_color(&self) -> Vector3<f32> { self.color }
+}
+
+/// The raw data of an Outline that is sent to the GPU.
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct OutlineRaw {
+
+    // The RGBA value for the color of the outline.
+    color: [f32; 4],
+
+    // The width of the outline, in model space.
+    width: f32,
+
+    // Uniform blocks are padded to a multiple of 16 bytes.
+    _padding: [f32; 3],
+}
+
+unsafe impl bytemuck::Pod for OutlineRaw {}
+unsafe impl bytemuck::Zeroable for OutlineRaw {}
@@ -1,21 +1,83 @@
+use std::collections::HashMap;
+use cgmath::Point3;
 use wgpu::{RenderPass, RenderPipeline};
 use crate::{
     light::Light,
-    model::Model,
+    model::{DrawMode, Model, Renderable},
     uniforms::Uniforms,
 };
+use super::{outline::Outline, StateCore};
+
+/// A variant of a RenderPipeline for each index format that a Mesh may use.
+///   The index format is baked into a RenderPipeline, so each Mesh is drawn with the matching variant.
+pub struct RenderPipelines {
+
+    // The variant for Meshes with 16-bit indices.
+    pub uint16: RenderPipeline,
+
+    // The variant for Meshes with 32-bit indices.
+    pub uint32: RenderPipeline,
+}
+
+impl RenderPipelines {
+
+    /// Get the variant for Meshes with the given index format.
+    pub fn get(&self, index_format: wgpu::IndexFormat) -> &RenderPipeline {
+        return match index_format {
+            wgpu::IndexFormat::Uint16 => &self.uint16,
+            wgpu::IndexFormat::Uint32 => &self.uint32,
+        }
+    }
+}
+
+/// A handle to a Model in a Renderer.
+///   Handles are never reused, so a handle stays valid (and keeps referring to the same Model)
+///   when other Models are added or removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ModelId(u64);
 
 /// An object used to render models to the screen.
 pub struct Renderer {
 
     // The Models to be rendered.
-    pub models: Vec<Model>,
+    models: Vec<Model>,
+
+    // The handles of the Models, in the same order as the Models.
+    ids: Vec<ModelId>,
+
+    // The handle given to the next Model (or Renderable) that is added.
+    next_id: u64,
 
-    // The RenderPipeline object used to sent data to the GPU.
-    render_pipeline: wgpu::RenderPipeline,
+    // The custom objects to be rendered after the Models, with their handles (see `add_renderable`).
+    renderables: Vec<(ModelId, Box<dyn Renderable>)>,
+
+    // The RenderPipeline objects used to sent data to the GPU.
+    render_pipeline: RenderPipelines,
+
+    // The RenderPipeline objects with the `LineList` topology used in wireframe mode.
+    wireframe_pipeline: Option<RenderPipelines>,
 
     // Whether the models are visibile, i.e. whether they should be rendered.
+    //   Individual Models can also be hidden through `Model::visible`.
     pub visible: bool,
+
+    // Whether the edges of the models are drawn instead of their faces.
+    //   This has no effect unless a wireframe pipeline has been set.
+    pub wireframe: bool,
+
+    // Whether the Materials of the Meshes are bound (to set 0) when drawing.
+    //   Otherwise only the uniforms and light are bound, to sets 0 and 1.
+    textured: bool,
+
+    // The RenderPipeline objects used to draw the outlines of selected Models (see `outline.vert`).
+    outline_pipeline: Option<RenderPipelines>,
+
+    // The outlines of the selected Models.
+    outlines: HashMap<ModelId, Outline>,
+
+    // The RenderPipeline objects which alpha blend the transparent Meshes (see `render_transparent`).
+    //   If None, the transparent Meshes are drawn with the opaque ones.
+    transparent_pipeline: Option<RenderPipelines>,
 }
 
 impl Renderer {
@@ -27,12 +89,151 @@ impl Renderer {
     /// # Arguments
     ///
     /// * `models`          - The Models to be rendered. 
-    /// * `render_pipeline` - The RenderPipeline object used to sent data to the GPU.
-    pub fn new(models: Vec<Model>, render_pipeline: RenderPipeline) -> Self {
-        return Renderer { models, render_pipeline, visible: true }
+    /// * `render_pipeline` - The RenderPipeline objects used to sent data to the GPU.
+    pub fn new(models: Vec<Model>, render_pipeline: RenderPipelines) -> Self {
+        let ids = (0..models.len() as u64).map(ModelId).collect();
+        let next_id = models.len() as u64;
+        return Renderer {
+            models,
+            ids,
+            next_id,
+            renderables: Vec::new(),
+            render_pipeline,
+            wireframe_pipeline: None,
+            visible: true,
+            wireframe: false,
+            textured: true,
+            outline_pipeline: None,
+            outlines: HashMap::new(),
+            transparent_pipeline: None,
+        }
+    }
+
+    /// Create a new Renderer object that draws its Models without their Materials (see `DrawLight`).
+    ///   The RenderPipeline objects must be created with only the uniforms and light bind group layouts.
+    ///
+    /// # Arguments
+    ///
+    /// * `models`          - The Models to be rendered.
+    /// * `render_pipeline` - The RenderPipeline objects used to sent data to the GPU.
+    pub fn without_materials(models: Vec<Model>, render_pipeline: RenderPipelines) -> Self {
+        let mut renderer = Self::new(models, render_pipeline);
+        renderer.textured = false;
+        return renderer
+    }
+
+    /// Add a Model to be rendered.
+    ///
+    /// # Returns
+    ///
+    /// The handle of the Model, used to access or remove it later.
+    pub fn add_model(&mut self, model: Model) -> ModelId {
+        let id = ModelId(self.next_id);
+        self.next_id += 1;
+        self.models.push(model);
+        self.ids.push(id);
+        return id
+    }
+
+    /// Remove a Model, dropping its GPU resources.
+    ///
+    /// # Returns
+    ///
+    /// The removed Model, or None if there is no Model with the handle.
+    pub fn remove_model(&mut self, id: ModelId) -> Option<Model> {
+        let index = self.ids.iter().position(|model_id| *model_id == id)?;
+        self.ids.remove(index);
+        self.outlines.remove(&id);
+        return Some(self.models.remove(index))
+    }
+
+    /// Add a custom object to be rendered after the Models, in each of the DrawModes of the Renderer.
+    ///   Renderables aren't culled, picked, outlined or sorted by distance.
+    ///
+    /// # Returns
+    ///
+    /// The handle of the Renderable, used to remove it later. Handles are shared with the Models.
+    pub fn add_renderable(&mut self, renderable: Box<dyn Renderable>) -> ModelId {
+        let id = ModelId(self.next_id);
+        self.next_id += 1;
+        self.renderables.push((id, renderable));
+        return id
+    }
+
+    /// Remove a custom object, dropping it.
+    ///
+    /// # Returns
+    ///
+    /// The removed Renderable, or None if there is no Renderable with the handle.
+    pub fn remove_renderable(&mut self, id: ModelId) -> Option<Box<dyn Renderable>> {
+        let index = self.renderables.iter().position(|(renderable_id, _)| *renderable_id == id)?;
+        return Some(self.renderables.remove(index).1)
+    }
+
+    /// Remove all Models and Renderables, dropping their GPU resources.
+    pub fn clear_models(&mut self) {
+        self.models.clear();
+        self.ids.clear();
+        self.outlines.clear();
+        self.renderables.clear();
+    }
+
+    /// Get a reference to a Model, or None if there is no Model with the handle.
+    pub fn get_model(&self, id: ModelId) -> Option<&Model> {
+        let index = self.ids.iter().position(|model_id| *model_id == id)?;
+        return Some(&self.models[index])
     }
 
-    /// Render the Models.
+    /// Get a mutable reference to a Model, or None if there is no Model with the handle.
+    pub fn get_model_mut(&mut self, id: ModelId) -> Option<&mut Model> {
+        let index = self.ids.iter().position(|model_id| *model_id == id)?;
+        return Some(&mut self.models[index])
+    }
+
+    /// Get the Models to be rendered, in the order they were added.
+    pub fn get_models(&self) -> &[Model] { &self.models }
+
+    /// Get mutable references to the Models to be rendered, in the order they were added.
+    pub fn get_models_mut(&mut self) -> &mut [Model] { &mut self.models }
+
+    /// Get the handles of the Models, in the same order as `get_models`.
+    pub fn get_model_ids(&self) -> &[ModelId] { &self.ids }
+
+    /// Replace the RenderPipeline objects, e.g. after the sample count changed.
+    pub fn set_render_pipeline(&mut self, render_pipeline: RenderPipelines) {
+        self.render_pipeline = render_pipeline;
+    }
+
+    /// Set the RenderPipeline objects used in wireframe mode. These must use the `LineList` topology.
+    pub fn set_wireframe_pipeline(&mut self, wireframe_pipeline: RenderPipelines) {
+        self.wireframe_pipeline = Some(wireframe_pipeline);
+    }
+
+    /// Set the RenderPipeline objects used to draw outlines. These must cull front faces,
+    ///   and be created with only the uniforms and outline bind group layouts.
+    pub fn set_outline_pipeline(&mut self, outline_pipeline: RenderPipelines) {
+        self.outline_pipeline = Some(outline_pipeline);
+    }
+
+    /// Set the RenderPipeline objects used to draw the transparent Meshes.
+    ///   These must alpha blend, and must not write to the depth buffer.
+    pub fn set_transparent_pipeline(&mut self, transparent_pipeline: RenderPipelines) {
+        self.transparent_pipeline = Some(transparent_pipeline);
+    }
+
+    /// Set or remove the outline of a Model. Outlines of unknown handles are ignored.
+    pub(super) fn set_outline(&mut self, id: ModelId, outline: Option<Outline>) {
+        match outline {
+            Some(outline) if self.ids.contains(&id) => { self.outlines.insert(id, outline); }
+            _ => { self.outlines.remove(&id); }
+        }
+    }
+
+    /// Get the outline of a Model, if it is selected.
+    pub(super) fn get_outline(&self, id: ModelId) -> Option<&Outline> { self.outlines.get(&id) }
+
+    /// Render the Models, followed by the Renderables. Everything is drawn through `Renderable::draw`,
+    ///   in the DrawMode matching the settings of the Renderer.
     ///
     /// # Arguments
     ///
@@ -40,17 +241,95 @@ impl Renderer {
     /// * `uniforms`    - The Uniforms objects needed by the shader progams.
     /// * `light`       - The Light object needed by the shader programs.
     pub fn render<'r>(
-        &'r mut self,
+        &'r self,
         render_pass: &mut RenderPass<'r>,
         uniforms: &'r Uniforms,
         light: &'r Light,
     ) {
-        if self.visible {
-            render_pass.set_pipeline(&self.render_pipeline);
-            for model in self.models.iter_mut() {
-                use crate::model::DrawModel;
-                render_pass.draw_model(model, &uniforms.bind_group, &light.bind_group);
+        if !self.visible {
+            return
+        }
+        let (pipelines, mode) = match &self.wireframe_pipeline {
+            _ if !self.textured => (&self.render_pipeline, DrawMode::Untextured),
+            Some(wireframe_pipeline) if self.wireframe => (wireframe_pipeline, DrawMode::Wireframe),
+            _ if self.transparent_pipeline.is_some() => (&self.render_pipeline, DrawMode::Opaque),
+            _ => (&self.render_pipeline, DrawMode::Textured),
+        };
+
+        // The outlines are drawn first. Their inflated back faces lie behind the Models,
+        //   so only the rim around each Model passes the depth test. They are drawn like the
+        //   light box, with the outline bind group in place of the light's.
+        if let Some(outline_pipeline) = self.outline_pipeline.as_ref().filter(|_| !self.outlines.is_empty()) {
+            for (model, id) in self.models.iter().zip(self.ids.iter()) {
+                if let Some(outline) = self.outlines.get(id) {
+                    model.draw(render_pass, outline_pipeline, DrawMode::Untextured, &uniforms.bind_group, &outline.bind_group);
+                }
             }
         }
+
+        for renderable in self.all_renderables() {
+            renderable.draw(render_pass, pipelines, mode, &uniforms.bind_group, &light.bind_group);
+        }
+    }
+
+    /// Get the Models followed by the Renderables, as the objects to draw.
+    fn all_renderables(&self) -> impl Iterator<Item = &dyn Renderable> {
+        return self.models.iter()
+            .map(|model| model as &dyn Renderable)
+            .chain(self.renderables.iter().map(|(_, renderable)| renderable.as_ref()))
+    }
+
+    /// Order the instances of the transparent Models back to front from the Camera (see `render_transparent`).
+    ///   This should be run every frame before the instances are culled, as either the Camera or the
+    ///   instances may have moved. The opaque Models are left unsorted, as the depth test orders them.
+    ///
+    /// # Arguments
+    ///
+    /// * `eye`  - The position of the Camera.
+    /// * `core` - Structure for holding the WGPU primitives for running a windowed application.
+    pub fn sort_transparent(&mut self, eye: Point3<f32>, core: &StateCore) {
+        if self.transparent_pipeline.is_none() {
+            return
+        }
+        for model in self.models.iter_mut().filter(|model| model.has_transparency()) {
+            model.sort_instances_back_to_front(eye, core);
+        }
+    }
+
+    /// Render the transparent Meshes of the Models. This must follow the rendering of everything opaque,
+    ///   as the transparent Meshes are blended with what was drawn before them.
+    ///   The Models (and their instances) are drawn back to front, so the nearer ones are blended over the farther ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `render_pass` - An object that connect RenderPipelines to the GPU.
+    /// * `uniforms`    - The Uniforms objects needed by the shader progams.
+    /// * `light`       - The Light object needed by the shader programs.
+    /// * `eye`         - The position of the Camera.
+    pub fn render_transparent<'r>(
+        &'r self,
+        render_pass: &mut RenderPass<'r>,
+        uniforms: &'r Uniforms,
+        light: &'r Light,
+        eye: Point3<f32>,
+    ) {
+        let pipelines = match &self.transparent_pipeline {
+            Some(pipelines) if self.visible && self.textured && !self.wireframe => pipelines,
+            _ => return,
+        };
+
+        // The Models are ordered by their farthest instance, the first of each Model to be drawn.
+        //   The instances within each Model are already ordered (see `sort_transparent`).
+        let mut models: Vec<(&Model, f32)> = self.models.iter()
+            .filter(|model| model.visible && model.has_transparency())
+            .filter_map(|model| Some((model, model.get_farthest_instance_distance2(eye)?)))
+            .collect();
+        models.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+
+        // The Renderables have no position to sort by, so they are drawn after the Models.
+        let renderables = self.renderables.iter().map(|(_, renderable)| renderable.as_ref());
+        for renderable in models.into_iter().map(|(model, _)| model as &dyn Renderable).chain(renderables) {
+            renderable.draw(render_pass, pipelines, DrawMode::Transparent, &uniforms.bind_group, &light.bind_group);
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,124 @@
+use std::path::{Path, PathBuf};
+use cgmath::Vector3;
+use futures::executor::block_on;
+use winit::window::Window;
+use crate::{light::MAX_LIGHTS, model::Instance};
+use super::{State, StateCore, StateCoreConfig};
+
+/// A model file to be loaded into the scene, with the Instances to be drawn.
+pub(super) struct SceneModel {
+
+    // The path to the model file (see `Model::load`), relative to the asset root.
+    pub path: PathBuf,
+
+    // The Instances of the Model. If None, the Model has a single default Instance.
+    pub instances: Option<Vec<Instance>>,
+}
+
+/// A light source to be added to the scene.
+#[derive(Clone, Copy, Debug)]
+pub(super) struct SceneLight {
+
+    // The 3D position of the light source.
+    pub position: Vector3<f32>,
+
+    // The RGB color of the light.
+    pub color: Vector3<f32>,
+}
+
+/// Describes the contents of a scene, which is built into a State.
+///
+/// ```no_run
+/// # fn example(window: &winit::window::Window) -> Result<(), failure::Error> {
+/// use arm_viz::{model::Instance, state::SceneBuilder};
+/// let state = SceneBuilder::new()
+///     .add_model_instances("res/sphere.obj", Instance::grid(10, 10, 3.0))
+///     .add_light((5.0, 10.0, 5.0).into(), (1.0, 1.0, 1.0).into())
+///     .build(window)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct SceneBuilder {
+
+    // The model files to be loaded, in the order they are added.
+    models: Vec<SceneModel>,
+
+    // The light sources. If empty, a white light is placed above the scene.
+    lights: Vec<SceneLight>,
+
+    // The options for creating the graphics device.
+    config: StateCoreConfig,
+}
+
+impl SceneBuilder {
+
+    /// Create an empty scene.
+    pub fn new() -> Self {
+        return SceneBuilder { models: Vec::new(), lights: Vec::new(), config: StateCoreConfig::default() }
+    }
+
+    /// Add a model file with a single default Instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the model file (see `Model::load`).
+    pub fn add_model<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.models.push(SceneModel { path: path.as_ref().to_path_buf(), instances: None });
+        return self
+    }
+
+    /// Add a model file, drawn at each of the given Instances.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`      - The path to the model file (see `Model::load`).
+    /// * `instances` - The Instances of the Model (see `Instance::grid`).
+    pub fn add_model_instances<P: AsRef<Path>>(mut self, path: P, instances: Vec<Instance>) -> Self {
+        self.models.push(SceneModel { path: path.as_ref().to_path_buf(), instances: Some(instances) });
+        return self
+    }
+
+    /// Add a light source. At most `light::MAX_LIGHTS` light sources are supported.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The 3D position of the light source.
+    /// * `color`    - The RGB color of the light.
+    pub fn add_light(mut self, position: Vector3<f32>, color: Vector3<f32>) -> Self {
+        self.lights.push(SceneLight { position, color });
+        return self
+    }
+
+    /// Set the options for creating the graphics device.
+    pub fn with_config(mut self, config: StateCoreConfig) -> Self {
+        self.config = config;
+        return self
+    }
+
+    /// Build the scene into a State, rendering to the window.
+    ///   This blocks the current thread until the graphics device is ready (see `build_async`).
+    ///
+    /// # Returns
+    ///
+    /// An error if the scene has too many lights, if no suitable graphics adapter exists,
+    ///   or if a model file can't be loaded.
+    pub fn build(self, window: &Window) -> Result<State, failure::Error> {
+        return block_on(self.build_async(window))
+    }
+
+    /// Build the scene into a State, rendering to the window, without blocking (see `State::new_async`).
+    pub async fn build_async(self, window: &Window) -> Result<State, failure::Error> {
+        // Validate the scene before the graphics device is requested.
+        if self.lights.len() > MAX_LIGHTS {
+            return Err(failure::format_err!(
+                "The scene has {} lights, but at most {} are supported", self.lights.len(), MAX_LIGHTS
+            ))
+        }
+        let core = StateCore::new(window, &self.config).await?;
+        return State::from_core(core, self.config.asset_root.clone(), self.models, self.lights.first().copied())
+    }
+}
+
+impl Default for SceneBuilder {
+    fn default() -> Self { Self::new() }
+}
@@ -0,0 +1,67 @@
+use wgpu::Device;
+use crate::texture::Texture;
+
+/// Create the layout of the bind group holding the cubemap of a skybox (see `skybox.frag`).
+pub(super) fn create_skybox_bind_group_layout(device: &Device) -> wgpu::BindGroupLayout {
+    return device.create_bind_group_layout(
+        &wgpu::BindGroupLayoutDescriptor {
+            bindings: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::SampledTexture {
+                        multisampled: false,
+                        dimension: wgpu::TextureViewDimension::Cube,
+                        component_type: wgpu::TextureComponentType::Float,
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStage::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler { comparison: false },
+                },
+            ],
+            label: Some("Skybox Bind Group Layout"),
+        }
+    )
+}
+
+/// The environment drawn behind the scene, sampled from a cubemap in the viewing direction.
+pub(super) struct Skybox {
+
+    // The cubemap Texture (see `Texture::load_cubemap`). This is kept alive for the bind group.
+    _texture: Texture,
+
+    // The Bind Group used for rendering.
+    pub bind_group: wgpu::BindGroup,
+}
+
+impl Skybox {
+
+    /// Create a new Skybox object.
+    ///
+    /// # Arguments
+    ///
+    /// * `device`  - The connection to the graphics device. Used to create the rendering resources.
+    /// * `layout`  - The `wgpu::BindGroupLayout` object created by `create_skybox_bind_group_layout`.
+    /// * `texture` - The cubemap Texture.
+    pub fn new(device: &Device, layout: &wgpu::BindGroupLayout, texture: Texture) -> Self {
+        let bind_group = device.create_bind_group(
+            &wgpu::BindGroupDescriptor {
+                layout,
+                bindings: &[
+                    wgpu::Binding {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&texture.view),
+                    },
+                    wgpu::Binding {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&texture.sampler),
+                    },
+                ],
+                label: Some("Skybox Bind Group"),
+            }
+        );
+        return Skybox { _texture: texture, bind_group }
+    }
+}
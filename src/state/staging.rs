@@ -0,0 +1,241 @@
+use std::{future::Future, pin::Pin};
+use futures::FutureExt;
+use super::StateCore;
+
+type WriteMapping = Pin<Box<dyn Future<Output = Result<wgpu::BufferWriteMapping, wgpu::BufferAsyncErr>>>>;
+
+/// What a staging buffer is waiting on before it can be written again.
+enum StagingState<M = WriteMapping> {
+
+    // The buffer was copied from in the frame with the given index (see `StateCore::get_frame_index`),
+    //   which may not have been submitted yet.
+    Copied(u64),
+
+    // The buffer was requested to be mapped for writing, once the GPU is done copying from it.
+    Mapping(M),
+}
+
+/// How the next write is staged.
+#[derive(Debug, PartialEq)]
+enum Staging<W> {
+
+    // Through the staging buffer with the given index, which is mapped for writing.
+    Mapped(usize, W),
+
+    // Through a new staging buffer, which is kept for the next writes.
+    Allocate,
+
+    // Through a new staging buffer that is dropped afterwards, as `MAX_BUFFERS` are already kept.
+    Throwaway,
+}
+
+/// A set of staging buffers that are reused to write data of a fixed size into a GPU buffer.
+///   wgpu 0.5 has no `Queue::write_buffer`, and a staging buffer can only be rewritten once the GPU
+///   is done copying from it, so the buffers are mapped again in the background and take turns.
+pub(crate) struct StagingBuffers {
+
+    // The size (in bytes) of the data written through the staging buffers.
+    size: wgpu::BufferAddress,
+
+    // The staging buffers, with what each of them is waiting on.
+    buffers: Vec<(wgpu::Buffer, StagingState)>,
+
+    // The number of staging buffers that were allocated, including the ones that were dropped.
+    allocation_count: u64,
+}
+
+impl StagingBuffers {
+
+    // The most staging buffers that are kept. If none of them are mapped yet,
+    //   a write falls back to a staging buffer that is dropped afterwards.
+    const MAX_BUFFERS: usize = 3;
+
+    /// Create an empty set of staging buffers. They are allocated by the first writes.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The size (in bytes) of the data written through the staging buffers.
+    pub fn new(size: wgpu::BufferAddress) -> Self {
+        return StagingBuffers { size, buffers: Vec::new(), allocation_count: 0 }
+    }
+
+    /// Get the number of staging buffers that were allocated, e.g. to profile the allocations of the updates.
+    ///   This stops growing once the GPU keeps up with the writes.
+    pub fn get_allocation_count(&self) -> u64 { self.allocation_count }
+
+    /// Write data into the start of a GPU buffer, along with the next frame (see `StateCore::encode`).
+    ///
+    /// # Arguments
+    ///
+    /// * `core`        - Structure for holding the WGPU primitives for running a windowed application.
+    /// * `data`        - The bytes to write, which must be the size of the staging buffers.
+    /// * `destination` - The buffer to write into. It must have the `COPY_DST` usage.
+    pub fn write(&mut self, core: &StateCore, data: &[u8], destination: &wgpu::Buffer) {
+        debug_assert_eq!(data.len() as wgpu::BufferAddress, self.size);
+
+        // Resolve the mappings that were requested by the previous writes.
+        core.device.poll(wgpu::Maintain::Poll);
+
+        let size = self.size;
+        let frame_index = core.get_frame_index();
+        let staging = stage(
+            &mut self.buffers,
+            frame_index,
+            |buffer| -> WriteMapping { Box::pin(buffer.map_write(0, size)) },
+            |mapping| mapping.as_mut().now_or_never(),
+        );
+        let staged = match staging {
+            Staging::Mapped(index, mut mapping) => {
+                // Dropping the mapping unmaps the buffer, so it can be copied from.
+                mapping.as_slice().copy_from_slice(data);
+                drop(mapping);
+                Some(index)
+            }
+            Staging::Allocate => {
+                self.allocation_count += 1;
+                let buffer = core.device.create_buffer_with_data(
+                    data,
+                    wgpu::BufferUsage::MAP_WRITE | wgpu::BufferUsage::COPY_SRC,
+                );
+                self.buffers.push((buffer, StagingState::Copied(frame_index)));
+                Some(self.buffers.len() - 1)
+            }
+            Staging::Throwaway => None,
+        };
+
+        match staged {
+            Some(index) => {
+                let source = &self.buffers[index].0;
+                core.encode(|encoder| encoder.copy_buffer_to_buffer(source, 0, destination, 0, size));
+            }
+            None => {
+                self.allocation_count += 1;
+                let source = core.device.create_buffer_with_data(data, wgpu::BufferUsage::COPY_SRC);
+                core.encode(|encoder| encoder.copy_buffer_to_buffer(&source, 0, destination, 0, size));
+            }
+        }
+    }
+}
+
+/// Decide how the next write is staged, requesting the mapping of the buffers whose copy was submitted.
+///   This is generic over the buffers and their mappings, so that the rotation doesn't need a GPU.
+///
+/// # Arguments
+///
+/// * `buffers`     - The staging buffers, with what each of them is waiting on.
+/// * `frame_index` - The index of the frame that the write is recorded into.
+/// * `map`         - Request the mapping of a buffer.
+/// * `poll`        - Check whether a mapping resolved, without waiting on it.
+///
+/// # Returns
+///
+/// How the write is staged. The buffer that is written through is marked as copied in this frame.
+fn stage<B, M, W, E>(
+    buffers: &mut [(B, StagingState<M>)],
+    frame_index: u64,
+    mut map: impl FnMut(&B) -> M,
+    mut poll: impl FnMut(&mut M) -> Option<Result<W, E>>,
+) -> Staging<W> {
+    let mut staged = None;
+    for (index, (buffer, state)) in buffers.iter_mut().enumerate() {
+        // Once the copy from a buffer was submitted, it can be mapped again.
+        if let StagingState::Copied(copied_frame) = state {
+            if *copied_frame < frame_index {
+                *state = StagingState::Mapping(map(buffer));
+            }
+        }
+        if staged.is_some() {
+            continue
+        }
+
+        let mapped = match state {
+            StagingState::Mapping(mapping) => poll(mapping),
+            StagingState::Copied(_) => None,
+        };
+        match mapped {
+            Some(Ok(mapping)) => {
+                *state = StagingState::Copied(frame_index);
+                staged = Some(Staging::Mapped(index, mapping));
+            }
+            // The mapping is requested again by the next write.
+            Some(Err(_)) => *state = StagingState::Copied(frame_index),
+            None => {}
+        }
+    }
+
+    return match staged {
+        Some(staging) => staging,
+        None if buffers.len() < StagingBuffers::MAX_BUFFERS => Staging::Allocate,
+        None => Staging::Throwaway,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A mapping that resolves after being polled the given number of times, or fails if None.
+    type TestMapping = Option<u32>;
+
+    fn map(_: &()) -> TestMapping { Some(0) }
+
+    fn poll(mapping: &mut TestMapping) -> Option<Result<(), ()>> {
+        return match mapping {
+            Some(0) => Some(Ok(())),
+            Some(polls) => {
+                *polls -= 1;
+                None
+            }
+            None => Some(Err(())),
+        }
+    }
+
+    fn copied_frames(buffers: &[((), StagingState<TestMapping>)]) -> Vec<Option<u64>> {
+        return buffers.iter().map(|(_, state)| match state {
+            StagingState::Copied(frame) => Some(*frame),
+            StagingState::Mapping(_) => None,
+        }).collect()
+    }
+
+    #[test]
+    fn buffers_are_allocated_up_to_the_limit() {
+        let mut buffers: Vec<((), StagingState<TestMapping>)> = Vec::new();
+        for _ in 0..StagingBuffers::MAX_BUFFERS {
+            assert_eq!(stage(&mut buffers, 0, map, poll), Staging::Allocate);
+            buffers.push(((), StagingState::Copied(0)));
+        }
+        // None of the copies were submitted, so the next write falls back to a throwaway buffer.
+        assert_eq!(stage(&mut buffers, 0, map, poll), Staging::Throwaway);
+    }
+
+    #[test]
+    fn buffers_are_reused_once_their_copy_was_submitted() {
+        let mut buffers = vec![((), StagingState::Copied(0)), ((), StagingState::Copied(1))];
+
+        // The first buffer that is mapped is written, and the other one is mapped in the background.
+        assert_eq!(stage(&mut buffers, 2, map, poll), Staging::Mapped(0, ()));
+        assert_eq!(copied_frames(&buffers), [Some(2), None]);
+
+        // The next write of the same frame takes the other buffer.
+        assert_eq!(stage(&mut buffers, 2, map, poll), Staging::Mapped(1, ()));
+        assert_eq!(copied_frames(&buffers), [Some(2), Some(2)]);
+    }
+
+    #[test]
+    fn pending_mappings_are_waited_on() {
+        let mut buffers = vec![((), StagingState::Mapping(Some(1)))];
+        assert_eq!(stage(&mut buffers, 1, map, poll), Staging::Allocate);
+        assert_eq!(stage(&mut buffers, 1, map, poll), Staging::Mapped(0, ()));
+    }
+
+    #[test]
+    fn failed_mappings_are_requested_again() {
+        let mut buffers = vec![((), StagingState::Mapping(None)), ((), StagingState::Copied(0))];
+        assert_eq!(stage(&mut buffers, 1, map, poll), Staging::Mapped(1, ()));
+        assert_eq!(copied_frames(&buffers), [Some(1), Some(1)]);
+
+        // The failed buffer is mapped again once the frame was submitted.
+        assert_eq!(stage(&mut buffers, 2, map, poll), Staging::Mapped(0, ()));
+    }
+}
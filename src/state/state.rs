@@ -1,19 +1,34 @@
+use std::{collections::HashMap, path::{Path, PathBuf}, time::{Duration, Instant}};
+use cgmath::{Point3, Vector3};
 use futures::executor::block_on;
 use wgpu::BindGroupLayoutDescriptor;
 use winit::{
-    event::{ElementState, KeyboardInput, VirtualKeyCode, WindowEvent},
+    event::{ElementState, KeyboardInput, WindowEvent},
     window::Window,
 };
 
 use crate::{
-    camera::{Camera, CameraController, Projection, View},
-    light::Light,
-    model::{Instance, Model},
-    shaders::{ShaderData, LIGHT_SHADER_DATA, MODEL_SHADER_DATA},
+    camera::{Camera, CameraController, CameraPreset, Projection, View},
+    input::{Action, KeyBindings},
+    light::{CircularOrbit, Light},
+    lines::{DrawLines, Lines},
+    model::{Instance, Model, ModelData, Renderable, DEFAULT_GRID_SIZE, DEFAULT_GRID_SPACING},
+    robot::Robot,
+    shaders::{
+        ShaderData, ShaderProgram, AXES_SHADER_DATA, LIGHT_SHADER_DATA, LINE_SHADER_DATA, MODEL_SHADER_DATA,
+        OUTLINE_SHADER_DATA, SKYBOX_SHADER_DATA,
+    },
     texture,
     Uniforms,
 };
-use super::{Renderer, StateCore};
+use super::{
+    outline::{create_outline_bind_group_layout, Outline, OUTLINE_WIDTH},
+    scene::{SceneLight, SceneModel},
+    skybox::{create_skybox_bind_group_layout, Skybox},
+    FrameTimer, ModelId, RenderPipelines, Renderer, SceneBuilder, StateCore, StateCoreConfig,
+};
+#[cfg(feature = "urdf")]
+use crate::robot::{Link, Pose, UrdfRobot};
 
 const TEXTURE_BIND_GROUP_LAYOUT_DESC: BindGroupLayoutDescriptor = 
     BindGroupLayoutDescriptor {
@@ -32,10 +47,68 @@ const TEXTURE_BIND_GROUP_LAYOUT_DESC: BindGroupLayoutDescriptor =
                 visibility: wgpu::ShaderStage::FRAGMENT,
                 ty: wgpu::BindingType::Sampler { comparison: false },
             },
+            // The normal map.
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStage::FRAGMENT,
+                ty: wgpu::BindingType::SampledTexture {
+                    multisampled: false,
+                    dimension: wgpu::TextureViewDimension::D2,
+                    component_type: wgpu::TextureComponentType::Float,
+                },
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 3,
+                visibility: wgpu::ShaderStage::FRAGMENT,
+                ty: wgpu::BindingType::Sampler { comparison: false },
+            },
+            // The bone transforms of the Model (see `Model::set_bone_transforms`).
+            wgpu::BindGroupLayoutEntry {
+                binding: 4,
+                visibility: wgpu::ShaderStage::VERTEX,
+                ty: wgpu::BindingType::UniformBuffer { dynamic: false },
+            },
         ],
         label: Some("Texture Bind Group Layout"),
     };
 
+/// The default color that the frame is cleared to.
+pub const DEFAULT_BACKGROUND_COLOR: wgpu::Color = wgpu::Color { r: 0.1, g: 0.2, b: 0.3, a: 1.0 };
+
+/// The default color of the grid on the ground plane.
+pub const DEFAULT_GROUND_GRID_COLOR: Vector3<f32> = Vector3 { x: 0.5, y: 0.5, z: 0.5 };
+
+/// The angle (in radians) that the selected Joint of the Robot turns by per press of a jog key.
+pub const JOINT_JOG_STEP: f32 = std::f32::consts::PI / 36.0;
+
+/// The color of the outline of the Links moved by the selected Joint of the Robot.
+pub const SELECTED_JOINT_COLOR: Vector3<f32> = Vector3 { x: 1.0, y: 0.6, z: 0.0 };
+
+/// The sample counts supported for multisample anti-aliasing.
+const SUPPORTED_SAMPLE_COUNTS: [u32; 4] = [1, 2, 4, 8];
+
+/// How the Models are shaded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShadingMode {
+
+    // Blinn-Phong lighting with normal mapping.
+    Realistic,
+
+    // Cel shading: the diffuse lighting is quantized into bands, with a lit rim (see `State::set_toon_bands`).
+    Toon,
+}
+
+impl ShadingMode {
+
+    /// Get the shader programs of the Model pipeline for the shading mode.
+    fn shader_program(self) -> ShaderProgram {
+        return match self {
+            ShadingMode::Realistic => ShaderProgram::Model,
+            ShadingMode::Toon => ShaderProgram::Toon,
+        }
+    }
+}
+
 /// The State of the Application.
 pub struct State {
     
@@ -54,6 +127,9 @@ pub struct State {
     // The controller of the Camer object. This processes events to affect the position of the Camera.
     camera_controller: CameraController,
 
+    // The viewpoints of the Camera that were saved, by name.
+    camera_presets: HashMap<String, CameraPreset>,
+
     // The Light object, i.e. the point that eminates light.
     light: Light,
 
@@ -62,15 +138,201 @@ pub struct State {
 
     // The texture object that tells the GPU the relative depth of objects in the scene.
     depth_texture: texture::Texture,
+
+    // The Layout used for the texture BindGroups. Kept to rebuild the render pipelines.
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+
+    // The Bind Group Layout of the color and width of an outline, used to outline selected Models.
+    outline_bind_group_layout: wgpu::BindGroupLayout,
+
+    // The Bind Group Layout of the cubemap of a Skybox.
+    skybox_bind_group_layout: wgpu::BindGroupLayout,
+
+    // The pipeline for the Skybox, which is drawn on the far plane behind the scene.
+    skybox_pipeline: wgpu::RenderPipeline,
+
+    // The environment drawn behind the scene. If None, the frame is only cleared to the background color.
+    skybox: Option<Skybox>,
+
+    // How the Models are shaded, i.e. which shader programs the Model pipeline uses.
+    shading_mode: ShadingMode,
+
+    // The shader programs that were reloaded from disk, replacing the ones compiled into the binary.
+    reloaded_shaders: HashMap<ShaderProgram, ShaderData>,
+
+    // Watches the GLSL sources, so the shaders are reloaded when they change.
+    #[cfg(feature = "hot-reload")]
+    shader_watcher: Option<crate::shaders::ShaderWatcher>,
+
+    // Reads the gamepad that navigates the scene, if enabled.
+    #[cfg(feature = "gamepad")]
+    gamepad: Option<crate::input::GamepadInput>,
+
+    // Reads the joint states that turn the Joints of the Robot, if enabled.
+    #[cfg(feature = "joint-states")]
+    joint_state_input: Option<crate::input::JointStateInput>,
+
+    // The number of samples per pixel used for multisample anti-aliasing (1 disables it).
+    sample_count: u32,
+
+    // The multisampled color target that is resolved into each frame. None if `sample_count` is 1.
+    multisampled_framebuffer: Option<wgpu::TextureView>,
+
+    // Whether the scene is animated, i.e. whether it changes every frame.
+    animating: bool,
+
+    // Whether the scene is frozen. The Camera can still be moved while paused.
+    paused: bool,
+
+    // Whether the scene changed since the last update, i.e. whether a new frame should be drawn.
+    dirty: bool,
+
+    // Whether frames should only be drawn when the scene has changed.
+    idle_throttle: bool,
+
+    // Whether the last update changed the View of the Camera.
+    view_dirty: bool,
+
+    // The maximum number of frames to draw per second. If None, the framerate is unbounded.
+    target_fps: Option<f32>,
+
+    // The time at which the last frame was drawn.
+    last_frame: Instant,
+
+    // The rolling average of the time between drawn frames.
+    frame_timer: FrameTimer,
+
+    // The number of queue submissions made for the last drawn frame (see `get_frame_submit_count`).
+    frame_submit_count: u64,
+
+    // The submit count of the StateCore when the last frame was submitted.
+    last_frame_submit_total: u64,
+
+    // The time at which the scene was last updated.
+    last_update: Instant,
+
+    // Whether instances outside of the view of the Camera are skipped when drawing.
+    frustum_culling: bool,
+
+    // Whether the window is minimized, i.e. whether it was resized to zero.
+    minimized: bool,
+
+    // The color that the frame is cleared to before drawing.
+    background_color: wgpu::Color,
+
+    // The RenderPipeline object used to draw Lines, e.g. the ground grid.
+    line_pipeline: wgpu::RenderPipeline,
+
+    // The grid on the ground plane. None if it is disabled.
+    ground_grid: Option<Lines>,
+
+    // The size and number of divisions of the ground grid, kept to rebuild it when its color changes.
+    ground_grid_shape: (f32, u32),
+
+    // The color of the lines of the ground grid.
+    ground_grid_color: Vector3<f32>,
+
+    // The RenderPipeline object used to draw the coordinate axes on top of the scene.
+    axes_pipeline: wgpu::RenderPipeline,
+
+    // The coordinate axes at the origin.
+    axes: Lines,
+
+    // The color of the fog. If None, the fog takes the background color so the horizon blends in.
+    fog_color: Option<Vector3<f32>>,
+
+    // The density of the fog. Zero disables the fog.
+    fog_density: f32,
+
+    // The directory that relative model paths are resolved against.
+    asset_root: PathBuf,
+
+    // The window title requested since the last call to `take_window_title`.
+    //   The State doesn't own the Window, so the event loop applies it.
+    pending_window_title: Option<String>,
+
+    // The Models loaded from model files, by their (resolved) path. See `add_instanced`.
+    instanced_models: HashMap<PathBuf, ModelId>,
+
+    // The Robot whose Joints move the Models of its Links, if any (see `set_robot`).
+    robot: Option<Robot>,
+
+    // The index of the Joint of the Robot that the jog keys turn, if any (see `select_joint`).
+    selected_joint: Option<usize>,
 }
 
 impl State {
 
-    /// Construct a new State from a `winit::window::Window` object.
-    pub fn new(window: &Window) -> Self {
+    /// Construct a new State from a `winit::window::Window` object, rendering the given models.
+    ///   This blocks the current thread until the graphics device is ready (see `new_async`).
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - The window to render to.
+    /// * `models` - Pairs of the path to a model file (see `Model::load`) and the Instances of that model.
+    ///
+    /// # Returns
+    ///
+    /// An error if no suitable graphics adapter exists (e.g. on a headless machine),
+    ///   or if the models of the scene can't be loaded.
+    pub fn new(window: &Window, models: Vec<(PathBuf, Vec<Instance>)>) -> Result<Self, failure::Error> {
+        return block_on(Self::new_async(window, models))
+    }
+
+    /// Construct the demo scene: a grid of spheres lit by an orbiting light.
+    ///   This blocks the current thread until the graphics device is ready.
+    ///   The sphere is compiled into the binary, so this works from any working directory.
+    pub fn new_demo(window: &Window) -> Result<Self, failure::Error> {
+        let mut state = Self::new(window, Vec::new())?;
+        let sphere = state.add_model_data(ModelData::sphere()?)?;
+        let instances = Instance::grid(DEFAULT_GRID_SIZE, DEFAULT_GRID_SIZE, DEFAULT_GRID_SPACING);
+        state.set_model_instances(sphere, instances);
+        return Ok(state)
+    }
+
+    /// Construct a new State from a `winit::window::Window` object, with options for the graphics device.
+    ///   This blocks the current thread until the graphics device is ready.
+    pub fn with_config(
+        window: &Window,
+        models: Vec<(PathBuf, Vec<Instance>)>,
+        config: &StateCoreConfig,
+    ) -> Result<Self, failure::Error> {
+        return block_on(Self::new_async_with_config(window, models, config))
+    }
+
+    /// Construct a new State from a `winit::window::Window` object without blocking.
+    ///   Only requesting the graphics adapter and device is awaited; the rest of the setup is synchronous.
+    ///   The returned Future does nothing until it is driven by the caller's executor.
+    pub async fn new_async(window: &Window, models: Vec<(PathBuf, Vec<Instance>)>) -> Result<Self, failure::Error> {
+        return Self::new_async_with_config(window, models, &StateCoreConfig::default()).await
+    }
+
+    /// Construct a new State from a `winit::window::Window` object without blocking,
+    ///   with options for the graphics device (see `new_async`).
+    pub async fn new_async_with_config(
+        window: &Window,
+        models: Vec<(PathBuf, Vec<Instance>)>,
+        config: &StateCoreConfig,
+    ) -> Result<Self, failure::Error> {
+        let scene = models.into_iter()
+            .fold(SceneBuilder::new(), |scene, (path, instances)| scene.add_model_instances(path, instances));
+        return scene.with_config(config.clone()).build_async(window).await
+    }
 
-        // The core of the State object.
-        let core: StateCore = block_on(StateCore::new(window));
+    /// Create the rendering resources of the scene on top of the core WGPU primitives.
+    ///
+    /// # Arguments
+    ///
+    /// * `core`       - The core WGPU primitives.
+    /// * `asset_root` - The directory that relative model paths are resolved against.
+    /// * `models`     - The model files to be loaded, with their Instances.
+    /// * `light`      - The light source. If None, a white light is placed above the scene.
+    pub(super) fn from_core(
+        core: StateCore,
+        asset_root: PathBuf,
+        models: Vec<SceneModel>,
+        light: Option<SceneLight>,
+    ) -> Result<Self, failure::Error> {
 
         // The Camera and Camera Controller objects.
         let camera = Camera::new(
@@ -80,11 +342,22 @@ impl State {
         let camera_controller = CameraController::new();
 
         // Create the Light object. (This is point from which light shines, not the physical light box).
-        let light = Light::new_white(&core.device, (5.0, 10.0, 5.0).into());
+        //    The light orbits about the vertical axis while the scene is animated.
+        let mut light = match light {
+            Some(light) => Light::new(&core.device, light.position, light.color),
+            None => Light::new_white(&core.device, (5.0, 10.0, 5.0).into()),
+        };
+        light.set_animation(Some(Box::new(CircularOrbit::around_y(light.get_position(), cgmath::Deg(60.0)))));
 
         // Texture Bind Group Layout.
         let texture_bind_group_layout = 
             core.device.create_bind_group_layout(&TEXTURE_BIND_GROUP_LAYOUT_DESC);
+
+        // The Bind Group Layout of the color and width of an outline.
+        let outline_bind_group_layout = create_outline_bind_group_layout(&core.device);
+
+        // The Bind Group Layout of the cubemap of a Skybox.
+        let skybox_bind_group_layout = create_skybox_bind_group_layout(&core.device);
         
         // Uniforms.
         let uniforms = Uniforms::new(
@@ -94,15 +367,22 @@ impl State {
         );
 
         // Render Pipelines.
+        let mut scene_paths = Vec::with_capacity(models.len());
         let model_renderer = {
-            // Create the model object and submit them to the GPU.
-            let (mut obj_model, cmds) = 
-                Model::load(&core.device, &texture_bind_group_layout, "src/res/sphere.obj").unwrap();
-            core.submit(&cmds);
-            
-            // Construct the instances of these objects (if they need to be replicated).
-            let instances = create_tutorial_instances();
-            obj_model.set_instances(instances, &core.device);
+            // Create the model objects and submit them to the GPU.
+            let mut obj_models = Vec::with_capacity(models.len());
+            for scene_model in models {
+                let path = asset_root.join(&scene_model.path);
+                let (mut obj_model, cmds) = Model::load(&core.device, &texture_bind_group_layout, &path)?;
+                core.submit(&cmds);
+                scene_paths.push(path);
+
+                // Construct the instances of these objects (if they need to be replicated).
+                if let Some(instances) = scene_model.instances {
+                    obj_model.set_instances(instances, &core.device);
+                }
+                obj_models.push(obj_model);
+            }
             
             // These BindGroupLayouts define the structure of the data that will be sent to GPU
             //    and used during the shader programs.
@@ -115,16 +395,46 @@ impl State {
             // Construct the render pipeline (the pipeline for sending data to the GPU and executing
             //   the shader programs).
             let render_pipeline = create_render_pipeline(
-                &core, bind_group_layouts, &MODEL_SHADER_DATA
+                &core, bind_group_layouts, &MODEL_SHADER_DATA, 1, wgpu::PrimitiveTopology::TriangleList
+            );
+            let wireframe_pipeline = create_render_pipeline(
+                &core, bind_group_layouts, &MODEL_SHADER_DATA, 1, wgpu::PrimitiveTopology::LineList
+            );
+
+            // The outlines of selected Models are drawn with only their back faces (see `outline.vert`).
+            let outline_pipeline = create_render_pipeline_with_state(
+                &core,
+                &[&uniforms.bind_group_layout, &outline_bind_group_layout],
+                &OUTLINE_SHADER_DATA,
+                1,
+                wgpu::PrimitiveTopology::TriangleList,
+                wgpu::CullMode::Front,
+                false,
             );
 
-            Renderer::new(vec![obj_model], render_pipeline)
+            // Transparent Meshes are alpha blended after everything opaque is drawn.
+            let transparent_pipeline = create_render_pipeline_with_state(
+                &core,
+                bind_group_layouts,
+                &MODEL_SHADER_DATA,
+                1,
+                wgpu::PrimitiveTopology::TriangleList,
+                wgpu::CullMode::Back,
+                true,
+            );
+
+            let mut renderer = Renderer::new(obj_models, render_pipeline);
+            renderer.set_wireframe_pipeline(wireframe_pipeline);
+            renderer.set_outline_pipeline(outline_pipeline);
+            renderer.set_transparent_pipeline(transparent_pipeline);
+            renderer
         };
+        let instanced_models: HashMap<PathBuf, ModelId> =
+            scene_paths.into_iter().zip(model_renderer.get_model_ids().iter().copied()).collect();
 
         let light_renderer = {
             // Create the model object for the light box and submit it to the GPU.
-            let (mut light_model, cmds) = 
-                Model::load(&core.device, &texture_bind_group_layout, "src/res/light.obj").unwrap();
+            let (mut light_model, cmds) = Model::new_light(&core.device, &texture_bind_group_layout)?;
             core.submit(&cmds);
 
             // Move the instance of the light box to the position of the Light object.
@@ -132,11 +442,8 @@ impl State {
             light_model.set_instances(vec![light_instance], &core.device);
 
             // These BindGroupLayouts define the structure of the data that will be sent to GPU
-            //    and used during the shader programs.
-            // TODO: The texture bind group layout isn't necessary here.
-            //       The rendering logic needs to be updated to make this more efficient.
+            //    and used during the shader programs. The light box isn't textured.
             let bind_group_layouts = &[
-                &texture_bind_group_layout,
                 &uniforms.bind_group_layout,
                 &light.bind_group_layout,
             ];
@@ -144,219 +451,1572 @@ impl State {
             // Construct the render pipeline (the pipeline for sending data to the GPU and executing
             //   the shader programs).
             let render_pipeline = create_render_pipeline(
-                &core, bind_group_layouts, &LIGHT_SHADER_DATA
+                &core, bind_group_layouts, &LIGHT_SHADER_DATA, 1, wgpu::PrimitiveTopology::TriangleList
             );
-            let mut renderer = Renderer::new(vec![light_model], render_pipeline);
+            let mut renderer = Renderer::without_materials(vec![light_model], render_pipeline);
             renderer.visible = false; // Make the light box invisible by default.
             renderer
             
         };
 
+        // The pipeline for Lines, which are unlit and only need the Uniforms.
+        let line_pipeline = create_line_pipeline(&core, &[&uniforms.bind_group_layout], &LINE_SHADER_DATA, 1, true);
+
+        // The coordinate axes, drawn on top of the scene at a constant size on screen (see `axes.vert`).
+        //    They are hidden by default.
+        let axes_pipeline = create_line_pipeline(&core, &[&uniforms.bind_group_layout], &AXES_SHADER_DATA, 1, false);
+        let mut axes = Lines::axes(&core.device, 1.0);
+        axes.visible = false;
+
+        // The pipeline for the Skybox, which is only drawn once one is set.
+        let skybox_pipeline = create_skybox_pipeline(
+            &core, &[&uniforms.bind_group_layout, &skybox_bind_group_layout], &SKYBOX_SHADER_DATA, 1
+        );
+
         // Depth Texture.
         let depth_texture = texture::Texture::create_depth_texture(
             &core.device, 
             &core.swap_chain_desc, 
+            1,
             "depth_texture",
         );
 
-        return Self {
+        return Ok(Self {
             core,
             model_renderer,
             light_renderer,
             camera,
             camera_controller,
+            camera_presets: HashMap::new(),
             light,
             uniforms,
             depth_texture,
-        }
+            animating: true,
+            paused: false,
+            dirty: true,
+            idle_throttle: false,
+            view_dirty: true,
+            target_fps: None,
+            last_frame: Instant::now(),
+            frame_timer: FrameTimer::new(),
+            frame_submit_count: 0,
+            last_frame_submit_total: 0,
+            last_update: Instant::now(),
+            frustum_culling: true,
+            minimized: false,
+            background_color: DEFAULT_BACKGROUND_COLOR,
+            texture_bind_group_layout,
+            outline_bind_group_layout,
+            skybox_bind_group_layout,
+            skybox_pipeline,
+            skybox: None,
+            shading_mode: ShadingMode::Realistic,
+            reloaded_shaders: HashMap::new(),
+            #[cfg(feature = "hot-reload")]
+            shader_watcher: None,
+            #[cfg(feature = "gamepad")]
+            gamepad: None,
+            #[cfg(feature = "joint-states")]
+            joint_state_input: None,
+            sample_count: 1,
+            multisampled_framebuffer: None,
+            line_pipeline,
+            ground_grid: None,
+            ground_grid_shape: (0.0, 0),
+            ground_grid_color: DEFAULT_GROUND_GRID_COLOR,
+            axes_pipeline,
+            axes,
+            fog_color: None,
+            fog_density: 0.0,
+            asset_root,
+            pending_window_title: None,
+            instanced_models,
+            robot: None,
+            selected_joint: None,
+        })
     }
 
-    /// Handle a resizing of the window.
-    pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
-        self.core.resize(new_size);
-        self.depth_texture = texture::Texture::create_depth_texture(
-            &self.core.device, &self.core.swap_chain_desc, "depth_texture"
-        );
+    /// Set whether frames should only be drawn when the scene has changed.
+    ///
+    /// When enabled, `needs_redraw` only reports `true` after input, a resize or
+    ///   while the scene is animated, so the event loop can fall back to waiting for events.
+    pub fn set_idle_throttle(&mut self, enabled: bool) { self.idle_throttle = enabled; }
+
+    /// Set whether the scene is animated (i.e. whether the Light orbits the scene).
+    pub fn set_animating(&mut self, animating: bool) {
+        self.animating = animating;
+        self.dirty = true;
     }
 
-    /// Handle the Window events.
+    /// Pause or resume the scene. While paused, the animations are frozen but the Camera can still be moved.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.dirty = true;
+    }
+
+    /// Whether the scene is paused.
+    pub fn is_paused(&self) -> bool { self.paused }
+
+    /// Whether a new frame should be drawn.
+    pub fn needs_redraw(&self) -> bool {
+        if self.minimized {
+            return false
+        }
+        // Gamepads don't produce window events, so the loop must keep running to poll them.
+        #[cfg(feature = "gamepad")]
+        {
+            if self.gamepad.is_some() {
+                return true
+            }
+        }
+        // Neither do the joint states.
+        #[cfg(feature = "joint-states")]
+        {
+            if self.joint_state_input.is_some() {
+                return true
+            }
+        }
+        return !self.idle_throttle || (self.animating && !self.paused) || self.dirty
+    }
+
+    /// Set the maximum number of frames to draw per second.
     ///
-    /// This includes processing events on the camera controller (see for more information),
-    /// And processing the following events here:
-    ///   * If the `L` key is pressed, toggle the visibility of the light box.
+    /// # Arguments
+    ///
+    /// * `fps` - The target framerate. If None (or not positive), the framerate is unbounded.
+    pub fn set_target_fps(&mut self, fps: Option<f32>) {
+        self.target_fps = fps.filter(|fps| *fps > 0.0);
+    }
+
+    /// Get the time at which the next frame should be drawn to hit the target framerate.
     ///
     /// # Returns
-    /// 
-    /// Boolean of whether an event was handled.
-    pub fn input(&mut self, event: &WindowEvent) -> bool {
-        let handled_event = self.camera_controller.process_events(event);
-        match event {
-            WindowEvent::KeyboardInput {
-                input: KeyboardInput { state, virtual_keycode: Some(keycode), .. },
-                ..
-            } => {
-                let is_pressed = *state == ElementState::Pressed;
-                match keycode {
-                    VirtualKeyCode::L => { self.light_renderer.visible ^= is_pressed },
-                    _ => return handled_event,
-                }
-            },
-            _ => return handled_event,
+    ///
+    /// The time of the next frame, or None if a frame can be drawn immediately.
+    pub fn next_frame_time(&self) -> Option<Instant> {
+        let fps = self.target_fps?;
+        let next_frame = self.last_frame + Duration::from_secs_f32(1.0 / fps);
+        if next_frame > Instant::now() { Some(next_frame) } else { None }
+    }
+
+    /// Set whether instances outside of the view of the Camera are skipped when drawing.
+    pub fn set_frustum_culling(&mut self, enabled: bool) {
+        self.frustum_culling = enabled;
+        if !enabled {
+            self.model_renderer.get_models_mut().iter_mut().for_each(Model::clear_culling);
         }
-        return true
+        self.dirty = true;
     }
 
-    /// Make updates to the scene and data being sent to the GPU.
-    pub fn update(&mut self) {
+    /// Move the Camera so that every Model in the scene is visible.
+    pub fn frame_all(&mut self) {
+        let bounds = self.model_renderer.get_models().iter()
+            .filter_map(Model::get_world_bounds)
+            .fold(None, |bounds: Option<(Vector3<f32>, Vector3<f32>)>, (min, max)| {
+                Some(match bounds {
+                    None => (min, max),
+                    Some((all_min, all_max)) => (
+                        Vector3::new(all_min.x.min(min.x), all_min.y.min(min.y), all_min.z.min(min.z)),
+                        Vector3::new(all_max.x.max(max.x), all_max.y.max(max.y), all_max.z.max(max.z)),
+                    ),
+                })
+            });
+        if let Some((min, max)) = bounds {
+            self.camera.frame_bounds(min, max);
+            self.camera_controller.stop();
+            self.uniforms.update_from_camera(&self.camera, &self.core);
+            self.dirty = true;
+        }
+    }
+
+    /// Get the View of the Camera.
+    pub fn get_camera_view(&self) -> View { *self.camera.get_view() }
+
+    /// Set the View of the Camera, e.g. to drive it from an external controller.
+    ///   Any Camera movement in progress is stopped.
+    pub fn set_camera_view(&mut self, view: View) {
+        self.camera.set_view(view);
+        self.camera_controller.stop();
+        self.uniforms.update_from_camera(&self.camera, &self.core);
+        self.dirty = true;
+    }
+
+    /// Get the position of the Camera.
+    pub fn get_camera_eye(&self) -> Point3<f32> { self.camera.get_view().get_position() }
+
+    /// Move the Camera, keeping it looking at the same target.
+    ///
+    /// # Arguments
+    ///
+    /// * `eye` - The new position of the Camera. Ignored if it coincides with the target.
+    pub fn set_camera_eye(&mut self, eye: Point3<f32>) {
+        let mut view = *self.camera.get_view();
+        view.set_position(eye);
+        self.set_camera_view(view);
+    }
+
+    /// Get the target that the Camera is looking at.
+    pub fn get_camera_target(&self) -> Point3<f32> { self.camera.get_view().get_target() }
 
-        // Move the camera in a circular motion.
-        let new_position = {
-            use cgmath::{Deg, Quaternion};
-            use cgmath::Rotation3;
+    /// Point the Camera at a new target, keeping it in place.
+    ///
+    /// # Arguments
+    ///
+    /// * `target` - The new target of the Camera. Ignored if it coincides with the position of the Camera.
+    pub fn set_camera_target(&mut self, target: Point3<f32>) {
+        let mut view = *self.camera.get_view();
+        view.set_target(target);
+        self.set_camera_view(view);
+    }
+
+    /// Save the current viewpoint of the Camera to a named slot, replacing any preset already in the slot.
+    pub fn save_camera_preset(&mut self, name: &str) {
+        self.camera_presets.insert(name.to_string(), self.camera.to_preset());
+    }
 
-            let old_position = self.light.get_position();
-            Quaternion::from_axis_angle((0.0, 1.0, 0.0).into(), Deg(1.0)) * old_position
+    /// Move the Camera to the viewpoint saved in a named slot.
+    ///
+    /// # Returns
+    ///
+    /// Whether a preset was saved in the slot.
+    pub fn recall_camera_preset(&mut self, name: &str) -> bool {
+        let preset = match self.camera_presets.get(name) {
+            Some(preset) => *preset,
+            None => return false,
         };
-        self.light.set_position(new_position, &self.core);
-        let light_instance = Instance::from_position(self.light.get_position());
-        self.light_renderer.models[0].set_instances(vec![light_instance], &self.core.device);
+        self.camera.apply_preset(&preset);
+        self.camera_controller.stop();
+        self.uniforms.update_from_camera(&self.camera, &self.core);
+        self.dirty = true;
+        return true
+    }
 
-        // Make updates to the camera and uniform objects if necessary.
-        if self.camera_controller.update_camera(&mut self.camera) {
-            self.uniforms.update_from_camera(&self.camera, &self.core);
-        }
+    /// Remove the preset saved in a named slot, returning it if there was one.
+    pub fn remove_camera_preset(&mut self, name: &str) -> Option<CameraPreset> {
+        return self.camera_presets.remove(name)
     }
 
-    /// Render the scene.
-    pub fn render(&mut self) {
-        let frame = 
-            self.core.swap_chain.get_next_texture().expect("Timeout getting texture");
+    /// Get the saved viewpoints of the Camera, by name.
+    pub fn get_camera_presets(&self) -> &HashMap<String, CameraPreset> { &self.camera_presets }
 
-        let mut encoder = self.core.device.create_command_encoder(
-            &wgpu::CommandEncoderDescriptor { label: Some("Render Encoder") }
-        );
-        
-        let mut render_pass = encoder.begin_render_pass(
-            &wgpu::RenderPassDescriptor {
-                color_attachments: &[
-                    wgpu::RenderPassColorAttachmentDescriptor {
-                        attachment: &frame.view,
-                        resolve_target: None,
-                        load_op: wgpu::LoadOp::Clear,
-                        store_op: wgpu::StoreOp::Store,
-                        clear_color: wgpu::Color { r: 0.1, g: 0.2, b: 0.3, a: 1.0 },
-                    }
-                ],
-                depth_stencil_attachment: Some(
-                    wgpu::RenderPassDepthStencilAttachmentDescriptor {
-                        attachment: &self.depth_texture.view,
-                        depth_load_op: wgpu::LoadOp::Clear,
-                        depth_store_op: wgpu::StoreOp::Store,
-                        clear_depth: 1.0,
-                        stencil_load_op: wgpu::LoadOp::Clear,
-                        stencil_store_op: wgpu::StoreOp::Store,
-                        clear_stencil: 0,
-                    }
-                ),
-            }
-        );
-        self.model_renderer.render(&mut render_pass, &self.uniforms, &self.light);
-        self.light_renderer.render(&mut render_pass, &self.uniforms, &self.light);
-        drop(render_pass);
-    
-        self.core.submit(&[encoder.finish()]);
+    /// Write the saved viewpoints of the Camera to a JSON file.
+    #[cfg(feature = "presets")]
+    pub fn save_camera_presets<P: AsRef<Path>>(&self, path: P) -> Result<(), failure::Error> {
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), &self.camera_presets)?;
+        return Ok(())
     }
-}
 
-/// Create a new RenderPipeline object.
-fn create_render_pipeline(
-    core: &StateCore,
-    bind_group_layouts: &[&wgpu::BindGroupLayout],
-    shader_data: &ShaderData
-) -> wgpu::RenderPipeline {
+    /// Read viewpoints of the Camera from a JSON file (see `save_camera_presets`).
+    ///   They are added to the saved presets, replacing any presets with the same names.
+    #[cfg(feature = "presets")]
+    pub fn load_camera_presets<P: AsRef<Path>>(&mut self, path: P) -> Result<(), failure::Error> {
+        let file = std::fs::File::open(path)?;
+        let presets: HashMap<String, CameraPreset> = serde_json::from_reader(std::io::BufReader::new(file))?;
+        self.camera_presets.extend(presets);
+        return Ok(())
+    }
 
-    let fragment_stage = wgpu::ProgrammableStageDescriptor { 
-        module: &core.device.create_shader_module(&shader_data.fragment),
-        entry_point: "main"
-    };
-    let vertex_stage = wgpu::ProgrammableStageDescriptor { 
-        module: &core.device.create_shader_module(&shader_data.vertex), 
-        entry_point: "main"
-    };
-    let render_pipeline_layout = core.device.create_pipeline_layout(
-        &wgpu::PipelineLayoutDescriptor { bind_group_layouts }
-    );
+    /// Whether the last call to `update` changed the View of the Camera.
+    pub fn view_dirty(&self) -> bool { self.view_dirty }
 
-    return core.device.create_render_pipeline(
-        &wgpu::RenderPipelineDescriptor {
-            layout: &render_pipeline_layout,
-            vertex_stage: vertex_stage,
-            fragment_stage: Some(fragment_stage),
-            rasterization_state: Some(
-                wgpu::RasterizationStateDescriptor {
-                    front_face: wgpu::FrontFace::Ccw,
-                    cull_mode: wgpu::CullMode::Back,
-                    depth_bias: 0,
-                    depth_bias_slope_scale: 0.0,
-                    depth_bias_clamp: 0.0,
-                }
-            ),
-            color_states: &[
-                wgpu::ColorStateDescriptor {
-                    format: core.swap_chain_desc.format,
-                    color_blend: wgpu::BlendDescriptor::REPLACE,
-                    alpha_blend: wgpu::BlendDescriptor::REPLACE,
-                    write_mask: wgpu::ColorWrite::ALL,
-                },
-            ],
-            primitive_topology: wgpu::PrimitiveTopology::TriangleList,
-            depth_stencil_state: Some(
-                wgpu::DepthStencilStateDescriptor {
-                    format: texture::Texture::DEPTH_FORMAT,
-                    depth_write_enabled: true,
-                    depth_compare: wgpu::CompareFunction::Less,
-                    stencil_front: wgpu::StencilStateFaceDescriptor::IGNORE,
-                    stencil_back: wgpu::StencilStateFaceDescriptor::IGNORE,
-                    stencil_read_mask: 0,
-                    stencil_write_mask: 0,
-                }
-            ),
-            vertex_state: wgpu::VertexStateDescriptor {
-                index_format: wgpu::IndexFormat::Uint32,
-                vertex_buffers: {
-                    use crate::model::{InstanceRaw, ModelVertex, Vertex};
-                    &[ModelVertex::describe(), InstanceRaw::describe()]
-                },
-            },
-            sample_count: 1,
-            sample_mask: !0,
-            alpha_to_coverage_enabled: false,
-        }
-    )
-}
+    /// Get a mutable reference to the CameraController object, e.g. to configure its acceleration.
+    pub fn get_camera_controller_mut(&mut self) -> &mut CameraController { &mut self.camera_controller }
 
-fn create_tutorial_instances() -> Vec<Instance> {
-    const NUM_INSTANCES_PER_ROW: u32 = 10;
-    const SPACE_BETWEEN: f32 = 3.0;
+    /// Get a mutable reference to the keys that trigger each Action, to override individual bindings.
+    ///   The bindings are shared by the CameraController and the scene toggles.
+    pub fn get_key_bindings_mut(&mut self) -> &mut KeyBindings { self.camera_controller.get_key_bindings_mut() }
 
-    use cgmath::{InnerSpace, One, Rotation3, Zero};
-    
-    return
-    (0..NUM_INSTANCES_PER_ROW).flat_map(|z| {
-        (0..NUM_INSTANCES_PER_ROW).map(move |x| {
-            let x = SPACE_BETWEEN * (x as f32 - NUM_INSTANCES_PER_ROW as f32 / 2.0);
-            let z = SPACE_BETWEEN * (z as f32 - NUM_INSTANCES_PER_ROW as f32 / 2.0);
-            let position = cgmath::Vector3 { x, y: 0.0, z };
-    
-            let rotation = if position.is_zero() {
-                // this is needed so an object at (0, 0, 0) won't get scaled to zero
-                // as Quaternions can effect scale if they're not create correctly
-                cgmath::Quaternion::one()
-            } else {
-                cgmath::Quaternion::from_axis_angle(position.clone().normalize(), cgmath::Deg(45.0))
+    /// Set the color that the frame is cleared to before drawing. Defaults to `DEFAULT_BACKGROUND_COLOR`.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The new background color.
+    pub fn set_background_color(&mut self, color: wgpu::Color) {
+        self.background_color = color;
+        if self.fog_color.is_none() {
+            self.update_fog();
+        }
+        self.dirty = true;
+    }
+
+    /// Set the fog, which fades the Models into the fog color with their distance from the Camera.
+    ///   The light box and the helper lines (ground grid, axes) are drawn without fog.
+    ///
+    /// # Arguments
+    ///
+    /// * `color`   - The RGB value of the fog color. If None, the fog takes the background color.
+    /// * `density` - The density of the fog. Zero (the default) disables the fog.
+    pub fn set_fog(&mut self, color: Option<Vector3<f32>>, density: f32) {
+        self.fog_color = color;
+        self.fog_density = density;
+        self.update_fog();
+        self.dirty = true;
+    }
+
+    /// Set the exposure, the factor that the linear color of the Models is scaled by before it is
+    ///   tonemapped (Reinhard). Defaults to 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `exposure` - The exposure factor. Larger values brighten the scene.
+    pub fn set_exposure(&mut self, exposure: f32) {
+        self.uniforms.set_exposure(exposure, &self.core);
+        self.dirty = true;
+    }
+
+    /// Restage the fog Uniforms.
+    fn update_fog(&mut self) {
+        let background = self.background_color;
+        let color = self.fog_color.unwrap_or_else(|| {
+            Vector3::new(background.r as f32, background.g as f32, background.b as f32)
+        });
+        self.uniforms.set_fog(color, self.fog_density, &self.core);
+    }
+
+    /// Load a cubemap and draw it as the environment behind the scene.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - The paths to the square image files of the faces, in the order +X, -X, +Y, -Y, +Z, -Z.
+    ///               Relative paths are resolved against the asset root (see `StateCoreConfig::asset_root`).
+    pub fn set_skybox(&mut self, paths: [PathBuf; 6]) -> Result<(), failure::Error> {
+        let root = &self.asset_root;
+        let paths = [
+            root.join(&paths[0]), root.join(&paths[1]), root.join(&paths[2]),
+            root.join(&paths[3]), root.join(&paths[4]), root.join(&paths[5]),
+        ];
+        let (texture, cmd) = texture::Texture::load_cubemap(&self.core.device, &paths)?;
+        self.core.submit(&[cmd]);
+        self.skybox = Some(Skybox::new(&self.core.device, &self.skybox_bind_group_layout, texture));
+        self.dirty = true;
+        return Ok(())
+    }
+
+    /// Stop drawing the Skybox, so the frame is cleared to the background color again.
+    pub fn clear_skybox(&mut self) {
+        self.skybox = None;
+        self.dirty = true;
+    }
+
+    /// Draw a square grid on the ground (XZ) plane, centered at the origin.
+    ///   This replaces the previous ground grid, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `size`      - The length of the sides of the grid.
+    /// * `divisions` - The number of cells along each side of the grid.
+    pub fn enable_ground_grid(&mut self, size: f32, divisions: u32) {
+        self.ground_grid_shape = (size, divisions);
+        self.ground_grid = Some(Lines::grid(&self.core.device, size, divisions, self.ground_grid_color));
+        self.dirty = true;
+    }
+
+    /// Stop drawing the grid on the ground plane.
+    pub fn disable_ground_grid(&mut self) {
+        self.ground_grid = None;
+        self.dirty = true;
+    }
+
+    /// Set the color of the grid on the ground plane. Defaults to `DEFAULT_GROUND_GRID_COLOR`.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The RGB value for the new color of the grid lines.
+    pub fn set_ground_grid_color(&mut self, color: Vector3<f32>) {
+        self.ground_grid_color = color;
+        if self.ground_grid.is_some() {
+            let (size, divisions) = self.ground_grid_shape;
+            self.enable_ground_grid(size, divisions);
+        }
+    }
+
+    /// Request a new title for the window.
+    ///   The State only borrows the Window during construction, so the event loop is expected to
+    ///   apply the title, e.g. `window.set_title(&title)` for each title from `take_window_title`.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - The new title of the window.
+    pub fn set_window_title<S: Into<String>>(&mut self, title: S) {
+        self.pending_window_title = Some(title.into());
+    }
+
+    /// Take the window title requested with `set_window_title`, if it wasn't taken yet.
+    pub fn take_window_title(&mut self) -> Option<String> { self.pending_window_title.take() }
+
+    /// Show or hide the coordinate axes at the origin (X in red, Y in green and Z in blue).
+    ///   The axes are drawn on top of the scene and keep a constant size on screen.
+    pub fn show_axes(&mut self, visible: bool) {
+        self.axes.visible = visible;
+        self.dirty = true;
+    }
+
+    /// Set how frames are presented to the window, e.g. `wgpu::PresentMode::Immediate` to disable vsync.
+    ///   The swap chain is rebuilt; unsupported modes fall back to `Fifo` (see `StateCore::set_present_mode`).
+    pub fn set_present_mode(&mut self, present_mode: wgpu::PresentMode) {
+        self.core.set_present_mode(present_mode);
+        self.dirty = true;
+    }
+
+    /// Set the number of samples per pixel used for multisample anti-aliasing.
+    ///   The render pipelines and render targets are rebuilt to match.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - The number of samples per pixel: 1 (disabled), 2, 4 or 8.
+    ///               Unsupported values are ignored.
+    pub fn set_msaa(&mut self, samples: u32) {
+        if !SUPPORTED_SAMPLE_COUNTS.contains(&samples) {
+            warn!("Unsupported MSAA sample count {}, expected one of {:?}", samples, SUPPORTED_SAMPLE_COUNTS);
+            return
+        }
+        if samples == self.sample_count {
+            return
+        }
+        self.sample_count = samples;
+        self.rebuild_render_pipelines();
+        self.rebuild_render_targets();
+        self.dirty = true;
+    }
+
+    /// Set how the Models are shaded. The Model pipeline is rebuilt with the matching shader programs.
+    pub fn set_shading_mode(&mut self, shading_mode: ShadingMode) {
+        if shading_mode == self.shading_mode {
+            return
+        }
+        self.shading_mode = shading_mode;
+        self.rebuild_render_pipelines();
+        self.dirty = true;
+    }
+
+    /// Get how the Models are shaded.
+    pub fn get_shading_mode(&self) -> ShadingMode { self.shading_mode }
+
+    /// Set the number of bands that the diffuse lighting is quantized into in `ShadingMode::Toon`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bands` - The number of bands, at least 1. Defaults to 3.
+    pub fn set_toon_bands(&mut self, bands: u32) {
+        self.uniforms.set_toon_bands(bands, &self.core);
+        self.dirty = true;
+    }
+
+    /// Get the number of samples per pixel used for multisample anti-aliasing.
+    pub fn get_msaa(&self) -> u32 { self.sample_count }
+
+    /// Recreate the render pipelines with the current sample count.
+    fn rebuild_render_pipelines(&mut self) {
+        let bind_group_layouts = &[
+            &self.texture_bind_group_layout,
+            &self.uniforms.bind_group_layout,
+            &self.light.bind_group_layout,
+        ];
+        let light_bind_group_layouts = &[
+            &self.uniforms.bind_group_layout,
+            &self.light.bind_group_layout,
+        ];
+        let shader = |program: ShaderProgram| self.reloaded_shaders.get(&program).unwrap_or(program.baked());
+        use wgpu::PrimitiveTopology::{LineList, TriangleList};
+        let render_pipeline = create_render_pipeline(
+            &self.core, bind_group_layouts, shader(self.shading_mode.shader_program()), self.sample_count, TriangleList
+        );
+        let wireframe_pipeline = create_render_pipeline(
+            &self.core, bind_group_layouts, shader(ShaderProgram::Model), self.sample_count, LineList
+        );
+        let light_pipeline = create_render_pipeline(
+            &self.core, light_bind_group_layouts, shader(ShaderProgram::Light), self.sample_count, TriangleList
+        );
+        let line_pipeline = create_line_pipeline(
+            &self.core, &[&self.uniforms.bind_group_layout], shader(ShaderProgram::Line), self.sample_count, true
+        );
+        let axes_pipeline = create_line_pipeline(
+            &self.core, &[&self.uniforms.bind_group_layout], shader(ShaderProgram::Axes), self.sample_count, false
+        );
+        let outline_pipeline = create_render_pipeline_with_state(
+            &self.core,
+            &[&self.uniforms.bind_group_layout, &self.outline_bind_group_layout],
+            shader(ShaderProgram::Outline),
+            self.sample_count,
+            TriangleList,
+            wgpu::CullMode::Front,
+            false,
+        );
+        self.model_renderer.set_render_pipeline(render_pipeline);
+        self.model_renderer.set_wireframe_pipeline(wireframe_pipeline);
+        let transparent_pipeline = create_render_pipeline_with_state(
+            &self.core,
+            bind_group_layouts,
+            shader(self.shading_mode.shader_program()),
+            self.sample_count,
+            TriangleList,
+            wgpu::CullMode::Back,
+            true,
+        );
+        self.model_renderer.set_outline_pipeline(outline_pipeline);
+        self.model_renderer.set_transparent_pipeline(transparent_pipeline);
+        self.skybox_pipeline = create_skybox_pipeline(
+            &self.core,
+            &[&self.uniforms.bind_group_layout, &self.skybox_bind_group_layout],
+            shader(ShaderProgram::Skybox),
+            self.sample_count,
+        );
+        self.light_renderer.set_render_pipeline(light_pipeline);
+        self.line_pipeline = line_pipeline;
+        self.axes_pipeline = axes_pipeline;
+    }
+
+    /// Read and compile the GLSL sources of the shaders from a directory, and rebuild the pipelines.
+    ///   Shaders whose sources are missing fall back to the ones compiled into the binary;
+    ///   shaders that fail to compile keep their previous version, so a typo doesn't stop the application.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The directory holding the GLSL sources, e.g. `src/shaders/src`.
+    pub fn reload_shaders<P: AsRef<Path>>(&mut self, directory: P) {
+        let directory = directory.as_ref();
+        for &program in ShaderProgram::ALL.iter() {
+            let (vertex, fragment) = program.file_names();
+            if !directory.join(vertex).is_file() || !directory.join(fragment).is_file() {
+                self.reloaded_shaders.remove(&program);
+                continue
+            }
+            match program.load_from_dir(directory) {
+                Ok(shader_data) => { self.reloaded_shaders.insert(program, shader_data); },
+                Err(error) => warn!("Failed to reload the {:?} shaders: {}", program, error),
+            }
+        }
+        self.rebuild_render_pipelines();
+        self.dirty = true;
+    }
+
+    /// Watch a directory of GLSL sources, reloading the shaders (see `reload_shaders`) whenever they change.
+    ///   The changes are picked up by `update`.
+    ///
+    /// # Arguments
+    ///
+    /// * `directory` - The directory holding the GLSL sources, e.g. `src/shaders/src`.
+    #[cfg(feature = "hot-reload")]
+    pub fn watch_shaders<P: AsRef<Path>>(&mut self, directory: P) -> Result<(), failure::Error> {
+        self.shader_watcher = Some(crate::shaders::ShaderWatcher::new(directory)?);
+        return Ok(())
+    }
+
+    /// Navigate the scene with a gamepad: the left stick orbits the Camera and the triggers zoom.
+    ///   The gamepad is polled in `update`, so while it is enabled `needs_redraw` always reports `true`.
+    #[cfg(feature = "gamepad")]
+    pub fn enable_gamepad(&mut self) -> Result<(), failure::Error> {
+        self.gamepad = Some(crate::input::GamepadInput::new()?);
+        self.dirty = true;
+        return Ok(())
+    }
+
+    /// Stop navigating the scene with a gamepad.
+    #[cfg(feature = "gamepad")]
+    pub fn disable_gamepad(&mut self) {
+        self.gamepad = None;
+        self.camera_controller.set_analog_input(0.0, 0.0, 0.0);
+    }
+
+    /// Turn the Joints of the Robot by the newline-delimited JSON joint states read from stdin,
+    ///   e.g. `{"name": ["shoulder"], "position": [0.5]}` (see `input::JointState`).
+    ///   The joint states are applied in `update`, so while they are read `needs_redraw` always reports `true`.
+    #[cfg(feature = "joint-states")]
+    pub fn read_joint_states(&mut self) {
+        self.joint_state_input = Some(crate::input::JointStateInput::from_stdin());
+    }
+
+    /// Turn the Joints of the Robot by the newline-delimited JSON joint states read from a TCP connection
+    ///   (see `read_joint_states`).
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address that sends the joint states, e.g. `"127.0.0.1:9090"`.
+    #[cfg(feature = "joint-states")]
+    pub fn read_joint_states_from<A: std::net::ToSocketAddrs>(&mut self, address: A) -> Result<(), failure::Error> {
+        self.joint_state_input = Some(crate::input::JointStateInput::connect(address)?);
+        return Ok(())
+    }
+
+    /// Stop reading joint states.
+    #[cfg(feature = "joint-states")]
+    pub fn stop_reading_joint_states(&mut self) {
+        self.joint_state_input = None;
+    }
+
+    /// Recreate the depth texture and multisampled framebuffer at the size of the swap chain.
+    fn rebuild_render_targets(&mut self) {
+        self.depth_texture = texture::Texture::create_depth_texture(
+            &self.core.device, &self.core.swap_chain_desc, self.sample_count, "depth_texture"
+        );
+        self.multisampled_framebuffer = if self.sample_count > 1 {
+            Some(texture::Texture::create_multisampled_framebuffer(
+                &self.core.device, &self.core.swap_chain_desc, self.sample_count
+            ))
+        } else {
+            None
+        };
+    }
+
+    /// The average time between the recently drawn frames (see `FrameTimer`).
+    ///   While idle-throttled, this includes the time spent waiting for events.
+    pub fn average_frame_time(&self) -> Duration { self.frame_timer.average_frame_time() }
+
+    /// The number of frames drawn per second, based on the average frame time.
+    pub fn fps(&self) -> f32 { self.frame_timer.fps() }
+
+    /// Get the number of queue submissions made since the frame before the last drawn frame, including it.
+    ///   The buffer updates are submitted with the frame (see `StateCore::encode`), so this is 1
+    ///   unless something else was submitted in between, e.g. the textures of a loaded Model.
+    pub fn get_frame_submit_count(&self) -> u64 { self.frame_submit_count }
+
+    /// Get the number of staging buffers allocated by the updates of the Uniforms, the Light and the bones
+    ///   of the Models. This stops growing once the staging buffers are reused (see `StagingBuffers`).
+    pub fn get_staging_allocation_count(&self) -> u64 {
+        let models = self.model_renderer.get_models().iter().map(Model::get_staging_allocation_count).sum::<u64>();
+        return self.uniforms.get_staging_allocation_count() + self.light.get_staging_allocation_count() + models
+    }
+
+    /// Get a reference to the depth texture of the scene, e.g. for custom post-processing passes.
+    ///   The texture is created with `SAMPLED` usage and uses the `texture::Texture::DEPTH_FORMAT` format.
+    ///   It is recreated when the window is resized, and is multisampled when MSAA is enabled.
+    pub fn depth_texture(&self) -> &texture::Texture { &self.depth_texture }
+
+    /// Get a reference to the Light object.
+    pub fn get_light(&self) -> &Light { &self.light }
+
+    /// Set the color of the Light object.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The RGB value for the new color of the light.
+    pub fn set_light_color(&mut self, color: Vector3<f32>) {
+        self.light.set_color(color, &self.core);
+        self.dirty = true;
+    }
+
+    /// Set the color of the ambient light, which lights the scene regardless of the Light position.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The RGB value for the new color of the ambient light.
+    pub fn set_ambient_color(&mut self, color: Vector3<f32>) {
+        self.light.set_ambient_color(color, &self.core);
+        self.dirty = true;
+    }
+
+    /// Set the strength of the ambient light. Defaults to `Light::DEFAULT_AMBIENT_INTENSITY`.
+    ///
+    /// # Arguments
+    ///
+    /// * `intensity` - The new strength of the ambient light, where 0 disables it.
+    pub fn set_ambient_intensity(&mut self, intensity: f32) {
+        self.light.set_ambient_intensity(intensity, &self.core);
+        self.dirty = true;
+    }
+
+    /// Set the coefficients of the attenuation of the Light over distance (see `Light::set_attenuation`).
+    ///
+    /// # Arguments
+    ///
+    /// * `constant`  - The constant coefficient.
+    /// * `linear`    - The coefficient of the distance.
+    /// * `quadratic` - The coefficient of the squared distance.
+    pub fn set_light_attenuation(&mut self, constant: f32, linear: f32, quadratic: f32) {
+        self.light.set_attenuation(constant, linear, quadratic, &self.core);
+        self.dirty = true;
+    }
+
+    /// Set the position of the Light object. The light box is moved along with it.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The new 3D position of the light source.
+    pub fn set_light_position(&mut self, position: Vector3<f32>) {
+        self.light.set_position(position, &self.core);
+        self.move_light_box();
+        self.dirty = true;
+    }
+
+    /// Move the light box to the position of the Light object.
+    ///   The instance isn't restaged if the light box is already (within an epsilon) in place.
+    fn move_light_box(&mut self) {
+        use cgmath::InnerSpace;
+        const LIGHT_BOX_EPSILON: f32 = 1e-4;
+        let position = self.light.get_position();
+        let light_model = &mut self.light_renderer.get_models_mut()[0];
+        let in_place = light_model.instances.first().map_or(false, |instance| {
+            (instance.position - position).magnitude2() <= LIGHT_BOX_EPSILON * LIGHT_BOX_EPSILON
+        });
+        if in_place {
+            return
+        }
+        if let Err(error) = light_model.update_instance(0, Instance::from_position(position), &self.core) {
+            warn!("Failed to move the light box: {}", error);
+        }
+    }
+
+    /// Load a model file and add it to the scene, with a single default instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the model file (see `Model::load`). Relative paths are resolved
+    ///              against the asset root (see `StateCoreConfig::asset_root`).
+    ///
+    /// # Returns
+    ///
+    /// The handle of the Model, which stays valid when other Models are removed.
+    pub fn add_model<P: AsRef<Path>>(&mut self, path: P) -> Result<ModelId, failure::Error> {
+        let path = self.asset_root.join(path);
+        let (model, cmds) = Model::load(&self.core.device, &self.texture_bind_group_layout, path)?;
+        self.core.submit(&cmds);
+        self.dirty = true;
+        return Ok(self.model_renderer.add_model(model))
+    }
+
+    /// Add Instances of a model file to the scene. If the file was already loaded (by this method
+    ///   or as part of the scene), the Instances are appended to that Model rather than loading a new one.
+    ///   All of the Instances of a Model are drawn together, with a single draw call per Mesh;
+    ///   e.g. the default 10x10 grid of spheres takes 1 draw call instead of 100 as separate Models.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`      - The path to the model file (see `Model::load`). Relative paths are resolved
+    ///                   against the asset root (see `StateCoreConfig::asset_root`).
+    /// * `instances` - The Instances to add.
+    ///
+    /// # Returns
+    ///
+    /// The handle of the Model holding the Instances.
+    pub fn add_instanced<P: AsRef<Path>>(&mut self, path: P, instances: Vec<Instance>) -> Result<ModelId, failure::Error> {
+        let path = self.asset_root.join(path);
+        let existing = self.instanced_models.get(&path).copied();
+        if let Some(id) = existing {
+            if let Some(model) = self.model_renderer.get_model_mut(id) {
+                let mut combined = std::mem::take(&mut model.instances);
+                combined.extend(instances);
+                model.update_instances(combined, &self.core);
+                self.dirty = true;
+                return Ok(id)
+            }
+        }
+
+        let (mut model, cmds) = Model::load(&self.core.device, &self.texture_bind_group_layout, &path)?;
+        self.core.submit(&cmds);
+        model.set_instances(instances, &self.core.device);
+        let id = self.model_renderer.add_model(model);
+        self.instanced_models.insert(path, id);
+        self.dirty = true;
+        return Ok(id)
+    }
+
+    /// Upload a loaded model file (e.g. from `Model::load_async`) and add it to the scene,
+    ///   with a single default instance.
+    ///
+    /// # Returns
+    ///
+    /// The handle of the Model, which stays valid when other Models are removed.
+    pub fn add_model_data(&mut self, data: ModelData) -> Result<ModelId, failure::Error> {
+        let (model, cmds) = Model::from_data(&self.core.device, &self.texture_bind_group_layout, data)?;
+        self.core.submit(&cmds);
+        self.dirty = true;
+        return Ok(self.model_renderer.add_model(model))
+    }
+
+    /// Add a custom object to the scene, e.g. procedural geometry with its own vertex format.
+    ///   It is drawn after the Models, with the Uniforms and Light of the scene (see `Renderable`).
+    ///
+    /// # Returns
+    ///
+    /// The handle of the Renderable, which can be removed with `remove_model`.
+    pub fn add_renderable(&mut self, renderable: Box<dyn Renderable>) -> ModelId {
+        self.dirty = true;
+        return self.model_renderer.add_renderable(renderable)
+    }
+
+    /// Remove a Model (or a Renderable) from the scene, dropping its GPU resources.
+    ///
+    /// # Returns
+    ///
+    /// Whether there was a Model with the handle.
+    pub fn remove_model(&mut self, id: ModelId) -> bool {
+        let removed = self.model_renderer.remove_model(id).is_some()
+            || self.model_renderer.remove_renderable(id).is_some();
+        self.instanced_models.retain(|_, model_id| *model_id != id);
+        self.dirty |= removed;
+        return removed
+    }
+
+    /// Show or hide a Model in the scene. Unknown handles are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`      - The handle of the Model in the scene.
+    /// * `visible` - Whether the Model should be rendered.
+    pub fn set_model_visible(&mut self, id: ModelId, visible: bool) {
+        if let Some(model) = self.model_renderer.get_model_mut(id) {
+            model.visible = visible;
+            self.dirty = true;
+        }
+    }
+
+    /// Recolor a Material of a Model with a single color, e.g. to highlight a part.
+    ///   The color persists until it is reset with `reset_model_material_color`.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`             - The handle of the Model in the scene.
+    /// * `material_index` - The index of the Material in the Model (see `Model::material_by_name`).
+    /// * `color`          - The color of the Material.
+    pub fn set_model_material_color(
+        &mut self,
+        id: ModelId,
+        material_index: usize,
+        color: image::Rgba<u8>,
+    ) -> Result<(), failure::Error> {
+        let model = self.model_renderer.get_model_mut(id)
+            .ok_or_else(|| failure::format_err!("No model with the handle {:?}", id))?;
+        let cmd = model.set_material_color(&self.core.device, &self.texture_bind_group_layout, material_index, color)?;
+        self.core.submit(&[cmd]);
+        self.dirty = true;
+        return Ok(())
+    }
+
+    /// Restore the original Texture of a Material recolored with `set_model_material_color`.
+    ///   Unknown handles and Materials which weren't recolored are ignored.
+    pub fn reset_model_material_color(&mut self, id: ModelId, material_index: usize) {
+        if let Some(model) = self.model_renderer.get_model_mut(id) {
+            self.dirty |= model.reset_material_color(&self.core.device, &self.texture_bind_group_layout, material_index);
+        }
+    }
+
+    /// Find the Model under a position on the screen, e.g. under the cursor when it is clicked.
+    ///   The ray through the position is intersected with the boxes bounding each instance of the visible Models.
+    ///
+    /// # Arguments
+    ///
+    /// * `x` - The horizontal position, in physical pixels from the left edge of the window.
+    /// * `y` - The vertical position, in physical pixels from the top edge of the window.
+    ///
+    /// # Returns
+    ///
+    /// The handle of the nearest Model that was hit, and the distance to it from the near plane of the Camera.
+    pub fn pick(&self, x: f32, y: f32) -> Option<(ModelId, f32)> {
+        let (width, height) = (self.core.size.width as f32, self.core.size.height as f32);
+        if width == 0.0 || height == 0.0 {
+            return None
+        }
+        let (origin, direction) = self.camera.screen_ray(x, y, width, height)?;
+        return self.model_renderer.get_model_ids().iter()
+            .zip(self.model_renderer.get_models())
+            .filter(|(_, model)| model.visible)
+            .filter_map(|(id, model)| model.intersect_ray(origin, direction).map(|distance| (*id, distance)))
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+    }
+
+    /// Select or deselect a Model, i.e. draw a flat colored outline around it.
+    ///   Unknown handles are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`    - The handle of the Model in the scene.
+    /// * `color` - The RGB value for the color of the outline. If None, the Model is deselected.
+    pub fn set_selected(&mut self, id: ModelId, color: Option<Vector3<f32>>) {
+        let outline = match (color, self.model_renderer.get_model(id)) {
+            (Some(color), Some(model)) => {
+                let width = model.get_bounding_radius() * OUTLINE_WIDTH;
+                Some(Outline::new(&self.core.device, &self.outline_bind_group_layout, color, width))
+            }
+            _ => None,
+        };
+        self.model_renderer.set_outline(id, outline);
+        self.dirty = true;
+    }
+
+    /// Get the color of the outline of a Model, or None if it isn't selected.
+    pub fn get_selected(&self, id: ModelId) -> Option<Vector3<f32>> {
+        return self.model_renderer.get_outline(id).map(Outline::get_color)
+    }
+
+    /// Get the handles of the Models in the scene, in the order they were added.
+    pub fn get_model_ids(&self) -> &[ModelId] { self.model_renderer.get_model_ids() }
+
+    /// Remove all Models from the scene, dropping their GPU resources.
+    ///   The Robot is removed along with the Models of its Links.
+    ///
+    /// The Camera and the Light are left intact, as the shader programs always expect a Light.
+    pub fn clear_scene(&mut self) {
+        self.model_renderer.clear_models();
+        self.instanced_models.clear();
+        self.robot = None;
+        self.selected_joint = None;
+        self.dirty = true;
+    }
+
+    /// Replace the Instances of a Model. Unknown handles are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`        - The handle of the Model in the scene.
+    /// * `instances` - The new Instances of the Model (see `Instance::grid`).
+    pub fn set_model_instances(&mut self, id: ModelId, instances: Vec<Instance>) {
+        if let Some(model) = self.model_renderer.get_model_mut(id) {
+            model.set_instances(instances, &self.core.device);
+            self.dirty = true;
+        }
+    }
+
+    /// Set the Robot whose Joints move the Models of its Links, placing each of the Links.
+    ///   The Models of the Links must already be in the scene (e.g. added with `add_model`).
+    pub fn set_robot(&mut self, robot: Robot) {
+        self.select_joint(None);
+        for (id, instance) in robot.get_link_instances() {
+            self.place_link(id, instance);
+        }
+        self.robot = Some(robot);
+        self.dirty = true;
+    }
+
+    /// Load a robot description from a URDF file, adding a Model for each link with a mesh
+    ///   and setting the Robot (see `set_robot`). The joint limits of the file bound `set_joint_angle`.
+    ///   Only the mesh formats of `Model::load` are supported; links with other meshes (e.g. STL) are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the URDF file. Relative paths are resolved against the asset root
+    ///              (see `StateCoreConfig::asset_root`). Mesh paths are resolved against the URDF file,
+    ///              and `package://` paths against the directories above it.
+    #[cfg(feature = "urdf")]
+    pub fn load_urdf<P: AsRef<Path>>(&mut self, path: P) -> Result<(), failure::Error> {
+        let UrdfRobot { joints, joint_names, links: urdf_links, .. } = UrdfRobot::load(self.asset_root.join(path))?;
+        let mut links = Vec::new();
+        for link in urdf_links {
+            let mesh = match link.mesh {
+                Some(mesh) => mesh,
+                None => continue,
+            };
+            let model = match Model::load(&self.core.device, &self.texture_bind_group_layout, &mesh) {
+                Ok((mut model, cmds)) => {
+                    // Each link is drawn by a single instance, placed at the pose of its joint.
+                    let joint_pose = link.joint.map_or(Pose::identity(), |joint| joints.get_world_pose(joint));
+                    let mut instance = joint_pose.then(&link.origin).to_instance();
+                    instance.scale = link.scale;
+                    model.set_instances(vec![instance], &self.core.device);
+                    self.core.submit(&cmds);
+                    self.model_renderer.add_model(model)
+                }
+                Err(error) => {
+                    warn!("Skipping the mesh {:?} of link {:?}: {}", mesh, link.name, error);
+                    continue
+                }
             };
+            links.push(Link { joint: link.joint, model, origin: link.origin, scale: link.scale });
+        }
+        let mut robot = Robot::new(joints, links);
+        robot.set_joint_names(joint_names);
+        self.set_robot(robot);
+        return Ok(())
+    }
+
+    /// Get the Robot of the scene, if any.
+    pub fn get_robot(&self) -> Option<&Robot> { self.robot.as_ref() }
+
+    /// Set the angle of a Joint of the Robot, moving the Models of the Links below it.
+    ///   The angle is clamped to the limits of the Joint (e.g. from `load_urdf`).
+    ///
+    /// # Arguments
+    ///
+    /// * `index`   - The index of the Joint (see `JointHierarchy::get_joints`).
+    /// * `radians` - The new angle of the Joint about its axis.
+    ///
+    /// # Returns
+    ///
+    /// Whether the Robot has a Joint with the index.
+    pub fn set_joint_angle(&mut self, index: usize, radians: f32) -> bool {
+        let robot = match self.robot.as_mut() {
+            Some(robot) if index < robot.get_joints().len() => robot,
+            _ => return false,
+        };
+        for (id, instance) in robot.set_joint_angle(index, radians) {
+            self.place_link(id, instance);
+        }
+        return true
+    }
+
+    /// Set the angles of the Joints of the Robot by their names (see `Robot::set_joint_names`).
+    ///   Unknown names are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `names`   - The names of the Joints, e.g. from `load_urdf`.
+    /// * `radians` - The new angles of the Joints, in the same order as the names.
+    ///
+    /// # Returns
+    ///
+    /// The number of Joints that were set.
+    pub fn set_joint_angles_named<S: AsRef<str>>(&mut self, names: &[S], radians: &[f32]) -> usize {
+        let indices: Vec<Option<usize>> = match self.robot.as_ref() {
+            Some(robot) => names.iter().map(|name| robot.find_joint(name.as_ref())).collect(),
+            None => return 0,
+        };
+        let mut set_count = 0;
+        for (index, &angle) in indices.into_iter().zip(radians.iter()) {
+            if let Some(index) = index {
+                set_count += self.set_joint_angle(index, angle) as usize;
+            }
+        }
+        if set_count > 0 {
+            self.dirty = true;
+        }
+        return set_count
+    }
+
+    /// Move the first instance of the Model of a Link, adding the instance if the Model has none.
+    ///   Unknown handles are ignored.
+    fn place_link(&mut self, id: ModelId, instance: Instance) {
+        let model = match self.model_renderer.get_model_mut(id) {
+            Some(model) => model,
+            None => return,
+        };
+        if model.instances.is_empty() {
+            model.push_instance(instance, &self.core);
+        } else if let Err(error) = model.update_instance(0, instance, &self.core) {
+            warn!("Failed to move the link model: {}", error);
+        }
+        self.dirty = true;
+    }
+
+    /// Select a Joint of the Robot to be turned by the jog keys, outlining the Links that it moves
+    ///   with `SELECTED_JOINT_COLOR`. The Links of the previously selected Joint are deselected.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the Joint (see `JointHierarchy::get_joints`), or None to deselect it.
+    ///
+    /// # Returns
+    ///
+    /// Whether the Robot has a Joint with the index (always true when deselecting).
+    pub fn select_joint(&mut self, index: Option<usize>) -> bool {
+        let joint_count = self.robot.as_ref().map_or(0, |robot| robot.get_joints().len());
+        if index.map_or(false, |index| index >= joint_count) {
+            return false
+        }
+        for id in self.get_joint_link_ids(self.selected_joint) {
+            self.set_selected(id, None);
+        }
+        for id in self.get_joint_link_ids(index) {
+            self.set_selected(id, Some(SELECTED_JOINT_COLOR));
+        }
+        self.selected_joint = index;
+        return true
+    }
+
+    /// Get the index of the Joint of the Robot that the jog keys turn, if any.
+    pub fn get_selected_joint(&self) -> Option<usize> { self.selected_joint }
+
+    /// Turn the selected Joint of the Robot by `JOINT_JOG_STEP`, within the limits of the Joint.
+    ///
+    /// # Arguments
+    ///
+    /// * `direction` - The number of steps to turn the Joint by, e.g. -1.0 to turn it back one step.
+    ///
+    /// # Returns
+    ///
+    /// Whether a Joint is selected.
+    pub fn jog_selected_joint(&mut self, direction: f32) -> bool {
+        let (index, angle) = match (self.selected_joint, self.robot.as_ref()) {
+            (Some(index), Some(robot)) => (index, robot.get_joints().get_joint_angle(index)),
+            _ => return false,
+        };
+        return self.set_joint_angle(index, angle + direction * JOINT_JOG_STEP)
+    }
+
+    /// Get the handles of the Models of the Links moved directly by a Joint of the Robot.
+    fn get_joint_link_ids(&self, index: Option<usize>) -> Vec<ModelId> {
+        return match (index, self.robot.as_ref()) {
+            (Some(index), Some(robot)) => robot.get_links().iter()
+                .filter(|link| link.joint == Some(index))
+                .map(|link| link.model)
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Handle a resizing of the window.
+    pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
+        // A minimized window has no size, so keep the previous swap chain until it is restored.
+        self.minimized = new_size.width == 0 || new_size.height == 0;
+        if self.minimized {
+            return
+        }
+        self.core.resize(new_size);
+        self.rebuild_render_targets();
+        self.camera.get_projection_mut().set_aspect(self.core.get_aspect_ratio());
+        self.uniforms.update_from_camera(&self.camera, &self.core);
+        self.dirty = true;
+    }
+
+    /// Handle the Window events.
+    ///
+    /// This includes processing events on the camera controller (see for more information),
+    /// And processing the following events here:
+    ///   * If the `L` key is pressed, toggle the visibility of the light box.
+    ///   * If the `O` key is pressed, toggle between the perspective and orthographic projections.
+    ///   * If the `R` key is pressed, reset the Camera to its default View and Projection.
+    ///   * If the `C` key is pressed, switch between the orbit and free-fly Camera modes.
+    ///   * If the `F` key is pressed, move the Camera so that every Model is visible.
+    ///   * If the `Z` key is pressed, toggle the wireframe rendering of the Models.
+    ///   * If a key from `1` to `9` is pressed, select that Joint of the Robot (or deselect it, if it was selected).
+    ///   * If the `]` or `[` key is pressed (or held), turn the selected Joint forward or back.
+    ///
+    /// # Returns
+    /// 
+    /// Boolean of whether an event was handled.
+    pub fn input(&mut self, event: &WindowEvent) -> bool {
+        let handled_event = self.camera_controller.process_events(event);
+        let handled_toggle = match event {
+            WindowEvent::KeyboardInput {
+                input: KeyboardInput { state, virtual_keycode: Some(keycode), .. },
+                ..
+            } => {
+                let is_pressed = *state == ElementState::Pressed;
+                match self.camera_controller.get_key_bindings().get_action(*keycode) {
+                    Some(Action::ToggleLight) => { self.light_renderer.visible ^= is_pressed; true },
+                    Some(Action::ToggleProjection) => {
+                        if is_pressed {
+                            self.camera.get_projection_mut().toggle_mode();
+                            self.uniforms.update_from_camera(&self.camera, &self.core);
+                        }
+                        true
+                    },
+                    Some(Action::ResetView) => {
+                        if is_pressed {
+                            self.camera.reset_view();
+                            self.camera_controller.stop();
+                            self.uniforms.update_from_camera(&self.camera, &self.core);
+                        }
+                        true
+                    },
+                    Some(Action::ToggleCameraMode) => {
+                        if is_pressed { self.camera_controller.toggle_mode(); }
+                        true
+                    },
+                    Some(Action::FrameAll) => {
+                        if is_pressed { self.frame_all(); }
+                        true
+                    },
+                    Some(Action::ToggleWireframe) => { self.model_renderer.wireframe ^= is_pressed; true },
+                    Some(Action::TogglePause) => { self.paused ^= is_pressed; true },
+                    Some(Action::SelectJoint(index)) => {
+                        if is_pressed {
+                            let toggled = if self.selected_joint == Some(index) { None } else { Some(index) };
+                            self.select_joint(toggled);
+                        }
+                        self.robot.is_some()
+                    },
+                    Some(Action::JogJointUp) => { is_pressed && self.jog_selected_joint(1.0) },
+                    Some(Action::JogJointDown) => { is_pressed && self.jog_selected_joint(-1.0) },
+                    _ => false,
+                }
+            },
+            _ => false,
+        };
+
+        // Any handled event may have changed the scene, so a new frame should be drawn.
+        let handled = handled_event || handled_toggle;
+        self.dirty |= handled;
+        return handled
+    }
+
+    /// Make updates to the scene and data being sent to the GPU.
+    ///
+    /// # Returns
+    ///
+    /// Boolean of whether anything in the scene changed.
+    pub fn update(&mut self) -> bool {
+        let mut changed = false;
+
+        // The time (in seconds) since the last update.
+        // This is capped so the scene doesn't jump after the loop was idle or stalled.
+        const MAX_DT: f32 = 0.1;
+        let now = Instant::now();
+        let dt = (now - self.last_update).as_secs_f32().min(MAX_DT);
+        self.last_update = now;
+
+        // Move the light along its animation, taking the light box with it.
+        if self.animating && !self.paused && self.light.update(dt, &self.core) {
+            self.move_light_box();
+            changed = true;
+        }
+
+        // Reload the shaders if their sources changed.
+        #[cfg(feature = "hot-reload")]
+        {
+            let changed_shaders = self.shader_watcher.as_ref().and_then(|watcher| {
+                let changed_files = watcher.changed_files();
+                if changed_files.is_empty() { None } else { Some((watcher.get_directory().to_path_buf(), changed_files)) }
+            });
+            if let Some((directory, changed_files)) = changed_shaders {
+                info!("Reloading shaders after changes to {:?}", changed_files);
+                self.reload_shaders(directory);
+                changed = true;
+            }
+        }
+
+        // Feed the gamepad into the CameraController. Polling never blocks the event loop.
+        #[cfg(feature = "gamepad")]
+        {
+            if let Some(gamepad) = self.gamepad.as_mut() {
+                let (yaw, pitch, radial) = gamepad.poll();
+                self.camera_controller.set_analog_input(yaw, pitch, radial);
+            }
+        }
+
+        // Turn the Joints of the Robot by the joint states that were read. Reading never blocks the event loop.
+        #[cfg(feature = "joint-states")]
+        {
+            let joint_states = self.joint_state_input.as_ref().map_or(Vec::new(), |input| input.poll());
+            for joint_state in joint_states {
+                if self.set_joint_angles_named(&joint_state.name, &joint_state.position) > 0 {
+                    changed = true;
+                }
+            }
+        }
+
+        // Make updates to the camera and uniform objects if necessary.
+        self.view_dirty = self.camera_controller.update_camera(&mut self.camera, dt);
+        if self.view_dirty {
+            self.uniforms.update_from_camera(&self.camera, &self.core);
+            changed = true;
+        }
+
+        // Order the transparent instances back to front, before the visible ones are picked out.
+        let eye = self.camera.get_view().get_position();
+        self.model_renderer.sort_transparent(eye, &self.core);
+
+        // Skip drawing the instances that are out of view.
+        if self.frustum_culling {
+            let planes = self.camera.frustum_planes();
+            for model in self.model_renderer.get_models_mut().iter_mut() {
+                model.cull_instances(&planes);
+            }
+        }
+
+        // Keep drawing while something changes (e.g. a camera key is held down).
+        self.dirty = changed;
+        return changed
+    }
+
+    /// Render the scene.
+    ///   If the next frame of the swap chain can't be acquired, the swap chain is recreated
+    ///   and the frame is skipped.
+    pub fn render(&mut self) {
+        // Nothing can be drawn into a minimized window. The buffer updates are still submitted,
+        //   so they don't pile up in the frame encoder.
+        if self.minimized {
+            return self.core.flush()
+        }
+
+        self.last_frame = Instant::now();
+        self.frame_timer.tick(self.last_frame);
+        let frame = match self.core.swap_chain.get_next_texture() {
+            Ok(frame) => frame,
+            Err(_) => {
+                warn!("Timed out acquiring the next frame, recreating the swap chain");
+                self.core.resize(self.core.size);
+                self.core.flush();
+                self.dirty = true;
+                return
+            }
+        };
+
+        // The frame is recorded after the buffer updates of this frame, and submitted with them.
+        let mut encoder = self.core.take_frame_encoder();
+        
+        // With MSAA, the multisampled framebuffer is drawn and then resolved into the frame.
+        let (attachment, resolve_target) = match &self.multisampled_framebuffer {
+            Some(framebuffer) => (framebuffer, Some(&frame.view)),
+            None => (&frame.view, None),
+        };
+
+        let mut render_pass = encoder.begin_render_pass(
+            &wgpu::RenderPassDescriptor {
+                color_attachments: &[
+                    wgpu::RenderPassColorAttachmentDescriptor {
+                        attachment,
+                        resolve_target,
+                        load_op: wgpu::LoadOp::Clear,
+                        store_op: wgpu::StoreOp::Store,
+                        clear_color: self.background_color,
+                    }
+                ],
+                depth_stencil_attachment: Some(
+                    wgpu::RenderPassDepthStencilAttachmentDescriptor {
+                        attachment: &self.depth_texture.view,
+                        depth_load_op: wgpu::LoadOp::Clear,
+                        depth_store_op: wgpu::StoreOp::Store,
+                        clear_depth: 1.0,
+                        stencil_load_op: wgpu::LoadOp::Clear,
+                        stencil_store_op: wgpu::StoreOp::Store,
+                        clear_stencil: 0,
+                    }
+                ),
+            }
+        );
+        self.model_renderer.render(&mut render_pass, &self.uniforms, &self.light);
+        self.light_renderer.render(&mut render_pass, &self.uniforms, &self.light);
+        // The Skybox only fills the pixels that the opaque scene left at the far plane.
+        if let Some(skybox) = &self.skybox {
+            render_pass.set_pipeline(&self.skybox_pipeline);
+            render_pass.set_bind_group(0, &self.uniforms.bind_group, &[]);
+            render_pass.set_bind_group(1, &skybox.bind_group, &[]);
+            render_pass.draw(0..36, 0..1);
+        }
+        // Transparent Meshes are blended over everything opaque, including the Skybox.
+        let eye = self.camera.get_view().get_position();
+        self.model_renderer.render_transparent(&mut render_pass, &self.uniforms, &self.light, eye);
+        if let Some(ground_grid) = &self.ground_grid {
+            render_pass.set_pipeline(&self.line_pipeline);
+            render_pass.draw_lines(ground_grid, &self.uniforms.bind_group);
+        }
+        // The axes are drawn last, as they ignore the depth of the scene.
+        if self.axes.visible {
+            render_pass.set_pipeline(&self.axes_pipeline);
+            render_pass.draw_lines(&self.axes, &self.uniforms.bind_group);
+        }
+        drop(render_pass);
     
-            Instance { position, rotation }
-        })
-    }).collect();
+        self.core.submit(&[encoder.finish()]);
+        let submit_total = self.core.get_submit_count();
+        self.frame_submit_count = submit_total - self.last_frame_submit_total;
+        self.last_frame_submit_total = submit_total;
+    }
+}
+
+/// Create a new RenderPipeline object for each of the index formats that a Mesh may use.
+///   Triangles are back face culled, while lines (which have no facing) are not culled.
+fn create_render_pipeline(
+    core: &StateCore,
+    bind_group_layouts: &[&wgpu::BindGroupLayout],
+    shader_data: &ShaderData,
+    sample_count: u32,
+    primitive_topology: wgpu::PrimitiveTopology,
+) -> RenderPipelines {
+    let cull_mode = match primitive_topology {
+        wgpu::PrimitiveTopology::TriangleList | wgpu::PrimitiveTopology::TriangleStrip => wgpu::CullMode::Back,
+        _ => wgpu::CullMode::None,
+    };
+    return create_render_pipeline_with_state(
+        core, bind_group_layouts, shader_data, sample_count, primitive_topology, cull_mode, false
+    )
+}
+
+/// Create a new RenderPipeline object for each of the index formats that a Mesh may use,
+///   with the given faces culled (e.g. the front faces for the inverted hull of an outline).
+///   Alpha blended pipelines (for transparent Meshes) test the depth, but don't write to it.
+fn create_render_pipeline_with_state(
+    core: &StateCore,
+    bind_group_layouts: &[&wgpu::BindGroupLayout],
+    shader_data: &ShaderData,
+    sample_count: u32,
+    primitive_topology: wgpu::PrimitiveTopology,
+    cull_mode: wgpu::CullMode,
+    alpha_blend: bool,
+) -> RenderPipelines {
+    let color_blend = if alpha_blend {
+        wgpu::BlendDescriptor {
+            src_factor: wgpu::BlendFactor::SrcAlpha,
+            dst_factor: wgpu::BlendFactor::OneMinusSrcAlpha,
+            operation: wgpu::BlendOperation::Add,
+        }
+    } else {
+        wgpu::BlendDescriptor::REPLACE
+    };
+
+    let fragment_module = core.device.create_shader_module(&shader_data.fragment);
+    let vertex_module = core.device.create_shader_module(&shader_data.vertex);
+    let render_pipeline_layout = core.device.create_pipeline_layout(
+        &wgpu::PipelineLayoutDescriptor { bind_group_layouts }
+    );
+
+    // The index format is baked into the RenderPipeline, so a variant is needed for each format.
+    let create_variant = |index_format: wgpu::IndexFormat| core.device.create_render_pipeline(
+        &wgpu::RenderPipelineDescriptor {
+            layout: &render_pipeline_layout,
+            vertex_stage: wgpu::ProgrammableStageDescriptor { module: &vertex_module, entry_point: "main" },
+            fragment_stage: Some(wgpu::ProgrammableStageDescriptor { module: &fragment_module, entry_point: "main" }),
+            rasterization_state: Some(
+                wgpu::RasterizationStateDescriptor {
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode,
+                    depth_bias: 0,
+                    depth_bias_slope_scale: 0.0,
+                    depth_bias_clamp: 0.0,
+                }
+            ),
+            color_states: &[
+                wgpu::ColorStateDescriptor {
+                    format: core.swap_chain_desc.format,
+                    color_blend: color_blend.clone(),
+                    alpha_blend: wgpu::BlendDescriptor::REPLACE,
+                    write_mask: wgpu::ColorWrite::ALL,
+                },
+            ],
+            primitive_topology,
+            depth_stencil_state: Some(
+                wgpu::DepthStencilStateDescriptor {
+                    format: texture::Texture::DEPTH_FORMAT,
+                    depth_write_enabled: !alpha_blend,
+                    depth_compare: wgpu::CompareFunction::Less,
+                    stencil_front: wgpu::StencilStateFaceDescriptor::IGNORE,
+                    stencil_back: wgpu::StencilStateFaceDescriptor::IGNORE,
+                    stencil_read_mask: 0,
+                    stencil_write_mask: 0,
+                }
+            ),
+            vertex_state: wgpu::VertexStateDescriptor {
+                index_format,
+                vertex_buffers: {
+                    use crate::model::{InstanceRaw, ModelVertex, Vertex};
+                    &[ModelVertex::describe(), InstanceRaw::describe()]
+                },
+            },
+            sample_count,
+            sample_mask: !0,
+            alpha_to_coverage_enabled: false,
+        }
+    );
+
+    return RenderPipelines {
+        uint16: create_variant(wgpu::IndexFormat::Uint16),
+        uint32: create_variant(wgpu::IndexFormat::Uint32),
+    }
+}
+
+/// Create a new RenderPipeline object for drawing Lines (see `LineVertex`).
+///   Lines are not indexed, so a single variant is needed.
+///   Without the depth test, the Lines are drawn on top of everything drawn before them.
+fn create_line_pipeline(
+    core: &StateCore,
+    bind_group_layouts: &[&wgpu::BindGroupLayout],
+    shader_data: &ShaderData,
+    sample_count: u32,
+    depth_test: bool,
+) -> wgpu::RenderPipeline {
+
+    let fragment_module = core.device.create_shader_module(&shader_data.fragment);
+    let vertex_module = core.device.create_shader_module(&shader_data.vertex);
+    let render_pipeline_layout = core.device.create_pipeline_layout(
+        &wgpu::PipelineLayoutDescriptor { bind_group_layouts }
+    );
+
+    return core.device.create_render_pipeline(
+        &wgpu::RenderPipelineDescriptor {
+            layout: &render_pipeline_layout,
+            vertex_stage: wgpu::ProgrammableStageDescriptor { module: &vertex_module, entry_point: "main" },
+            fragment_stage: Some(wgpu::ProgrammableStageDescriptor { module: &fragment_module, entry_point: "main" }),
+            rasterization_state: Some(
+                wgpu::RasterizationStateDescriptor {
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: wgpu::CullMode::None,
+                    depth_bias: 0,
+                    depth_bias_slope_scale: 0.0,
+                    depth_bias_clamp: 0.0,
+                }
+            ),
+            color_states: &[
+                wgpu::ColorStateDescriptor {
+                    format: core.swap_chain_desc.format,
+                    color_blend: wgpu::BlendDescriptor::REPLACE,
+                    alpha_blend: wgpu::BlendDescriptor::REPLACE,
+                    write_mask: wgpu::ColorWrite::ALL,
+                },
+            ],
+            primitive_topology: wgpu::PrimitiveTopology::LineList,
+            depth_stencil_state: Some(
+                wgpu::DepthStencilStateDescriptor {
+                    format: texture::Texture::DEPTH_FORMAT,
+                    depth_write_enabled: depth_test,
+                    depth_compare: if depth_test { wgpu::CompareFunction::Less } else { wgpu::CompareFunction::Always },
+                    stencil_front: wgpu::StencilStateFaceDescriptor::IGNORE,
+                    stencil_back: wgpu::StencilStateFaceDescriptor::IGNORE,
+                    stencil_read_mask: 0,
+                    stencil_write_mask: 0,
+                }
+            ),
+            vertex_state: wgpu::VertexStateDescriptor {
+                index_format: wgpu::IndexFormat::Uint16,
+                vertex_buffers: {
+                    use crate::{lines::LineVertex, model::Vertex};
+                    &[LineVertex::describe()]
+                },
+            },
+            sample_count,
+            sample_mask: !0,
+            alpha_to_coverage_enabled: false,
+        }
+    );
+}
+
+/// Create a new RenderPipeline object for drawing a Skybox. The cube is generated in `skybox.vert`,
+///   so there are no vertex buffers. The depth is tested but not written, so the Skybox only
+///   fills the pixels that nothing else was drawn to.
+fn create_skybox_pipeline(
+    core: &StateCore,
+    bind_group_layouts: &[&wgpu::BindGroupLayout],
+    shader_data: &ShaderData,
+    sample_count: u32,
+) -> wgpu::RenderPipeline {
+
+    let fragment_module = core.device.create_shader_module(&shader_data.fragment);
+    let vertex_module = core.device.create_shader_module(&shader_data.vertex);
+    let render_pipeline_layout = core.device.create_pipeline_layout(
+        &wgpu::PipelineLayoutDescriptor { bind_group_layouts }
+    );
+
+    return core.device.create_render_pipeline(
+        &wgpu::RenderPipelineDescriptor {
+            layout: &render_pipeline_layout,
+            vertex_stage: wgpu::ProgrammableStageDescriptor { module: &vertex_module, entry_point: "main" },
+            fragment_stage: Some(wgpu::ProgrammableStageDescriptor { module: &fragment_module, entry_point: "main" }),
+            rasterization_state: Some(
+                wgpu::RasterizationStateDescriptor {
+                    front_face: wgpu::FrontFace::Ccw,
+                    cull_mode: wgpu::CullMode::None,
+                    depth_bias: 0,
+                    depth_bias_slope_scale: 0.0,
+                    depth_bias_clamp: 0.0,
+                }
+            ),
+            color_states: &[
+                wgpu::ColorStateDescriptor {
+                    format: core.swap_chain_desc.format,
+                    color_blend: wgpu::BlendDescriptor::REPLACE,
+                    alpha_blend: wgpu::BlendDescriptor::REPLACE,
+                    write_mask: wgpu::ColorWrite::ALL,
+                },
+            ],
+            primitive_topology: wgpu::PrimitiveTopology::TriangleList,
+            depth_stencil_state: Some(
+                wgpu::DepthStencilStateDescriptor {
+                    format: texture::Texture::DEPTH_FORMAT,
+                    depth_write_enabled: false,
+                    // The Skybox lies on the far plane, which is where the depth is cleared to.
+                    depth_compare: wgpu::CompareFunction::LessEqual,
+                    stencil_front: wgpu::StencilStateFaceDescriptor::IGNORE,
+                    stencil_back: wgpu::StencilStateFaceDescriptor::IGNORE,
+                    stencil_read_mask: 0,
+                    stencil_write_mask: 0,
+                }
+            ),
+            vertex_state: wgpu::VertexStateDescriptor {
+                index_format: wgpu::IndexFormat::Uint16,
+                vertex_buffers: &[],
+            },
+            sample_count,
+            sample_mask: !0,
+            alpha_to_coverage_enabled: false,
+        }
+    );
 }
@@ -1,9 +1,61 @@
+use std::{cell::{Cell, RefCell}, path::PathBuf};
 use winit::window::Window;
 use wgpu::{DeviceDescriptor, SwapChainDescriptor};
 
 type PhysicalSize = winit::dpi::PhysicalSize<u32>;
 
 
+/// Options for creating the WGPU primitives of a StateCore.
+#[derive(Clone, Debug)]
+pub struct StateCoreConfig {
+
+    // Whether to request the anisotropic filtering extension of the graphics device.
+    //   The `wgpu::SamplerDescriptor` of wgpu 0.5 has no anisotropy clamp, so the samplers
+    //   can't opt in to it yet; this only enables the device extension.
+    pub anisotropic_filtering: bool,
+
+    // How frames are presented to the window. `Fifo` waits for vertical sync,
+    //   `Mailbox` and `Immediate` don't cap the framerate.
+    pub present_mode: wgpu::PresentMode,
+
+    // The graphics backends that adapters may be picked from, e.g. `wgpu::BackendBit::VULKAN`.
+    pub backend: wgpu::BackendBit,
+
+    // Whether to prefer an integrated (`LowPower`) or a discrete (`HighPerformance`) GPU.
+    pub power_preference: wgpu::PowerPreference,
+
+    // The directory that relative model paths are resolved against. Empty means the working directory.
+    //   The shaders and the built-in models are compiled into the binary, so they don't need it.
+    pub asset_root: PathBuf,
+
+    // The formats that the swap chain may use, in order of preference. The first one that the backend
+    //   of the adapter can present is picked (see `pick_swap_chain_format`).
+    pub swap_chain_formats: Vec<wgpu::TextureFormat>,
+}
+
+impl Default for StateCoreConfig {
+    fn default() -> Self {
+        return StateCoreConfig {
+            anisotropic_filtering: false,
+            present_mode: wgpu::PresentMode::Fifo,
+            backend: wgpu::BackendBit::PRIMARY, // Vulkan + Metal + DX12 + Browser WebGPU
+            power_preference: wgpu::PowerPreference::Default,
+            asset_root: PathBuf::new(),
+            swap_chain_formats: DEFAULT_SWAP_CHAIN_FORMATS.to_vec(),
+        }
+    }
+}
+
+/// The default formats that the swap chain may use, in order of preference.
+///   The sRGB formats come first, as the shader programs output linear colors.
+pub const DEFAULT_SWAP_CHAIN_FORMATS: [wgpu::TextureFormat; 4] = [
+    wgpu::TextureFormat::Bgra8UnormSrgb,
+    wgpu::TextureFormat::Bgra8Unorm,
+    wgpu::TextureFormat::Rgba8UnormSrgb,
+    wgpu::TextureFormat::Rgba8Unorm,
+];
+
+
 pub struct StateCore {
     pub adapter: wgpu::Adapter,
     pub device: wgpu::Device,
@@ -12,32 +64,56 @@ pub struct StateCore {
     pub surface: wgpu::Surface,
     pub swap_chain: wgpu::SwapChain,
     pub swap_chain_desc: wgpu::SwapChainDescriptor,
+    present_mode: wgpu::PresentMode,
+
+    // The encoder that buffer updates are recorded into until the next frame is submitted (see `encode`).
+    frame_encoder: RefCell<Option<wgpu::CommandEncoder>>,
+
+    // The number of submissions made to the queue.
+    submit_count: Cell<u64>,
+
+    // The index of the frame encoder, counting up each time one is taken to be submitted.
+    frame_index: Cell<u64>,
 }
 
 impl StateCore {
 
-    const DEVICE_DESC: DeviceDescriptor = DeviceDescriptor {
-        extensions: wgpu::Extensions { anisotropic_filtering: false },
-        limits: wgpu::Limits { max_bind_groups: wgpu::MAX_BIND_GROUPS as u32 },
-    };
-
-    pub async fn new(window: &Window) -> Self {
+    /// Create the WGPU primitives for rendering to the window.
+    ///
+    /// # Arguments
+    ///
+    /// * `window` - The window to render to.
+    /// * `config` - The options for creating the graphics device.
+    ///
+    /// # Returns
+    ///
+    /// An error if no adapter matching the backends of the config can render to the window.
+    pub async fn new(window: &Window, config: &StateCoreConfig) -> Result<Self, failure::Error> {
         let size = window.inner_size();
         let surface = wgpu::Surface::create(window);
         let adapter = wgpu::Adapter::request(
             &wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::Default,
+                power_preference: config.power_preference,
                 compatible_surface: Some(&surface),
             },
-            wgpu::BackendBit::PRIMARY, // Vulkan + Metal + DX12 + Browser WebGPU
-        ).await.unwrap();
+            config.backend,
+        ).await.ok_or_else(|| failure::format_err!(
+            "No graphics adapter found for backends {:?} with power preference {:?}",
+            config.backend, config.power_preference,
+        ))?;
+        info!("Using graphics adapter: {:?}", adapter.get_info());
 
-        let (device, queue) = adapter.request_device(&Self::DEVICE_DESC).await;
+        let device_desc = DeviceDescriptor {
+            extensions: wgpu::Extensions { anisotropic_filtering: config.anisotropic_filtering },
+            limits: wgpu::Limits { max_bind_groups: wgpu::MAX_BIND_GROUPS as u32 },
+        };
+        let (device, queue) = adapter.request_device(&device_desc).await;
 
-        let swap_chain_desc = create_swap_chain_desc(size);
+        let format = pick_swap_chain_format(adapter.get_info().backend, &config.swap_chain_formats)?;
+        let swap_chain_desc = create_swap_chain_desc(size, format, config.present_mode);
         let swap_chain = device.create_swap_chain(&surface, &swap_chain_desc);
 
-        return StateCore {
+        return Ok(StateCore {
             adapter,
             device,
             queue,
@@ -45,32 +121,182 @@ impl StateCore {
             surface,
             swap_chain,
             swap_chain_desc,
-        }
+            present_mode: config.present_mode,
+            frame_encoder: RefCell::new(None),
+            submit_count: Cell::new(0),
+            frame_index: Cell::new(0),
+        })
     }
 
     pub fn get_aspect_ratio(&self) -> f32 {
         return (self.size.width as f32) / (self.size.height as f32)
     }
 
+    /// Recreate the swap chain at the new size.
+    ///   Zero sizes (e.g. when the window is minimized) are ignored, keeping the previous swap chain.
     pub fn resize(&mut self, new_size: PhysicalSize) {
+        if !resize_swap_chain_desc(&mut self.swap_chain_desc, new_size) {
+            return
+        }
+        debug!("Recreating the swap chain at {}x{}", new_size.width, new_size.height);
         self.size = new_size;
-        self.swap_chain_desc.width = new_size.width;
-        self.swap_chain_desc.height = new_size.height;
         self.swap_chain = self.device.create_swap_chain(&self.surface, &self.swap_chain_desc);
     }
 
+    /// Get how frames are presented to the window.
+    pub fn get_present_mode(&self) -> wgpu::PresentMode { self.present_mode }
+
+    /// Set how frames are presented to the window, recreating the swap chain.
+    ///   wgpu falls back to `Fifo` (which every adapter supports) if the adapter doesn't support the mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `present_mode` - `Fifo` (vsync), `Mailbox` or `Immediate`.
+    pub fn set_present_mode(&mut self, present_mode: wgpu::PresentMode) {
+        debug!("Recreating the swap chain with present mode {:?}", present_mode);
+        self.present_mode = present_mode;
+        self.swap_chain_desc.present_mode = present_mode;
+        self.swap_chain = self.device.create_swap_chain(&self.surface, &self.swap_chain_desc);
+    }
+
+    /// Get the queue of the graphics device, e.g. to submit custom compute passes or buffer readbacks.
+    ///   Submissions made directly to the queue aren't counted by `get_submit_count`, and run before whatever
+    ///   is recorded by `encode` for the next frame; `flush` that first if the submission depends on it.
+    ///   Like the rest of the StateCore, the queue is meant to be used from the thread running the event loop.
+    pub fn queue(&self) -> &wgpu::Queue { &self.queue }
+
+    /// Submit command buffers to the queue of the graphics device.
     pub fn submit(&self, command_buffers: &[wgpu::CommandBuffer]) {
+        self.submit_count.set(self.submit_count.get() + 1);
         self.queue.submit(command_buffers)
     }
+
+    /// Record commands (e.g. the copies of a buffer update) into the encoder of the next frame.
+    ///   The commands are submitted along with the frame, rather than in a submission of their own,
+    ///   so they run before the frame is drawn.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - A function recording the commands into the encoder.
+    pub fn encode<F: FnOnce(&mut wgpu::CommandEncoder)>(&self, record: F) {
+        let mut frame_encoder = self.frame_encoder.borrow_mut();
+        let encoder = frame_encoder.get_or_insert_with(|| self.device.create_command_encoder(
+            &wgpu::CommandEncoderDescriptor { label: Some("Frame Encoder") }
+        ));
+        record(encoder);
+    }
+
+    /// Take the encoder of the next frame, holding any commands recorded by `encode`.
+    ///   The frame should be recorded into it and then submitted.
+    pub fn take_frame_encoder(&self) -> wgpu::CommandEncoder {
+        self.frame_index.set(self.frame_index.get() + 1);
+        return self.frame_encoder.borrow_mut().take().unwrap_or_else(|| self.device.create_command_encoder(
+            &wgpu::CommandEncoderDescriptor { label: Some("Frame Encoder") }
+        ))
+    }
+
+    /// Submit the commands recorded by `encode`, if any, without waiting for the next frame
+    ///   (e.g. when no frame will be drawn).
+    pub fn flush(&self) {
+        let encoder = self.frame_encoder.borrow_mut().take();
+        if let Some(encoder) = encoder {
+            self.frame_index.set(self.frame_index.get() + 1);
+            self.submit(&[encoder.finish()]);
+        }
+    }
+
+    /// Get the number of submissions made to the queue, e.g. to profile how fragmented the GPU work is
+    ///   (see `State::get_frame_submit_count`). Every buffer update joins the frame encoder, so a frame
+    ///   with the Light animating and the Camera moving takes 1 submission, where it used to take 4:
+    ///   one each for the Light, the Uniforms, the light box instance and the render pass.
+    pub fn get_submit_count(&self) -> u64 { self.submit_count.get() }
+
+    /// Get the index of the frame that `encode` records into. Commands recorded in earlier frames were submitted.
+    pub fn get_frame_index(&self) -> u64 { self.frame_index.get() }
+}
+
+
+/// Pick the first of the formats that a swap chain of the backend can present.
+///   wgpu 0.5 can't query a surface for its formats, so this is based on what each backend supports:
+///   every backend presents the 8-bit BGRA and RGBA formats, but WebGPU canvases have no sRGB formats.
+///
+/// # Arguments
+///
+/// * `backend` - The backend of the adapter (see `wgpu::AdapterInfo`).
+/// * `formats` - The formats that the swap chain may use, in order of preference.
+fn pick_swap_chain_format(
+    backend: wgpu::Backend,
+    formats: &[wgpu::TextureFormat],
+) -> Result<wgpu::TextureFormat, failure::Error> {
+    use wgpu::TextureFormat::*;
+    let format = formats.iter().copied().find(|format| match format {
+        Bgra8Unorm | Rgba8Unorm => true,
+        Bgra8UnormSrgb | Rgba8UnormSrgb => backend != wgpu::Backend::BrowserWebGpu,
+        _ => false,
+    }).ok_or_else(|| failure::format_err!(
+        "None of the swap chain formats {:?} can be presented by the {:?} backend", formats, backend,
+    ))?;
+
+    if format != formats[0] {
+        warn!("The {:?} backend can't present {:?}, using {:?} instead", backend, formats[0], format);
+    }
+    if format == Bgra8Unorm || format == Rgba8Unorm {
+        warn!("The swap chain format {:?} isn't sRGB, so the colors will look darker", format);
+    }
+    return Ok(format)
 }
 
+/// Resize a swap chain description, unless the size is zero along either axis (e.g. a minimized window).
+///
+/// # Returns
+///
+/// Whether the size was accepted, i.e. whether the swap chain should be recreated.
+fn resize_swap_chain_desc(swap_chain_desc: &mut SwapChainDescriptor, size: PhysicalSize) -> bool {
+    if size.width == 0 || size.height == 0 {
+        return false
+    }
+    swap_chain_desc.width = size.width;
+    swap_chain_desc.height = size.height;
+    return true
+}
 
-fn create_swap_chain_desc(size: PhysicalSize) -> SwapChainDescriptor {
+fn create_swap_chain_desc(
+    size: PhysicalSize,
+    format: wgpu::TextureFormat,
+    present_mode: wgpu::PresentMode,
+) -> SwapChainDescriptor {
     return SwapChainDescriptor {
         usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
-        format: wgpu::TextureFormat::Bgra8UnormSrgb,
+        format,
         width: size.width,
         height: size.height,
-        present_mode: wgpu::PresentMode::Fifo,
+        present_mode,
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn desc() -> SwapChainDescriptor {
+        let size = PhysicalSize::new(800, 600);
+        return create_swap_chain_desc(size, wgpu::TextureFormat::Bgra8UnormSrgb, wgpu::PresentMode::Fifo)
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn zero_sizes_leave_the_swap_chain_desc_unchanged() {
+        for &(width, height) in &[(0, 0), (0, 600), (800, 0)] {
+            let mut swap_chain_desc = desc();
+            assert!(!resize_swap_chain_desc(&mut swap_chain_desc, PhysicalSize::new(width, height)));
+            assert_eq!((swap_chain_desc.width, swap_chain_desc.height), (800, 600));
+        }
+    }
+
+    #[test]
+    fn other_sizes_resize_the_swap_chain_desc() {
+        let mut swap_chain_desc = desc();
+        assert!(resize_swap_chain_desc(&mut swap_chain_desc, PhysicalSize::new(1024, 1)));
+        assert_eq!((swap_chain_desc.width, swap_chain_desc.height), (1024, 1));
+    }
+}
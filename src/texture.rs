@@ -36,7 +36,8 @@ impl Texture {
     pub fn load<P: AsRef<Path>>(device: &Device, path: P) -> TextureResult {
         let path_copy = path.as_ref().to_path_buf();
         let label = path_copy.to_str();
-        
+        debug!("Loading texture {:?}", path_copy);
+
         let img = image::open(path)?;
         return Self::from_image(device, &img, label)
     }
@@ -89,6 +90,58 @@ impl Texture {
         return Self::from_color(device, color.into())
     }
 
+    /// Load a normal map from an image file.
+    ///   Unlike color textures, normal maps hold linear data, so they aren't stored as sRGB.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - The connection to the graphics device. Used to create the rendering resources.
+    /// * `path`   - The path to the image file.
+    ///
+    /// # Returns
+    ///
+    /// Result object that wraps a Tuple of (Texture, CommandBuffer).
+    pub fn load_normal_map<P: AsRef<Path>>(device: &Device, path: P) -> TextureResult {
+        let path_copy = path.as_ref().to_path_buf();
+        let label = path_copy.to_str();
+        debug!("Loading normal map {:?}", path_copy);
+
+        let img = image::open(path)?;
+        return Self::normal_map_from_image(device, &img, label)
+    }
+
+    /// Create a flat normal map, i.e. one that leaves the normals of a surface unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - The connection to the graphics device. Used to create the rendering resources.
+    ///
+    /// # Returns
+    ///
+    /// Result object that wraps a Tuple of (Texture, CommandBuffer).
+    pub fn flat_normal_map(device: &Device) -> TextureResult {
+        use image::GenericImage;
+        let mut img = DynamicImage::new_rgba8(1, 1);
+        // The tangent space normal (0, 0, 1), mapped from [-1, 1] into [0, 1].
+        img.put_pixel(0, 0, [128, 128, 255, 255].into());
+
+        return Self::normal_map_from_image(device, &img, Some("Flat Normal Map"))
+    }
+
+    /// Creates a normal map Texture from a DynamicImage object.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - The connection to the graphics device. Used to create the rendering resources.
+    /// * `img` - The image parsed into a DynamicImage object.
+    ///
+    /// # Returns
+    ///
+    /// Result object that wraps a Tuple of (Texture, CommandBuffer).
+    pub fn normal_map_from_image(device: &Device, img: &DynamicImage, label: Option<&str>) -> TextureResult {
+        return Self::from_image_with_format(device, img, label, wgpu::TextureFormat::Rgba8Unorm)
+    }
+
     /// Creates a Texture from a DynamicImage object.
     ///
     /// # Arguments
@@ -100,6 +153,16 @@ impl Texture {
     ///
     /// Result object that wraps a Tuple of (Texture, CommandBuffer).
     pub fn from_image(device: &Device, img: &DynamicImage, label: Option<&str>) -> TextureResult {
+        return Self::from_image_with_format(device, img, label, wgpu::TextureFormat::Rgba8UnormSrgb)
+    }
+
+    /// Creates a Texture with the given (RGBA8) format from a DynamicImage object.
+    fn from_image_with_format(
+        device: &Device,
+        img: &DynamicImage,
+        label: Option<&str>,
+        format: wgpu::TextureFormat,
+    ) -> TextureResult {
         let rgba = img.to_rgba();
         let dimensions = {
             use image::GenericImageView;
@@ -115,7 +178,7 @@ impl Texture {
                 mip_level_count: 1,
                 sample_count: 1,
                 dimension: wgpu::TextureDimension::D2,
-                format: wgpu::TextureFormat::Rgba8UnormSrgb,
+                format,
                 usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
             }
         );
@@ -160,6 +223,97 @@ impl Texture {
         Ok((Self { texture, view, sampler }, cmd_buffer))
     }
 
+    /// Load a cubemap Texture from six square image files of the same size, e.g. for a skybox.
+    ///   The view of the Texture has the `Cube` dimension.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - The connection to the graphics device. Used to create the rendering resources.
+    /// * `paths`  - The paths to the image files of the faces, in the order +X, -X, +Y, -Y, +Z, -Z.
+    ///
+    /// # Returns
+    ///
+    /// Result object that wraps a Tuple of (Texture, CommandBuffer).
+    pub fn load_cubemap<P: AsRef<Path>>(device: &Device, paths: &[P; 6]) -> TextureResult {
+        use image::GenericImageView;
+
+        let mut faces = Vec::with_capacity(6);
+        for path in paths {
+            debug!("Loading cubemap face {:?}", path.as_ref());
+            faces.push(image::open(path)?);
+        }
+        let (width, height) = faces[0].dimensions();
+        if width != height || faces.iter().any(|face| face.dimensions() != (width, height)) {
+            return Err(failure::format_err!("The faces of a cubemap must be square images of the same size"))
+        }
+
+        let size = wgpu::Extent3d { width, height, depth: 1 };
+        let format = wgpu::TextureFormat::Rgba8UnormSrgb;
+        let texture = device.create_texture(
+            &TextureDescriptor {
+                label: Some("Cubemap Texture"),
+                size,
+                array_layer_count: 6,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+            }
+        );
+
+        // Each face is copied into its own layer of the Texture.
+        let cmd_buffer = {
+            let mut encoder = device.create_command_encoder(&TEXTURE_BUFFER_COPY);
+            for (layer, face) in faces.iter().enumerate() {
+                let buffer = device.create_buffer_with_data(&face.to_rgba(), BufferUsage::COPY_SRC);
+                encoder.copy_buffer_to_texture(
+                    wgpu::BufferCopyView {
+                        buffer: &buffer,
+                        offset: 0,
+                        bytes_per_row: 4 * width,
+                        rows_per_image: height,
+                    },
+                    wgpu::TextureCopyView {
+                        texture: &texture,
+                        mip_level: 0,
+                        array_layer: layer as u32,
+                        origin: wgpu::Origin3d::ZERO,
+                    },
+                    size,
+                );
+            }
+            encoder.finish()
+        };
+
+        let view = texture.create_view(
+            &wgpu::TextureViewDescriptor {
+                format,
+                dimension: wgpu::TextureViewDimension::Cube,
+                aspect: wgpu::TextureAspect::All,
+                base_mip_level: 0,
+                level_count: 1,
+                base_array_layer: 0,
+                array_layer_count: 6,
+            }
+        );
+        let sampler = device.create_sampler(
+            &wgpu::SamplerDescriptor {
+                address_mode_u: wgpu::AddressMode::ClampToEdge,
+                address_mode_v: wgpu::AddressMode::ClampToEdge,
+                address_mode_w: wgpu::AddressMode::ClampToEdge,
+                mag_filter: wgpu::FilterMode::Linear,
+                min_filter: wgpu::FilterMode::Linear,
+                mipmap_filter: wgpu::FilterMode::Nearest,
+                lod_min_clamp: -100.0,
+                lod_max_clamp: 100.0,
+                compare: wgpu::CompareFunction::Always,
+            }
+        );
+
+        Ok((Self { texture, view, sampler }, cmd_buffer))
+    }
+
     pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float; 
     
     /// Create a depth texture for the screen.
@@ -169,12 +323,18 @@ impl Texture {
     /// * `device`          - The connection to the graphics device.
     ///                       Used to create the rendering resources.
     /// * `swap_chain_desc` - Descriptor for the swap chain.
+    /// * `sample_count`    - The number of samples per pixel. This must match the render pipelines.
     /// * `label`           - The label for the TextureDescriptor.
     ///
     /// # Returns
     ///
     /// Result object that wraps a Tuple of (Texture, CommandBuffer).
-    pub fn create_depth_texture(device: &Device, swap_chain_desc: &SwapChainDescriptor, label: &str) -> Self {
+    pub fn create_depth_texture(
+        device: &Device,
+        swap_chain_desc: &SwapChainDescriptor,
+        sample_count: u32,
+        label: &str,
+    ) -> Self {
         let size = wgpu::Extent3d { 
             width: swap_chain_desc.width,
             height: swap_chain_desc.height,
@@ -185,7 +345,7 @@ impl Texture {
             size,
             array_layer_count: 1,
             mip_level_count: 1,
-            sample_count: 1,
+            sample_count,
             dimension: wgpu::TextureDimension::D2,
             format: Self::DEPTH_FORMAT,
             usage: (
@@ -212,5 +372,31 @@ impl Texture {
         );
 
         Self { texture, view, sampler }
-    }    
+    }
+
+    /// Create a multisampled color target that is resolved into the frames of the swap chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `device`          - The connection to the graphics device.
+    ///                       Used to create the rendering resources.
+    /// * `swap_chain_desc` - Descriptor for the swap chain.
+    /// * `sample_count`    - The number of samples per pixel. This must match the render pipelines.
+    pub fn create_multisampled_framebuffer(
+        device: &Device,
+        swap_chain_desc: &SwapChainDescriptor,
+        sample_count: u32,
+    ) -> wgpu::TextureView {
+        let desc = TextureDescriptor {
+            label: Some("multisampled_framebuffer"),
+            size: wgpu::Extent3d { width: swap_chain_desc.width, height: swap_chain_desc.height, depth: 1 },
+            array_layer_count: 1,
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: swap_chain_desc.format,
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+        };
+        return device.create_texture(&desc).create_default_view()
+    }
 }
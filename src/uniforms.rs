@@ -1,6 +1,6 @@
-use cgmath::{Matrix4, Vector4};
+use cgmath::{Matrix4, Vector3, Vector4};
 use wgpu::{BufferAddress, BindGroupLayoutDescriptor, Device};
-use crate::{camera::Camera, state::StateCore};
+use crate::{camera::Camera, state::{StagingBuffers, StateCore}};
 
 /// Structure for holding the Uniform objects that are sent to the Shader programs.
 pub struct Uniforms {
@@ -14,19 +14,44 @@ pub struct Uniforms {
     // The Buffer used to send data to the GPU.
     buffer: wgpu::Buffer,
 
+    // The staging buffers that the data is written through, reused between updates.
+    staging: StagingBuffers,
+
     // The position vector of the Viewer.
     view_position: cgmath::Vector4<f32>,
 
     // The View-Projection Matrix.
     view_projection: cgmath::Matrix4<f32>,
+
+    // The RGB color that distant fragments fade into.
+    fog_color: Vector3<f32>,
+
+    // The density of the fog. Zero disables the fog.
+    fog_density: f32,
+
+    // The number of bands that the diffuse lighting is quantized into by the toon shader.
+    toon_bands: f32,
+
+    // The factor that the linear color is scaled by before tonemapping.
+    exposure: f32,
 }
 
 impl Uniforms {
 
+    pub const DEFAULT_TOON_BANDS: f32 = 3.0;
+    pub const DEFAULT_EXPOSURE: f32 = 1.0;
+
     /// Create a new Uniform object.
     pub fn new(device: &Device, view_position: Vector4<f32>, view_projection: Matrix4<f32>) -> Self {
         // Create the UniformRaw object and stor it in a Buffer.
-        let uniforms_raw = UniformsRaw { view_position, view_projection };
+        // The fog is disabled by default.
+        let fog_color = Vector3::new(0.0, 0.0, 0.0);
+        let fog_density = 0.0;
+        let toon_bands = Self::DEFAULT_TOON_BANDS;
+        let exposure = Self::DEFAULT_EXPOSURE;
+        let uniforms_raw = UniformsRaw::new(
+            view_position, view_projection, fog_color, fog_density, toon_bands, exposure
+        );
         let buffer = device.create_buffer_with_data(
             bytemuck::cast_slice(&[uniforms_raw]),
             wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
@@ -61,12 +86,61 @@ impl Uniforms {
                 }
             );
 
-        Self { bind_group, bind_group_layout, buffer, view_position, view_projection }
+        Self {
+            bind_group,
+            bind_group_layout,
+            buffer,
+            staging: StagingBuffers::new(mem_size),
+            view_position,
+            view_projection,
+            fog_color,
+            fog_density,
+            toon_bands,
+            exposure,
+        }
     }
 
     pub fn update_from_camera(&mut self, camera: &Camera, core: &StateCore) {
-        self.view_position = camera.get_view().get_position().to_homogeneous();
-        self.view_projection = camera.build_view_projection_matrix();
+        let uniforms_raw = UniformsRaw::from_camera(
+            camera, self.fog_color, self.fog_density, self.toon_bands, self.exposure
+        );
+        self.view_position = uniforms_raw.view_position;
+        self.view_projection = uniforms_raw.view_projection;
+        self.update_buffer(core);
+    }
+
+    /// Set the fog, which fades fragments into the fog color with their distance from the Viewer.
+    ///
+    /// # Arguments
+    ///
+    /// * `color`   - The RGB color that distant fragments fade into.
+    /// * `density` - The density of the fog. Zero disables the fog.
+    /// * `core`    - Structure for holding the WGPU primitives for running a windowed application.
+    pub fn set_fog(&mut self, color: Vector3<f32>, density: f32, core: &StateCore) {
+        self.fog_color = color;
+        self.fog_density = density.max(0.0);
+        self.update_buffer(core);
+    }
+
+    /// Set the number of bands that the diffuse lighting is quantized into by the toon shader.
+    ///
+    /// # Arguments
+    ///
+    /// * `bands` - The number of bands, at least 1.
+    /// * `core`  - Structure for holding the WGPU primitives for running a windowed application.
+    pub fn set_toon_bands(&mut self, bands: u32, core: &StateCore) {
+        self.toon_bands = bands.max(1) as f32;
+        self.update_buffer(core);
+    }
+
+    /// Set the factor that the linear color is scaled by before tonemapping.
+    ///
+    /// # Arguments
+    ///
+    /// * `exposure` - The exposure factor. Larger values brighten the scene.
+    /// * `core`     - Structure for holding the WGPU primitives for running a windowed application.
+    pub fn set_exposure(&mut self, exposure: f32, core: &StateCore) {
+        self.exposure = exposure.max(0.0);
         self.update_buffer(core);
     }
 
@@ -77,28 +151,25 @@ impl Uniforms {
         self.update_buffer(core);
     }
 
+    /// Get the number of staging buffers allocated by the updates of the Uniforms.
+    pub fn get_staging_allocation_count(&self) -> u64 { self.staging.get_allocation_count() }
+
     /// Update the buffer of UniformsRaw objects that is sent to the GPU.
     ///
     /// # Arguments
     ///
     /// * `core` - Structure for holding the WGPU primitives for running a windowed application.
     fn update_buffer(&mut self, core: &StateCore) {
-        // A Command encoder is used to perform Copy operations on the GPU.
-        let mut encoder = core.device.create_command_encoder(
-            &wgpu::CommandEncoderDescriptor { label: Some("update encoder") }
+        // Write the updated Buffer data through the reused staging buffers.
+        let uniforms_raw = UniformsRaw::new(
+            self.view_position,
+            self.view_projection,
+            self.fog_color,
+            self.fog_density,
+            self.toon_bands,
+            self.exposure,
         );
-
-        // Create a staging buffer with the updated Buffer data.
-        let uniforms_raw = UniformsRaw { view_position: self.view_position, view_projection: self.view_projection };
-        let staging_buffer = core.device.create_buffer_with_data(
-            bytemuck::cast_slice(&[uniforms_raw]), 
-            wgpu::BufferUsage::COPY_SRC
-        );
-
-        // Copy the data from the staging buffer into the Uniforms buffer.
-        let copy_size = std::mem::size_of_val(&uniforms_raw) as wgpu::BufferAddress;
-        encoder.copy_buffer_to_buffer(&staging_buffer, 0, &self.buffer, 0, copy_size);
-        core.submit(&[encoder.finish()]);
+        self.staging.write(core, bytemuck::cast_slice(&[uniforms_raw]), &self.buffer);
     }
 }
 
@@ -109,7 +180,77 @@ struct UniformsRaw {
     view_position: cgmath::Vector4<f32>,
     // The View-Projection Matrix.
     view_projection: cgmath::Matrix4<f32>,
+    // The RGB color of the fog.
+    fog_color: [f32; 3],
+    // The density of the fog. This fills the space left after the fog color.
+    fog_density: f32,
+    // The number of bands of the toon shader.
+    toon_bands: f32,
+    // The exposure factor applied before tonemapping.
+    exposure: f32,
+    // Pad the structure to a multiple of 16 bytes.
+    _padding: [f32; 2],
 }
 
 unsafe impl bytemuck::Pod for UniformsRaw {}
 unsafe impl bytemuck::Zeroable for UniformsRaw {}
+
+impl UniformsRaw {
+    fn new(
+        view_position: Vector4<f32>,
+        view_projection: Matrix4<f32>,
+        fog_color: Vector3<f32>,
+        fog_density: f32,
+        toon_bands: f32,
+        exposure: f32,
+    ) -> Self {
+        return UniformsRaw {
+            view_position,
+            view_projection,
+            fog_color: fog_color.into(),
+            fog_density,
+            toon_bands,
+            exposure,
+            _padding: [0.0; 2],
+        }
+    }
+
+    /// Construct the UniformsRaw object describing a Camera. The rest of the Uniforms are passed through.
+    fn from_camera(camera: &Camera, fog_color: Vector3<f32>, fog_density: f32, toon_bands: f32, exposure: f32) -> Self {
+        return Self::new(
+            camera.get_view().get_position().to_homogeneous(),
+            camera.build_view_projection_matrix(),
+            fog_color,
+            fog_density,
+            toon_bands,
+            exposure,
+        )
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use cgmath::Point3;
+    use crate::camera::{Projection, View};
+    use super::*;
+
+    #[test]
+    fn uniforms_are_padded_to_16_bytes() {
+        assert_eq!(std::mem::size_of::<UniformsRaw>() % 16, 0);
+        assert_eq!(std::mem::size_of::<UniformsRaw>(), std::mem::size_of::<f32>() * 28);
+    }
+
+    #[test]
+    fn camera_is_packed_before_the_fog() {
+        let view = View::new(Point3::new(1.0, 2.0, 3.0), Point3::new(0.0, 0.0, 0.0), Vector3::unit_y());
+        let camera = Camera::new(view, Projection::with_aspect(1.5));
+        let uniforms_raw = UniformsRaw::from_camera(&camera, Vector3::new(0.1, 0.2, 0.3), 0.4, 5.0, 0.6);
+
+        let floats: &[f32] = bytemuck::cast_slice(std::slice::from_ref(&uniforms_raw));
+        assert_eq!(floats[0..4], [1.0, 2.0, 3.0, 1.0]);
+        let view_projection: &[f32; 16] = camera.build_view_projection_matrix().as_ref();
+        assert_eq!(floats[4..20], view_projection[..]);
+        assert_eq!(floats[20..28], [0.1, 0.2, 0.3, 0.4, 5.0, 0.6, 0.0, 0.0]);
+    }
+}